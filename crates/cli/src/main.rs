@@ -1,6 +1,6 @@
 use env_logger::Env;
 use repl::Repl;
-use std::env::args;
+use std::{env::args, process::exit};
 
 mod repl;
 
@@ -30,9 +30,16 @@ fn main() {
     // TODO: Probably swap this to a cmdline flag for safety (e.g. -f or -i)
     let looks_like_file = args[1].to_lowercase().ends_with(FILE_EXT);
 
-    if looks_like_file {
+    let command_result = if looks_like_file {
         repl.eval_file(&args[1])
     } else {
         repl.eval_command(&args[1])
     };
+
+    let succeeded = repl.print_command_result(command_result);
+    repl.shutdown();
+
+    if !succeeded {
+        exit(1);
+    }
 }
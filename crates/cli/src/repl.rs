@@ -1,18 +1,35 @@
 use std::{
-    io::{stdin, stdout, Write},
+    fs::File,
+    io::{self, stdin, stdout, BufReader, Write},
     process::exit,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Sender},
+    },
+    thread,
 };
 
 use anyhow::Error;
 use cli_common::ParseError;
-use engine::engine::{Engine, StatementResult};
-use lexer::Lexer;
-use parser::Parser;
+use engine::engine::{ColumnResult, Engine, ExecuteError, ExecuteResult, ExprResult};
+use lexer::{statements::StatementReader, LexError, Lexer};
+use parser::{lint::lint, Parser};
 
 pub struct Repl {
     engine: Engine,
 }
 
+/// Set while a command is being evaluated.
+static BUSY: AtomicBool = AtomicBool::new(false);
+
+/// What the REPL waits on at the prompt.
+enum Input {
+    /// A line read from stdin. Empty at the end of input.
+    Line(io::Result<String>),
+    /// SIGINT or SIGTERM arrived.
+    Interrupted,
+}
+
 #[derive(Debug)]
 pub enum Result {
     Exit,
@@ -26,10 +43,12 @@ pub enum Result {
 #[derive(Debug)]
 pub enum CommandResult {
     _UnrecognisedCommand,
-    ParseError(Vec<ParseError>),
+    SyntaxError(Vec<LexError>, Vec<ParseError>),
     ExecuteError(Error),
     Failed(String),
-    Ok(Vec<StatementResult>),
+    Ok(ExecuteResult),
+    /// Statements ran before a later one failed without running, such as with a syntax error.
+    Stopped(ExecuteResult, Box<CommandResult>),
 }
 
 impl Repl {
@@ -41,46 +60,27 @@ impl Repl {
     }
 
     pub fn run(&self) {
+        let (sender, receiver) = mpsc::channel();
+        Repl::install_signal_handler(sender.clone());
+        Repl::read_lines(sender);
+
         loop {
-            Repl::print_prompt();
+            self.print_prompt();
 
-            let mut buf = String::new();
-            match stdin().read_line(&mut buf) {
-                Ok(_) => {
+            // Signals are queued behind any running command, so it always finishes first
+            match receiver.recv().unwrap_or(Input::Line(Ok(String::new()))) {
+                // End of input, e.g. Ctrl-D
+                Input::Line(Ok(buf)) if buf.is_empty() => {
+                    println!("Goodbye.");
+                    break;
+                }
+                Input::Line(Ok(buf)) => {
                     let command_status = self.handle_repl_command(&buf);
 
                     match command_status {
-                        Result::Ok(command_result) => match command_result {
-                            CommandResult::_UnrecognisedCommand => {
-                                println!("Error! Unrecognised command.");
-                            }
-                            CommandResult::Failed(err) => {
-                                println!("Program Error: {err}");
-                            }
-                            CommandResult::ParseError(err) => {
-                                for e in err {
-                                    let message = e.kind;
-                                    let pos = e.position;
-                                    println!("Syntax Error: {message:?} (Position {pos})");
-                                }
-                            }
-                            CommandResult::ExecuteError(err) => {
-                                println!("Execution Error: {err:?}");
-                            }
-                            CommandResult::Ok(results) => {
-                                for result in results {
-                                    let repl_output = tabled::Table::new(result.result_set.columns)
-                                        .with(tabled::settings::Disable::row(
-                                            tabled::settings::object::Rows::first(),
-                                        ))
-                                        .with(tabled::settings::Rotate::Top)
-                                        .with(tabled::settings::Rotate::Right)
-                                        .to_string();
-
-                                    println!("{repl_output}");
-                                }
-                            }
-                        },
+                        Result::Ok(command_result) => {
+                            self.print_command_result(command_result);
+                        }
                         Result::Help => {
                             println!("Sorry, you're on your own.");
                         }
@@ -96,50 +96,225 @@ impl Repl {
                             println!("Goodbye.");
                             break;
                         }
-                        Result::NoInput => {
-                            continue;
-                        }
-                    };
+                        Result::NoInput => {}
+                    }
+                }
+                Input::Line(Err(err)) => eprintln!("{err}"),
+                Input::Interrupted => {
+                    println!("\nGoodbye.");
+                    break;
                 }
-                Err(err) => eprintln!("{err}"),
             }
         }
 
+        self.shutdown();
         exit(0);
     }
 
+    /// Print a command's results, warnings and errors, in statement order.
+    /// Returns whether everything succeeded.
+    pub fn print_command_result(&self, command_result: CommandResult) -> bool {
+        match command_result {
+            CommandResult::_UnrecognisedCommand => {
+                println!("Error! Unrecognised command.");
+                false
+            }
+            CommandResult::Failed(err) => {
+                println!("Program Error: {err}");
+                false
+            }
+            CommandResult::SyntaxError(lex_errors, parse_errors) => {
+                for e in lex_errors {
+                    let text = e.text;
+                    let pos = e.position;
+                    println!("Syntax Error: Unrecognised input '{text}' (Position {pos})");
+                }
+
+                for e in parse_errors {
+                    let message = e.kind;
+                    let pos = e.position;
+                    let end = e.end;
+                    println!("Syntax Error: {message:?} (Position {pos}..{end})");
+                }
+
+                false
+            }
+            CommandResult::ExecuteError(err) => {
+                println!("Execution Error: {err:?}");
+                false
+            }
+            CommandResult::Stopped(results, failed) => {
+                self.print_command_result(CommandResult::Ok(results));
+                self.print_command_result(*failed)
+            }
+            CommandResult::Ok(results) => {
+                let mut succeeded = true;
+
+                for result in results {
+                    match result {
+                        Ok(result) => {
+                            for warning in &result.warnings {
+                                println!("Warning: {warning}");
+                            }
+
+                            let columns = self.show_nulls(result.result_set.columns);
+                            let repl_output = tabled::Table::new(columns)
+                                .with(tabled::settings::Disable::row(
+                                    tabled::settings::object::Rows::first(),
+                                ))
+                                .with(tabled::settings::Rotate::Top)
+                                .with(tabled::settings::Rotate::Right)
+                                .to_string();
+
+                            println!("{repl_output}");
+                        }
+                        Err(err) => {
+                            println!("Execution Error: {err} ({})", err.statement);
+                            succeeded = false;
+                        }
+                    }
+                }
+
+                succeeded
+            }
+        }
+    }
+
+    /// Close the engine's files. Call before the process exits.
+    pub fn shutdown(&self) {
+        if let Err(err) = self.engine.shutdown() {
+            eprintln!("Error during shutdown: {err:?}");
+        }
+    }
+
+    /// Stop the REPL on SIGINT or SIGTERM.
+    /// The REPL shuts down when it next waits for input, so a running command finishes first.
+    fn install_signal_handler(sender: Sender<Input>) {
+        let result = ctrlc::set_handler(move || {
+            if BUSY.load(Ordering::SeqCst) {
+                println!("Stopping once the current command completes.");
+            }
+
+            let _ = sender.send(Input::Interrupted);
+        });
+
+        if let Err(err) = result {
+            log::warn!("Failed to install signal handler: {err}");
+        }
+    }
+
+    /// Read stdin on its own thread, so signals can wake up the REPL while it waits at the prompt.
+    fn read_lines(sender: Sender<Input>) {
+        thread::spawn(move || loop {
+            let mut buf = String::new();
+            let result = stdin().read_line(&mut buf).map(|_| buf);
+            let at_end = matches!(&result, Ok(buf) if buf.is_empty());
+
+            if sender.send(Input::Line(result)).is_err() || at_end {
+                break;
+            }
+        });
+    }
+
     pub fn eval_command(&self, input: &str) -> CommandResult {
         let input_str = input.to_string();
 
         let lexer = Lexer::new(&input_str);
         let lex_result = lexer.lex();
 
-        let mut parser = Parser::new(lex_result.tokens, &input_str);
+        let mut parser = Parser::new(lex_result.tokens, &input_str)
+            .with_options(self.engine.session_options().parser_options);
         let parse_result = parser.parse();
 
         match parse_result {
+            Ok(_) if !lex_result.errors.is_empty() => {
+                CommandResult::SyntaxError(lex_result.errors, vec![])
+            }
             Ok(ast) => {
+                for warning in lint(&ast) {
+                    println!("Warning: {warning}");
+                }
+
                 let execute_result = self.engine.execute(&ast);
 
                 match execute_result {
-                    Ok(ok_result) => {
-                        for err in ok_result.errors {
-                            println!("{err:?}");
-                        }
-
-                        CommandResult::Ok(ok_result.results)
-                    }
+                    Ok(ok_result) => CommandResult::Ok(ok_result),
                     Err(err) => CommandResult::ExecuteError(err),
                 }
             }
-            Err(e) => CommandResult::ParseError(e),
+            Err(e) => CommandResult::SyntaxError(lex_result.errors, e),
         }
     }
 
+    /// Run a script one statement at a time, so only one statement is held in memory.
+    /// Stops at the first statement that fails. Statement numbers and error positions are counted
+    /// from the start of the file.
     pub fn eval_file(&self, file: &str) -> CommandResult {
-        match std::fs::read_to_string(file) {
-            Ok(file_content) => self.eval_command(&file_content),
-            Err(_) => CommandResult::Failed(String::from("Failed to open file.")),
+        let Ok(file) = File::open(file) else {
+            return CommandResult::Failed(String::from("Failed to open file."));
+        };
+
+        let mut results: ExecuteResult = vec![];
+
+        for statement in StatementReader::new(BufReader::new(file)) {
+            let Ok((offset, statement)) = statement else {
+                return Repl::stopped(
+                    results,
+                    CommandResult::Failed(String::from("Failed to read file.")),
+                );
+            };
+
+            match self.eval_command(&statement) {
+                CommandResult::Ok(statement_results) => {
+                    let index = results.len();
+                    let failed = statement_results.iter().any(std::result::Result::is_err);
+
+                    results.extend(statement_results.into_iter().map(|result| {
+                        result.map_err(|err| ExecuteError {
+                            index: index + err.index,
+                            ..err
+                        })
+                    }));
+
+                    if failed {
+                        break;
+                    }
+                }
+                CommandResult::SyntaxError(lex_errors, parse_errors) => {
+                    let lex_errors = lex_errors
+                        .into_iter()
+                        .map(|err| LexError {
+                            position: err.position + offset,
+                            ..err
+                        })
+                        .collect();
+                    let parse_errors = parse_errors
+                        .into_iter()
+                        .map(|err| ParseError {
+                            position: err.position + offset,
+                            end: err.end + offset,
+                            ..err
+                        })
+                        .collect();
+
+                    return Repl::stopped(
+                        results,
+                        CommandResult::SyntaxError(lex_errors, parse_errors),
+                    );
+                }
+                failed => return Repl::stopped(results, failed),
+            }
+        }
+
+        CommandResult::Ok(results)
+    }
+
+    /// Keep the results of the statements which ran before one failed.
+    fn stopped(results: ExecuteResult, failed: CommandResult) -> CommandResult {
+        if results.is_empty() {
+            failed
+        } else {
+            CommandResult::Stopped(results, Box::new(failed))
         }
     }
 
@@ -149,11 +324,14 @@ impl Repl {
     /// short-circuit for a meta command.
     fn handle_repl_command(&self, buf: &str) -> Result {
         let fmt_buf = buf.trim();
-        
+
         if Repl::is_meta_command(fmt_buf) {
             Repl::handle_meta_command(fmt_buf)
         } else {
+            BUSY.store(true, Ordering::SeqCst);
             let command_result = self.eval_command(fmt_buf);
+            BUSY.store(false, Ordering::SeqCst);
+
             Result::Ok(command_result)
         }
     }
@@ -172,8 +350,25 @@ impl Repl {
         }
     }
 
-    fn print_prompt() {
-        print!("> ");
+    /// Show NULL values as the session's `null_display` text.
+    fn show_nulls(&self, columns: Vec<ColumnResult>) -> Vec<ColumnResult> {
+        let null_display = self.engine.session_options().null_display;
+
+        columns
+            .into_iter()
+            .map(|column| match column.value {
+                ExprResult::Null => ColumnResult {
+                    value: ExprResult::String(null_display.clone()),
+                    ..column
+                },
+                _ => column,
+            })
+            .collect()
+    }
+
+    /// Show which database statements will run against.
+    fn print_prompt(&self) {
+        print!("{}> ", self.engine.current_database().name);
         stdout().flush().unwrap();
     }
 }
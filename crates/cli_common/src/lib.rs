@@ -3,21 +3,30 @@ use thiserror::Error;
 #[derive(Clone, PartialEq, Debug)]
 pub struct ParseError {
     pub kind: ParseErrorKind,
-    pub position: usize,
+    pub position: usize, // Byte offset of the start of the offending token
+    pub end: usize,      // Byte offset just past the end of the offending token
 }
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum ParseErrorKind {
     ExpectedEOF,
+    UnexpectedEOF,
     ExpectedValue,
+    ExpectedExpression,
     ExpectedStatemnt,
     ExpectedIdentifier,
+    ExpectedAlias,
     ExpectedDataType,
     ExpectedParentheses(String),
     ExpressionNotClosed,
     ExpectedKeyword(String),
-    MaximumRecursionDepthReached,
+    MaximumRecursionDepthReached(usize),
+    TooManyStatements(usize),
+    TooManySelectItems(usize),
     UnsupportedSyntax,
+    InvalidHexLiteral,
+    InvalidNumericLiteral,
+    InvalidParameter,
 }
 
 #[derive(Clone, PartialEq, Debug, Error)]
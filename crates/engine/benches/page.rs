@@ -18,5 +18,7 @@ fn write_slots(n: u16) {
         }
     }
 
-    encoder.collect();
+    if let Err(e) = encoder.collect() {
+        panic!("Error collecting page: {:?}", e);
+    }
 }
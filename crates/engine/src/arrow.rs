@@ -0,0 +1,182 @@
+use std::sync::Arc;
+
+use arrow_array::{
+    ArrayRef, BinaryArray, BooleanArray, FixedSizeBinaryArray, Float64Array, NullArray,
+    RecordBatch, StringArray, UInt32Array, UInt8Array,
+};
+use arrow_schema::{ArrowError, Field, Schema};
+
+use crate::engine::{ColumnResult, ColumnType, ExprResult, ResultSet, StatementResult};
+
+impl ResultSet {
+    /// Convert the result set into an Arrow record batch, with one field per column.
+    /// Statements that return nothing, such as CREATE TABLE, give an empty batch.
+    pub fn to_record_batch(&self) -> Result<RecordBatch, ArrowError> {
+        if self.columns.is_empty() {
+            return Ok(RecordBatch::new_empty(Arc::new(Schema::empty())));
+        }
+
+        let (fields, arrays): (Vec<Field>, Vec<ArrayRef>) = self
+            .columns
+            .iter()
+            .map(to_field_and_array)
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .unzip();
+
+        let schema = Arc::new(Schema::new(fields));
+
+        RecordBatch::try_new(schema, arrays)
+    }
+}
+
+/// Convert each statement's result set into an Arrow record batch.
+pub fn to_record_batches(results: &[StatementResult]) -> Result<Vec<RecordBatch>, ArrowError> {
+    results
+        .iter()
+        .map(|result| result.result_set.to_record_batch())
+        .collect()
+}
+
+/// The field's type comes from the column's type, so a NULL in a typed column is a null of that
+/// type rather than a Null array.
+fn to_field_and_array(column: &ColumnResult) -> Result<(Field, ArrayRef), ArrowError> {
+    let value = &column.value;
+
+    let array: ArrayRef = match column.column_type {
+        ColumnType::Int => Arc::new(UInt32Array::from(vec![match value {
+            ExprResult::Int(x) => Some(*x),
+            _ => None,
+        }])),
+        ColumnType::Byte => Arc::new(UInt8Array::from(vec![match value {
+            ExprResult::Byte(x) => Some(*x),
+            _ => None,
+        }])),
+        ColumnType::Float => Arc::new(Float64Array::from(vec![match value {
+            ExprResult::Float(x) => Some(*x),
+            _ => None,
+        }])),
+        ColumnType::Bool => Arc::new(BooleanArray::from(vec![match value {
+            ExprResult::Bool(x) => Some(*x),
+            _ => None,
+        }])),
+        ColumnType::String => Arc::new(StringArray::from(vec![match value {
+            ExprResult::String(x) => Some(x.as_str()),
+            _ => None,
+        }])),
+        ColumnType::Blob => Arc::new(BinaryArray::from(vec![match value {
+            ExprResult::Blob(x) => Some(x.as_slice()),
+            _ => None,
+        }])),
+        ColumnType::Uuid => {
+            let uuid = match value {
+                ExprResult::Uuid(x) => Some(x.as_bytes()),
+                _ => None,
+            };
+
+            Arc::new(FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+                std::iter::once(uuid),
+                16,
+            )?)
+        }
+        ColumnType::Null => Arc::new(NullArray::new(1)),
+    };
+
+    let field = Field::new(&column.name, array.data_type().clone(), column.nullable);
+
+    Ok((field, array))
+}
+
+#[cfg(test)]
+mod arrow_tests {
+    use arrow_array::{Array, BinaryArray, FixedSizeBinaryArray, StringArray, UInt32Array};
+    use arrow_schema::DataType;
+
+    use uuid::Uuid;
+
+    use crate::engine::{ColumnResult, ColumnType, ExprResult, ResultSet};
+
+    #[test]
+    fn test_result_set_to_record_batch() {
+        let result_set = ResultSet {
+            columns: vec![
+                ColumnResult::new(String::from("Id"), ExprResult::Int(7)),
+                ColumnResult::new(
+                    String::from("Name"),
+                    ExprResult::String(String::from("wack")),
+                ),
+                ColumnResult::new(String::from("Data"), ExprResult::Blob(vec![0xDE, 0xAD])),
+                ColumnResult::new(String::from("Nothing"), ExprResult::Null),
+            ],
+        };
+
+        let batch = result_set.to_record_batch().unwrap();
+        let schema = batch.schema();
+
+        assert_eq!(batch.num_rows(), 1);
+        assert_eq!(schema.field(0).name(), "Id");
+        assert_eq!(schema.field(0).data_type(), &DataType::UInt32);
+        assert_eq!(schema.field(3).data_type(), &DataType::Null);
+
+        let ids = batch.column(0).as_any().downcast_ref::<UInt32Array>();
+        let names = batch.column(1).as_any().downcast_ref::<StringArray>();
+        let data = batch.column(2).as_any().downcast_ref::<BinaryArray>();
+
+        assert_eq!(ids.unwrap().value(0), 7);
+        assert_eq!(names.unwrap().value(0), "wack");
+        assert_eq!(data.unwrap().value(0), [0xDE, 0xAD]);
+        assert_eq!(batch.column(3).logical_null_count(), 1);
+    }
+
+    #[test]
+    fn test_empty_result_set_to_record_batch() {
+        let result_set = ResultSet { columns: vec![] };
+
+        let batch = result_set.to_record_batch().unwrap();
+
+        assert_eq!(batch.num_rows(), 0);
+        assert_eq!(batch.num_columns(), 0);
+    }
+
+    #[test]
+    fn test_uuid_to_record_batch() {
+        let uuid = Uuid::from_u128(0x0102_0304_0506_4708_890A_0B0C_0D0E_0F10);
+        let result_set = ResultSet {
+            columns: vec![ColumnResult::new(
+                String::from("Id"),
+                ExprResult::Uuid(uuid),
+            )],
+        };
+
+        let batch = result_set.to_record_batch().unwrap();
+        let ids = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<FixedSizeBinaryArray>();
+
+        assert_eq!(
+            batch.schema().field(0).data_type(),
+            &DataType::FixedSizeBinary(16)
+        );
+        assert_eq!(ids.unwrap().value(0), uuid.as_bytes());
+    }
+
+    #[test]
+    fn test_typed_null_to_record_batch() {
+        let result_set = ResultSet {
+            columns: vec![ColumnResult {
+                name: String::from("Age"),
+                value: ExprResult::Null,
+                column_type: ColumnType::Int,
+                nullable: true,
+            }],
+        };
+
+        let batch = result_set.to_record_batch().unwrap();
+        let field = batch.schema().field(0).clone();
+
+        assert_eq!(field.data_type(), &DataType::UInt32);
+        assert!(field.is_nullable());
+        assert_eq!(batch.column(0).logical_null_count(), 1);
+    }
+}
@@ -0,0 +1,470 @@
+use parser::ast::{
+    CreateTableBody, Expr, Identifier, SelectExpressionBody, ServerStatement, TableSource,
+    UserStatement,
+};
+use thiserror::Error;
+
+use crate::server::MASTER_NAME;
+
+/// The max length, in bytes, of any object name. Names are stored in 128 byte buffers on disk.
+pub const MAX_NAME_LENGTH_BYTES: usize = 128;
+
+/// Column references can be qualified by their table, and the table by its database.
+pub const MAX_COLUMN_NAME_PARTS: usize = 3;
+
+/// Database names which are used by the system and can't be created by users.
+const RESERVED_DATABASE_NAMES: [&str; 1] = [MASTER_NAME];
+
+/// Settings which can be changed with SET.
+pub const SETTINGS: [&str; 6] = [
+    CACHE_SIZE_SETTING,
+    NULL_DISPLAY_SETTING,
+    TRACE_PAGE_CACHE_SETTING,
+    MAX_EXPRESSION_DEPTH_SETTING,
+    MAX_STATEMENTS_SETTING,
+    MAX_SELECT_ITEMS_SETTING,
+];
+
+/// How many pages the page cache holds.
+pub const CACHE_SIZE_SETTING: &str = "cache_size";
+
+/// The text shown in place of NULL values.
+pub const NULL_DISPLAY_SETTING: &str = "null_display";
+
+/// Whether each page cache lookup is logged.
+pub const TRACE_PAGE_CACHE_SETTING: &str = "trace_page_cache";
+
+/// How deeply expressions in later queries can nest.
+pub const MAX_EXPRESSION_DEPTH_SETTING: &str = "max_expression_depth";
+
+/// How many statements a later query can hold.
+pub const MAX_STATEMENTS_SETTING: &str = "max_statements";
+
+/// How many items a SELECT list in a later query can hold.
+pub const MAX_SELECT_ITEMS_SETTING: &str = "max_select_items";
+
+#[derive(Debug, PartialEq, Error)]
+pub enum BindError {
+    #[error("{0} name is too long: '{1}'. Names can be at most {MAX_NAME_LENGTH_BYTES} bytes.")]
+    NameTooLong(&'static str, String),
+    #[error("Database name is reserved: '{0}'")]
+    ReservedName(String),
+    #[error("Generated column '{0}' can't refer to '{1}'. Only other, non-generated columns in the table can be used.")]
+    InvalidGeneratedReference(String, String),
+    #[error("Unknown setting: '{0}'")]
+    UnknownSetting(String),
+    #[error("Table '{0}' has more than one primary key column")]
+    MultiplePrimaryKeys(String),
+    #[error("Default for column '{0}' can't refer to '{1}'. Defaults can't use other columns.")]
+    InvalidDefaultReference(String, String),
+    #[error("Column name '{0}' has too many parts. Use at most {MAX_COLUMN_NAME_PARTS}: database.table.column.")]
+    TooManyNameParts(String),
+}
+
+/// Validate the names used by a user statement before it's executed.
+pub fn bind_user_statement(statement: &UserStatement) -> Result<(), BindError> {
+    match statement {
+        UserStatement::CreateTable(body) => {
+            validate_name_length("Table", &body.table_name)?;
+
+            for column in &body.column_list {
+                validate_name_length("Column", &column.column_name)?;
+            }
+
+            validate_constraints(body)?;
+            validate_generated_columns(body)
+        }
+        UserStatement::CreateIndex(body) => {
+            if let Some(index_name) = &body.index_name {
+                validate_name_length("Index", index_name)?;
+            }
+
+            validate_name_length("Table", &body.table_name)
+        }
+        UserStatement::DropTable(body) => validate_name_length("Table", &body.table_name),
+        UserStatement::Describe(body) => validate_name_length("Table", &body.table_name),
+        UserStatement::Select(body) => validate_column_names(body),
+        UserStatement::Update | UserStatement::Insert | UserStatement::Delete => Ok(()),
+    }
+}
+
+/// Validate the names used by a server statement before it's executed.
+pub fn bind_server_statement(statement: &ServerStatement) -> Result<(), BindError> {
+    match statement {
+        ServerStatement::CreateDatabase(body) => validate_user_database_name(&body.database_name),
+        ServerStatement::DropDatabase(body) => validate_user_database_name(&body.database_name),
+        ServerStatement::Use(body) => validate_name_length("Database", &body.database_name),
+        ServerStatement::Set(body) => {
+            let is_known = SETTINGS
+                .iter()
+                .any(|setting| body.name.value.eq_ignore_ascii_case(setting));
+
+            match is_known {
+                true => Ok(()),
+                false => Err(BindError::UnknownSetting(body.name.value.clone())),
+            }
+        }
+    }
+}
+
+/// Databases created or dropped by users can't have a reserved name.
+fn validate_user_database_name(name: &Identifier) -> Result<(), BindError> {
+    validate_name_length("Database", name)?;
+
+    let is_reserved = RESERVED_DATABASE_NAMES
+        .iter()
+        .any(|reserved| name.value.eq_ignore_ascii_case(reserved));
+
+    match is_reserved {
+        true => Err(BindError::ReservedName(name.value.clone())),
+        false => Ok(()),
+    }
+}
+
+fn validate_constraints(body: &CreateTableBody) -> Result<(), BindError> {
+    let primary_keys = body.column_list.iter().filter(|c| c.primary_key).count();
+
+    if primary_keys > 1 {
+        return Err(BindError::MultiplePrimaryKeys(
+            body.table_name.value.clone(),
+        ));
+    }
+
+    for column in &body.column_list {
+        let Some(default) = &column.default else {
+            continue;
+        };
+
+        let mut references = vec![];
+        column_references(default, &mut references);
+
+        if let Some(reference) = references.into_iter().next() {
+            return Err(BindError::InvalidDefaultReference(
+                column.column_name.value.clone(),
+                reference,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Generated columns may only be computed from the table's stored, non-generated columns.
+/// This also rules out cycles, such as a column which refers to itself.
+fn validate_generated_columns(body: &CreateTableBody) -> Result<(), BindError> {
+    for column in &body.column_list {
+        let Some(generated) = &column.generated else {
+            continue;
+        };
+
+        let mut references = vec![];
+        column_references(&generated.expr, &mut references);
+
+        for reference in references {
+            let is_valid = body.column_list.iter().any(|c| {
+                c.generated.is_none() && c.column_name.value.eq_ignore_ascii_case(&reference)
+            });
+
+            if !is_valid {
+                return Err(BindError::InvalidGeneratedReference(
+                    column.column_name.value.clone(),
+                    reference,
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check no column reference in a SELECT, or the SELECTs it reads from, has too many parts.
+/// Subqueries in expressions aren't checked yet, see Expr::find.
+fn validate_column_names(body: &SelectExpressionBody) -> Result<(), BindError> {
+    let from_clause = body.from_clause.as_ref();
+
+    let exprs = body
+        .select_item_list
+        .item_list
+        .iter()
+        .map(|item| &item.expr)
+        .chain(body.where_clause.iter().map(|w| &w.expr))
+        .chain(body.group_by_clause.iter().map(|g| g.expr.as_ref()))
+        .chain(body.order_by_clause.iter().map(|o| o.expr.as_ref()))
+        .chain(
+            from_clause
+                .into_iter()
+                .flat_map(|f| f.joins.iter().map(|j| &j.on)),
+        );
+
+    for expr in exprs {
+        let too_many_parts = expr.find(&|e| {
+            matches!(e, Expr::QualifiedIdentifier(parts) if parts.len() > MAX_COLUMN_NAME_PARTS)
+        });
+
+        if let Some(Expr::QualifiedIdentifier(parts)) = too_many_parts {
+            let name = parts.iter().map(|x| x.value.as_str()).collect::<Vec<_>>();
+            return Err(BindError::TooManyNameParts(name.join(".")));
+        }
+    }
+
+    match from_clause.map(|f| &f.source) {
+        Some(TableSource::Derived(derived)) => validate_column_names(derived),
+        _ => Ok(()),
+    }
+}
+
+/// Collect the name of every column an expression refers to.
+fn column_references(expr: &Expr, references: &mut Vec<String>) {
+    match expr {
+        Expr::IsTrue(expr)
+        | Expr::IsNotTrue(expr)
+        | Expr::IsFalse(expr)
+        | Expr::IsNotFalse(expr)
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::UnaryOperator { expr, .. }
+        | Expr::Cast { expr, .. }
+        | Expr::IsInSubquery { expr, .. }
+        | Expr::IsNotInSubquery { expr, .. } => column_references(expr, references),
+        Expr::IsIn { expr, list } | Expr::IsNotIn { expr, list } => {
+            column_references(expr, references);
+            list.iter().for_each(|x| column_references(x, references));
+        }
+        Expr::Between {
+            expr,
+            lower,
+            higher,
+        }
+        | Expr::NotBetween {
+            expr,
+            lower,
+            higher,
+        } => {
+            column_references(expr, references);
+            column_references(lower, references);
+            column_references(higher, references);
+        }
+        Expr::Like { expr, pattern } | Expr::NotLike { expr, pattern } => {
+            column_references(expr, references);
+            column_references(pattern, references);
+        }
+        Expr::BinaryOperator { left, right, .. } => {
+            column_references(left, references);
+            column_references(right, references);
+        }
+        Expr::Function { args, .. } => args.iter().for_each(|x| column_references(x, references)),
+        Expr::Identifier(identifier) => references.push(identifier.value.clone()),
+        Expr::QualifiedIdentifier(identifiers) => references.push(
+            identifiers
+                .iter()
+                .map(|x| x.value.as_str())
+                .collect::<Vec<_>>()
+                .join("."),
+        ),
+        // A subquery's columns belong to its own FROM clause
+        Expr::Exists(_)
+        | Expr::Subquery(_)
+        | Expr::Value(_)
+        | Expr::Parameter(_)
+        | Expr::Wildcard => {}
+    }
+}
+
+fn validate_name_length(object: &'static str, name: &Identifier) -> Result<(), BindError> {
+    match name.value.len() > MAX_NAME_LENGTH_BYTES {
+        true => Err(BindError::NameTooLong(object, name.value.clone())),
+        false => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod binder_tests {
+    use parser::ast::{
+        BinaryOperator, ColumnDefinition, CreateDatabaseBody, CreateTableBody, DataType,
+        DropDatabaseBody, Expr, GeneratedColumn, GeneratedStorage, Identifier, Program,
+        ServerStatement, Statement, UserStatement,
+    };
+
+    use lexer::Lexer;
+    use parser::Parser;
+
+    use crate::binder::{bind_server_statement, bind_user_statement, BindError};
+
+    fn create_database(name: &str) -> ServerStatement {
+        ServerStatement::CreateDatabase(CreateDatabaseBody {
+            database_name: Identifier::from(name.to_string()),
+            if_not_exists: false,
+        })
+    }
+
+    #[test]
+    fn test_database_name_max_length() {
+        let longest = "a".repeat(128);
+        let too_long = "a".repeat(129);
+
+        assert_eq!(bind_server_statement(&create_database(&longest)), Ok(()));
+        assert_eq!(
+            bind_server_statement(&create_database(&too_long)),
+            Err(BindError::NameTooLong("Database", too_long))
+        );
+    }
+
+    #[test]
+    fn test_database_name_reserved() {
+        assert_eq!(
+            bind_server_statement(&create_database("Master")),
+            Err(BindError::ReservedName("Master".to_string()))
+        );
+
+        let drop_master = ServerStatement::DropDatabase(DropDatabaseBody {
+            database_name: Identifier::from("master".to_string()),
+            if_exists: true,
+        });
+
+        assert_eq!(
+            bind_server_statement(&drop_master),
+            Err(BindError::ReservedName("master".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_column_name_too_long() {
+        let too_long = "c".repeat(129);
+
+        let statement = UserStatement::CreateTable(CreateTableBody {
+            table_name: Identifier::from("t".to_string()),
+            if_not_exists: false,
+            column_list: vec![ColumnDefinition {
+                column_name: Identifier::from(too_long.clone()),
+                datatype: DataType::Int,
+                nullable: true,
+                primary_key: false,
+                unique: false,
+                default: None,
+                generated: None,
+            }],
+            partition_by: None,
+        });
+
+        assert_eq!(
+            bind_user_statement(&statement),
+            Err(BindError::NameTooLong("Column", too_long))
+        );
+    }
+
+    fn create_table(columns: Vec<(&str, Option<Expr>)>) -> UserStatement {
+        UserStatement::CreateTable(CreateTableBody {
+            table_name: Identifier::from("t".to_string()),
+            if_not_exists: false,
+            column_list: columns
+                .into_iter()
+                .map(|(name, expr)| ColumnDefinition {
+                    column_name: Identifier::from(name.to_string()),
+                    datatype: DataType::Int,
+                    nullable: true,
+                    primary_key: false,
+                    unique: false,
+                    default: None,
+                    generated: expr.map(|expr| GeneratedColumn {
+                        expr,
+                        storage: GeneratedStorage::Virtual,
+                    }),
+                })
+                .collect(),
+            partition_by: None,
+        })
+    }
+
+    fn sum(left: &str, right: &str) -> Option<Expr> {
+        Some(Expr::BinaryOperator {
+            left: Box::new(Expr::Identifier(Identifier::from(left.to_string()))),
+            op: BinaryOperator::Plus,
+            right: Box::new(Expr::Identifier(Identifier::from(right.to_string()))),
+        })
+    }
+
+    #[test]
+    fn test_generated_column_references() {
+        let valid = create_table(vec![("a", None), ("b", None), ("c", sum("A", "b"))]);
+        assert_eq!(bind_user_statement(&valid), Ok(()));
+
+        let unknown = create_table(vec![("a", None), ("c", sum("a", "x"))]);
+        assert_eq!(
+            bind_user_statement(&unknown),
+            Err(BindError::InvalidGeneratedReference(
+                "c".to_string(),
+                "x".to_string()
+            ))
+        );
+
+        let generated = create_table(vec![
+            ("a", None),
+            ("b", sum("a", "a")),
+            ("c", sum("a", "b")),
+        ]);
+        assert_eq!(
+            bind_user_statement(&generated),
+            Err(BindError::InvalidGeneratedReference(
+                "c".to_string(),
+                "b".to_string()
+            ))
+        );
+    }
+
+    fn bind(query: &str) -> Result<(), BindError> {
+        let query = query.to_string();
+        let program = Parser::new(Lexer::new(&query).lex().tokens, &query).parse();
+
+        let Ok(Program::Statements(statements)) = program else {
+            panic!("Failed to parse: {program:?}");
+        };
+        let Statement::User(statement) = &statements[0] else {
+            panic!("Expected a user statement");
+        };
+
+        bind_user_statement(statement)
+    }
+
+    #[test]
+    fn test_column_constraints() {
+        assert_eq!(
+            bind("CREATE TABLE t (Id INT PRIMARY KEY, Age INT NOT NULL DEFAULT 1 + 2)"),
+            Ok(())
+        );
+        assert_eq!(
+            bind("CREATE TABLE t (a INT PRIMARY KEY, b INT PRIMARY KEY)"),
+            Err(BindError::MultiplePrimaryKeys("t".to_string()))
+        );
+        assert_eq!(
+            bind("CREATE TABLE t (a INT, b INT DEFAULT a)"),
+            Err(BindError::InvalidDefaultReference(
+                "b".to_string(),
+                "a".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_column_name_parts() {
+        assert_eq!(
+            bind("SELECT sales.users.Name FROM users WHERE users.Id = 1"),
+            Ok(())
+        );
+        assert_eq!(
+            bind("SELECT 1 FROM users WHERE a.sales.users.Id = 1"),
+            Err(BindError::TooManyNameParts("a.sales.users.Id".to_string()))
+        );
+        assert_eq!(
+            bind("SELECT x FROM (SELECT a.b.c.d FROM t) d"),
+            Err(BindError::TooManyNameParts("a.b.c.d".to_string()))
+        );
+        assert_eq!(
+            bind("SELECT 1 FROM t INNER JOIN u ON t.Id = a.b.u.Id"),
+            Err(BindError::TooManyNameParts("a.b.u.Id".to_string()))
+        );
+        assert_eq!(
+            bind("SELECT 1 FROM t ORDER BY LENGTH(a.b.t.Name)"),
+            Err(BindError::TooManyNameParts("a.b.t.Name".to_string()))
+        );
+    }
+}
@@ -0,0 +1,298 @@
+use parser::ast::DataType;
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+use crate::engine::ExprResult;
+
+/// Convert a value to the given type, as in `CAST(value AS type)`.
+/// NULL stays NULL whatever the type.
+/// Returns None if the value can't be represented in that type.
+pub fn cast(value: ExprResult, data_type: &DataType) -> Option<ExprResult> {
+    if value == ExprResult::Null {
+        return Some(ExprResult::Null);
+    }
+
+    match data_type {
+        // There's no 64-bit value yet, so BIGINT shares INT's range
+        DataType::Int | DataType::BigInt => to_int(value),
+        DataType::Boolean => to_boolean(value),
+        DataType::Blob => to_blob(value),
+        DataType::Json => to_json(value),
+        DataType::Uuid => to_uuid(value),
+        DataType::Text => to_text(value),
+        DataType::Varchar(length) => to_varchar(value, *length),
+        DataType::Float => to_float(value),
+        DataType::Date => to_date(value),
+    }
+}
+
+fn to_int(value: ExprResult) -> Option<ExprResult> {
+    let int = match value {
+        ExprResult::Int(x) => x,
+        ExprResult::Byte(x) => x.into(),
+        ExprResult::Bool(b) => b.into(),
+        // Truncate towards zero, as long as the result fits
+        ExprResult::Float(x) if x.is_finite() && x > -1.0 && x < 4_294_967_296.0 => x as u32,
+        ExprResult::String(s) => s.trim().parse().ok()?,
+        _ => return None,
+    };
+
+    Some(ExprResult::Int(int))
+}
+
+fn to_float(value: ExprResult) -> Option<ExprResult> {
+    let float = match value {
+        ExprResult::Float(x) => x,
+        ExprResult::Int(x) => x.into(),
+        ExprResult::Byte(x) => x.into(),
+        ExprResult::Bool(b) => u8::from(b).into(),
+        ExprResult::String(s) => s.trim().parse().ok()?,
+        _ => return None,
+    };
+
+    Some(ExprResult::Float(float))
+}
+
+fn to_boolean(value: ExprResult) -> Option<ExprResult> {
+    let boolean = match value {
+        ExprResult::Bool(b) => b,
+        ExprResult::Int(x) => x != 0,
+        ExprResult::Byte(x) => x != 0,
+        ExprResult::Float(x) => x != 0.0,
+        ExprResult::String(s) => match s.trim().to_lowercase().as_str() {
+            "true" | "1" => true,
+            "false" | "0" => false,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    Some(ExprResult::Bool(boolean))
+}
+
+fn to_blob(value: ExprResult) -> Option<ExprResult> {
+    let bytes = match value {
+        ExprResult::Blob(b) => b,
+        ExprResult::String(s) => s.into_bytes(),
+        ExprResult::Uuid(u) => u.as_bytes().to_vec(),
+        ExprResult::Int(x) => x.to_be_bytes().to_vec(),
+        ExprResult::Byte(x) => vec![x],
+        _ => return None,
+    };
+
+    Some(ExprResult::Blob(bytes))
+}
+
+fn to_text(value: ExprResult) -> Option<ExprResult> {
+    let text = match value {
+        ExprResult::String(s) => s,
+        ExprResult::Blob(b) => String::from_utf8(b).ok()?,
+        value => value.to_string(),
+    };
+
+    Some(ExprResult::String(text))
+}
+
+/// Text longer than the limit is cut short rather than rejected.
+fn to_varchar(value: ExprResult, length: u32) -> Option<ExprResult> {
+    let ExprResult::String(text) = to_text(value)? else {
+        return None;
+    };
+
+    Some(ExprResult::String(
+        text.chars().take(length as usize).collect(),
+    ))
+}
+
+/// Dates are held as text in the form YYYY-MM-DD.
+fn to_date(value: ExprResult) -> Option<ExprResult> {
+    let ExprResult::String(s) = value else {
+        return None;
+    };
+
+    let mut parts = s.trim().splitn(3, '-');
+    let mut next = |digits: usize| -> Option<u32> {
+        let part = parts.next()?;
+        match part.len() == digits && part.bytes().all(|b| b.is_ascii_digit()) {
+            true => part.parse().ok(),
+            false => None,
+        }
+    };
+
+    let (year, month, day) = (next(4)?, next(2)?, next(2)?);
+
+    let is_leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year => 29,
+        2 => 28,
+        _ => return None,
+    };
+
+    match (1..=days_in_month).contains(&day) {
+        true => Some(ExprResult::String(format!("{year:04}-{month:02}-{day:02}"))),
+        false => None,
+    }
+}
+
+/// JSON is held as text. Strings must already be valid JSON, other values become JSON scalars.
+fn to_json(value: ExprResult) -> Option<ExprResult> {
+    let json = match value {
+        ExprResult::String(s) => serde_json::from_str::<JsonValue>(&s).ok().map(|_| s)?,
+        ExprResult::Int(x) => x.to_string(),
+        ExprResult::Byte(x) => x.to_string(),
+        ExprResult::Bool(b) => b.to_string(),
+        ExprResult::Float(x) => serde_json::Number::from_f64(x)?.to_string(),
+        ExprResult::Uuid(u) => JsonValue::String(u.to_string()).to_string(),
+        _ => return None,
+    };
+
+    Some(ExprResult::String(json))
+}
+
+fn to_uuid(value: ExprResult) -> Option<ExprResult> {
+    let uuid = match value {
+        ExprResult::Uuid(u) => u,
+        ExprResult::String(s) => Uuid::parse_str(s.trim()).ok()?,
+        ExprResult::Blob(b) => Uuid::from_slice(&b).ok()?,
+        _ => return None,
+    };
+
+    Some(ExprResult::Uuid(uuid))
+}
+
+#[cfg(test)]
+mod cast_tests {
+    use parser::ast::DataType;
+    use uuid::Uuid;
+
+    use crate::cast::cast;
+    use crate::engine::ExprResult;
+
+    fn string(s: &str) -> ExprResult {
+        ExprResult::String(s.to_string())
+    }
+
+    #[test]
+    fn test_cast_null_is_null() {
+        for data_type in [DataType::Int, DataType::Boolean, DataType::Json] {
+            assert_eq!(cast(ExprResult::Null, &data_type), Some(ExprResult::Null));
+        }
+    }
+
+    #[test]
+    fn test_cast_to_int() {
+        let cases = [
+            (string(" 42 "), Some(ExprResult::Int(42))),
+            (string("-1"), None),
+            (string("abc"), None),
+            (ExprResult::Float(3.9), Some(ExprResult::Int(3))),
+            (ExprResult::Float(-0.5), Some(ExprResult::Int(0))),
+            (ExprResult::Float(-1.0), None),
+            (ExprResult::Float(f64::NAN), None),
+            (ExprResult::Bool(true), Some(ExprResult::Int(1))),
+            (ExprResult::Blob(vec![1]), None),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(cast(value.clone(), &DataType::Int), expected, "{value:?}");
+        }
+    }
+
+    #[test]
+    fn test_cast_to_boolean() {
+        let cases = [
+            (string("TRUE"), Some(ExprResult::Bool(true))),
+            (string("0"), Some(ExprResult::Bool(false))),
+            (string("yes"), None),
+            (ExprResult::Int(2), Some(ExprResult::Bool(true))),
+            (ExprResult::Float(0.0), Some(ExprResult::Bool(false))),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(
+                cast(value.clone(), &DataType::Boolean),
+                expected,
+                "{value:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_cast_to_json() {
+        assert_eq!(
+            cast(string(r#"{"a": 1}"#), &DataType::Json),
+            Some(string(r#"{"a": 1}"#))
+        );
+        assert_eq!(cast(string("{"), &DataType::Json), None);
+        assert_eq!(cast(ExprResult::Int(7), &DataType::Json), Some(string("7")));
+        assert_eq!(
+            cast(ExprResult::Float(f64::INFINITY), &DataType::Json),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cast_uuid_round_trips_through_text_and_blob() {
+        let uuid = Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
+        let text = string("67e55044-10b1-426f-9247-bb680e5fe0c8");
+
+        assert_eq!(cast(text, &DataType::Uuid), Some(ExprResult::Uuid(uuid)));
+
+        let blob = cast(ExprResult::Uuid(uuid), &DataType::Blob).unwrap();
+        assert_eq!(blob, ExprResult::Blob(uuid.as_bytes().to_vec()));
+        assert_eq!(cast(blob, &DataType::Uuid), Some(ExprResult::Uuid(uuid)));
+
+        assert_eq!(cast(ExprResult::Blob(vec![1, 2]), &DataType::Uuid), None);
+    }
+
+    #[test]
+    fn test_cast_to_text_types() {
+        assert_eq!(
+            cast(ExprResult::Int(42), &DataType::Text),
+            Some(string("42"))
+        );
+        assert_eq!(
+            cast(string("abcdef"), &DataType::Varchar(3)),
+            Some(string("abc"))
+        );
+        assert_eq!(
+            cast(string("ab"), &DataType::Varchar(3)),
+            Some(string("ab"))
+        );
+        assert_eq!(cast(ExprResult::Blob(vec![0xFF]), &DataType::Text), None);
+    }
+
+    #[test]
+    fn test_cast_to_float() {
+        assert_eq!(
+            cast(string(" 1.5 "), &DataType::Float),
+            Some(ExprResult::Float(1.5))
+        );
+        assert_eq!(
+            cast(ExprResult::Int(3), &DataType::Float),
+            Some(ExprResult::Float(3.0))
+        );
+        assert_eq!(cast(string("x"), &DataType::Float), None);
+    }
+
+    #[test]
+    fn test_cast_to_date() {
+        let cases = [
+            ("2024-02-29", Some(string("2024-02-29"))),
+            (" 1999-12-31 ", Some(string("1999-12-31"))),
+            ("2023-02-29", None),
+            ("2024-13-01", None),
+            ("2024-1-01", None),
+            ("2024-01-01T00:00", None),
+            ("today", None),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(cast(string(value), &DataType::Date), expected, "{value}");
+        }
+
+        assert_eq!(cast(ExprResult::Int(20240101), &DataType::Date), None);
+    }
+}
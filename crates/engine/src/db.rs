@@ -34,6 +34,8 @@ pub enum ValidationError {
     FileInfoChecksumIncorrect(crate::page::ChecksumResult),
     #[error("Persistence error: {0}")]
     PersistenceError(persistence::PersistenceError),
+    #[error("Unsupported database version: {0}")]
+    UnsupportedDatabaseVersion(u8),
 }
 
 /// The constant page index of the FILE_INFO page.
@@ -120,6 +122,16 @@ impl DatabaseInfo {
             database_id,
         }
     }
+
+    /// Check the database was written by a version of the format this build can read.
+    pub fn validate_version(&self) -> Result<(), ValidationError> {
+        match self.database_version > CURRENT_DATABASE_VERSION {
+            true => Err(ValidationError::UnsupportedDatabaseVersion(
+                self.database_version,
+            )),
+            false => Ok(()),
+        }
+    }
 }
 
 pub fn create_db_data_file(db_name: &str, db_id: DatabaseId) -> Result<File> {
@@ -138,7 +150,7 @@ pub fn create_db_log_file(db_name: &str) -> Result<File> {
 pub fn validate_data_file(file: &File) -> Result<()> {
     let file_info_page = persistence::read_page(file, FILE_INFO_PAGE_INDEX)?;
 
-    let page = PageDecoder::from_bytes(&file_info_page);
+    let page = PageDecoder::from_bytes(&file_info_page)?;
     let checksum_pass = page.check();
 
     match checksum_pass.pass {
@@ -164,7 +176,7 @@ fn write_file_info(file: &std::fs::File) -> Result<()> {
     let body = FileInfo::new(FileType::Primary, created_date);
 
     page.add_slot(body)?;
-    let collected = page.collect();
+    let collected = page.collect()?;
 
     persistence::write_page(
         file,
@@ -181,7 +193,7 @@ fn write_db_info(file: &std::fs::File, db_name: &str, db_id: DatabaseId) -> Resu
     let body = DatabaseInfo::new(db_name, db_id, CURRENT_DATABASE_VERSION);
 
     page.add_slot(body)?;
-    let collected = page.collect();
+    let collected = page.collect()?;
 
     persistence::write_page(
         file,
@@ -192,9 +204,11 @@ fn write_db_info(file: &std::fs::File, db_name: &str, db_id: DatabaseId) -> Resu
 
 #[cfg(test)]
 mod master_engine_tests {
-    use db::{FileInfo, FileType};
-    use deku::DekuContainerWrite;
-    use std::time::SystemTime;
+    use db::{DatabaseInfo, FileInfo, FileType, ValidationError};
+    use deku::{DekuContainerRead, DekuContainerWrite};
+    use engine::CURRENT_DATABASE_VERSION;
+    use page::PageDecoder;
+    use std::time::{Duration, SystemTime};
 
     use crate::*;
 
@@ -277,4 +291,111 @@ mod master_engine_tests {
 
         assert_eq!(bytes, expected);
     }
+
+    // Golden-byte tests follow. See "Golden-byte tests" in docs/file_layout.md before changing them.
+
+    #[test]
+    fn test_fileinfo_golden_bytes() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(0x1234);
+        let file_info = FileInfo::new(FileType::Log, time);
+        let bytes = file_info.to_bytes().unwrap();
+
+        let expected = vec![
+            0, 1, 6, 1, // Magic string
+            1, // File Type
+            0, 0, // Sector Size
+            0x12, 0x34, // Created
+        ];
+
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn test_fileinfo_round_trip() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(0x1234);
+        let file_info = FileInfo::new(FileType::Primary, time);
+        let bytes = file_info.to_bytes().unwrap();
+
+        let (_, actual) = FileInfo::from_bytes((&bytes, 0)).unwrap();
+
+        assert_eq!(actual, file_info);
+    }
+
+    #[test]
+    fn test_databaseinfo_golden_bytes() {
+        let db_info = DatabaseInfo::new("test", 0x0102, 1);
+        let bytes = db_info.to_bytes().unwrap();
+
+        let expected = vec![
+            4, // Name length
+            b't', b'e', b's', b't', // Name
+            1,    // Version
+            0x01, 0x02, // Database ID
+        ];
+
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn test_databaseinfo_round_trip() {
+        let db_info = DatabaseInfo::new("wack", 7, CURRENT_DATABASE_VERSION);
+        let bytes = db_info.to_bytes().unwrap();
+
+        let (_, actual) = DatabaseInfo::from_bytes((&bytes, 0)).unwrap();
+
+        assert_eq!(actual, db_info);
+    }
+
+    #[test]
+    fn test_databaseinfo_version_gate() {
+        let current = DatabaseInfo::new("wack", 7, CURRENT_DATABASE_VERSION);
+        let newer = DatabaseInfo::new("wack", 7, CURRENT_DATABASE_VERSION + 1);
+
+        assert!(current.validate_version().is_ok());
+        assert!(matches!(
+            newer.validate_version(),
+            Err(ValidationError::UnsupportedDatabaseVersion(v)) if v == CURRENT_DATABASE_VERSION + 1
+        ));
+    }
+
+    /// Open the version 1 data file fixture. It's checked in, rather than generated,
+    /// so every platform has to read the same big-endian bytes the same way.
+    fn open_v1_fixture() -> std::fs::File {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/v1.wak");
+        std::fs::File::open(path).expect("Failed to open fixture.")
+    }
+
+    #[test]
+    fn test_read_v1_fixture_file_info() {
+        let file = open_v1_fixture();
+        let bytes = persistence::read_page(&file, db::FILE_INFO_PAGE_INDEX).unwrap();
+        let page = PageDecoder::from_bytes(&bytes).unwrap();
+
+        assert!(page.check().pass);
+
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(0x1234);
+        let expected = FileInfo::new(FileType::Primary, time);
+
+        assert_eq!(page.try_read::<FileInfo>(0).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_read_v1_fixture_database_info() {
+        let file = open_v1_fixture();
+        let bytes = persistence::read_page(&file, db::DATABASE_INFO_PAGE_INDEX).unwrap();
+        let page = PageDecoder::from_bytes(&bytes).unwrap();
+
+        assert!(page.check().pass);
+
+        let expected = DatabaseInfo::new("fixture", 42, 1);
+
+        assert_eq!(page.try_read::<DatabaseInfo>(0).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_validate_v1_fixture() {
+        let file = open_v1_fixture();
+
+        assert!(db::validate_data_file(&file).is_ok());
+    }
 }
@@ -1,52 +1,163 @@
-use crate::db::{self, DatabaseId, DatabaseInfo, FileType, DATABASE_INFO_PAGE_INDEX};
+use crate::db::{
+    self, DatabaseId, DatabaseInfo, FileType, DATABASE_INFO_PAGE_INDEX, FILE_INFO_PAGE_INDEX,
+};
 use crate::fm::{FileId, FileManager, IdentifiedFile};
 use crate::page::PageDecoder;
-use crate::page_cache::PageCache;
-use crate::server::{self, OpenDatabaseResult, MASTER_DB_ID};
-use crate::{persistence, vm};
+use crate::page_cache::{self, FilePageId, PageCache};
+use crate::random::Random;
+use crate::server::{self, CreateDatabaseError, OpenDatabaseResult, MASTER_DB_ID, MASTER_NAME};
+use crate::{binder, persistence, util, vm};
 
 use anyhow::Result;
-use parser::ast::{Program, ServerStatement, UserStatement};
+use parser::ast::{
+    statement_to_sql, DataType, Program, ServerStatement, Statement, StatementKind, UserStatement,
+};
+use parser::{ParserOptions, MAX_DEPTH_LIMIT};
 use std::fmt::Display;
+use std::ops::RangeInclusive;
 use std::{cell::RefCell, fs::File, rc::Rc};
 use tabled::Tabled;
+use thiserror::Error;
+use uuid::Uuid;
 
 /// System wide Consts
 pub const DATA_FILE_EXT: &str = "wak";
 pub const LOG_FILE_EXT: &str = "wal";
 pub const CURRENT_DATABASE_VERSION: u8 = 1;
 
-//pub const PAGE_CACHE_CAPACITY: usize = 131_072; // 1GB
-pub const PAGE_CACHE_CAPACITY: usize = 10; // Test
+/// Page cache bounds, in pages. The cache starts at the minimum and is sized from memory on init.
+pub const MIN_PAGE_CACHE_CAPACITY: usize = 128; // 1MB
+pub const MAX_PAGE_CACHE_CAPACITY: usize = 131_072; // 1GB
 
+/// By default, the page cache may use a quarter of the memory available at startup.
+pub const DEFAULT_CACHE_MEMORY_FRACTION: f64 = 0.25;
+
+/// The single source of truth for the page size. All page I/O goes through persistence.rs.
 pub const PAGE_SIZE_BYTES: u16 = 8192; // 2^13
-pub const PAGE_SIZE_BYTES_USIZE: usize = 8192; // 2^13
+pub const PAGE_SIZE_BYTES_USIZE: usize = PAGE_SIZE_BYTES as usize;
 
 pub const PAGE_HEADER_SIZE_BYTES: u16 = 32;
-pub const PAGE_HEADER_SIZE_BYTES_USIZE: usize = 32;
+pub const PAGE_HEADER_SIZE_BYTES_USIZE: usize = PAGE_HEADER_SIZE_BYTES as usize;
 
 pub const WACK_DIRECTORY: &str = "data"; // TODO: Hardcoded for now. See /docs/assumptions.
+/// Overrides where database files are kept. See /docs/assumptions.
+pub const DATA_DIRECTORY_ENV: &str = "WACKDB_DATA";
 
 pub struct Engine {
     pub page_cache: PageCache,
     pub file_manager: Rc<RefCell<FileManager>>,
+    /// Reject any statement that isn't read-only.
+    pub read_only: bool,
+    /// Load each database's header pages into the page cache when the engine starts.
+    pub preload_pages: bool,
+    /// The share of available memory the page cache is sized to when the engine starts.
+    pub cache_memory_fraction: f64,
+    current_database: RefCell<CurrentDatabase>,
+    session_options: RefCell<SessionOptions>,
+    /// Generates RANDOM() and UUID() values. Seeding it with RANDOM(seed) only affects this engine.
+    random: RefCell<Random>,
 }
 
-#[derive(Debug)]
-pub struct ExecuteResult {
-    pub results: Vec<StatementResult>,
-    pub errors: Vec<anyhow::Error>,
+/// The database unqualified names refer to. Starts as master, and is changed with USE.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrentDatabase {
+    pub id: DatabaseId,
+    pub name: String,
+}
+
+/// Options for the session, changed with SET. Only cache_size changes the engine itself,
+/// so it isn't kept here.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionOptions {
+    /// The text clients show in place of NULL values.
+    pub null_display: String,
+    /// Log whether each page lookup was a hit or a miss.
+    pub trace_page_cache: bool,
+    /// The limits clients parse later queries with.
+    pub parser_options: ParserOptions,
+}
+
+impl Default for SessionOptions {
+    fn default() -> Self {
+        SessionOptions {
+            null_display: String::from("NULL"),
+            trace_page_cache: false,
+            parser_options: ParserOptions::default(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Error)]
+pub enum EngineError {
+    #[error("Can't run a {0} statement while the engine is read-only")]
+    ReadOnly(StatementKind),
+    #[error("Invalid value for {0}: {1}")]
+    InvalidSetting(String, String),
+    #[error("Database doesn't exist: '{0}'")]
+    UnknownDatabase(String),
+    #[error("Table doesn't exist: '{0}'")]
+    UnknownTable(String),
+    #[error("{0} isn't supported yet")]
+    UnsupportedStatement(&'static str),
+}
+
+/// Something a statement skipped instead of failing, so the user should be told about it.
+#[derive(Debug, PartialEq, Clone)]
+pub enum EngineWarning {
+    /// CREATE ... IF NOT EXISTS found the object already there.
+    AlreadyExists(&'static str, String),
+    /// DROP ... IF EXISTS found nothing to drop.
+    DoesNotExist(&'static str, String),
+}
+
+impl Display for EngineWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EngineWarning::AlreadyExists(object, name) => {
+                write!(f, "{object} already exists, so wasn't created: '{name}'")
+            }
+            EngineWarning::DoesNotExist(object, name) => {
+                write!(f, "{object} doesn't exist, so wasn't dropped: '{name}'")
+            }
+        }
+    }
+}
+
+/// The outcome of each statement in a program, in the order they ran.
+pub type ExecuteResult = Vec<std::result::Result<StatementResult, ExecuteError>>;
+
+/// A statement which failed, and where it was in the program.
+#[derive(Debug, Error)]
+#[error("Statement {} failed: {error}", .index + 1)]
+pub struct ExecuteError {
+    /// Position of the statement in the program, counting from 0.
+    pub index: usize,
+    /// The statement, written back out as SQL.
+    pub statement: String,
+    pub error: anyhow::Error,
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct StatementResult {
     pub result_set: ResultSet,
+    pub warnings: Vec<EngineWarning>,
 }
 
 impl Default for StatementResult {
     fn default() -> Self {
         StatementResult {
             result_set: ResultSet { columns: vec![] },
+            warnings: vec![],
+        }
+    }
+}
+
+impl StatementResult {
+    /// A result with nothing to return but a warning.
+    pub fn warning(warning: EngineWarning) -> Self {
+        StatementResult {
+            warnings: vec![warning],
+            ..StatementResult::default()
         }
     }
 }
@@ -60,14 +171,79 @@ pub struct ResultSet {
 pub struct ColumnResult {
     pub name: String,
     pub value: ExprResult,
+    #[tabled(skip)]
+    pub column_type: ColumnType,
+    /// Whether the column can hold NULL.
+    #[tabled(skip)]
+    pub nullable: bool,
+}
+
+impl ColumnResult {
+    /// A column whose type is taken from its value.
+    pub fn new(name: String, value: ExprResult) -> Self {
+        ColumnResult {
+            name,
+            column_type: ColumnType::of(&value),
+            nullable: value == ExprResult::Null,
+            value,
+        }
+    }
+}
+
+/// The type of a result column, so clients can decode its values without inspecting them.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ColumnType {
+    Int,
+    Byte,
+    Float,
+    Bool,
+    String,
+    Blob,
+    Uuid,
+    /// Nothing is known about the column except that it's always NULL.
+    Null,
+}
+
+impl ColumnType {
+    pub fn of(value: &ExprResult) -> Self {
+        match value {
+            ExprResult::Int(_) => ColumnType::Int,
+            ExprResult::Byte(_) => ColumnType::Byte,
+            ExprResult::Float(_) => ColumnType::Float,
+            ExprResult::Bool(_) => ColumnType::Bool,
+            ExprResult::String(_) => ColumnType::String,
+            ExprResult::Blob(_) => ColumnType::Blob,
+            ExprResult::Uuid(_) => ColumnType::Uuid,
+            ExprResult::Null => ColumnType::Null,
+        }
+    }
+}
+
+impl From<&DataType> for ColumnType {
+    /// How values of a SQL type are returned. JSON and dates are held as text.
+    fn from(data_type: &DataType) -> Self {
+        match data_type {
+            DataType::Int | DataType::BigInt => ColumnType::Int,
+            DataType::Boolean => ColumnType::Bool,
+            DataType::Blob => ColumnType::Blob,
+            DataType::Uuid => ColumnType::Uuid,
+            DataType::Float => ColumnType::Float,
+            DataType::Json | DataType::Varchar(_) | DataType::Text | DataType::Date => {
+                ColumnType::String
+            }
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum ExprResult {
     Int(u32),
     Byte(u8),
+    Float(f64),
     Bool(bool),
     String(String),
+    Blob(Vec<u8>),
+    Uuid(Uuid),
     Null,
 }
 
@@ -76,8 +252,14 @@ impl Display for ExprResult {
         match self {
             ExprResult::Int(x) => write!(f, "{}", x),
             ExprResult::Byte(x) => write!(f, "{}", x),
+            ExprResult::Float(x) => write!(f, "{}", x),
             ExprResult::Bool(x) => write!(f, "{}", x),
             ExprResult::String(x) => write!(f, "{}", x),
+            ExprResult::Blob(x) => {
+                write!(f, "0x")?;
+                x.iter().try_for_each(|b| write!(f, "{:02X}", b))
+            }
+            ExprResult::Uuid(x) => write!(f, "{}", x),
             ExprResult::Null => write!(f, "NULL"),
         }
     }
@@ -97,11 +279,20 @@ impl Default for Engine {
 impl Engine {
     pub fn new() -> Self {
         let file_manager = Rc::new(RefCell::new(FileManager::new()));
-        let page_cache = PageCache::new(PAGE_CACHE_CAPACITY, Rc::clone(&file_manager));
+        let page_cache = PageCache::new(MIN_PAGE_CACHE_CAPACITY, Rc::clone(&file_manager));
 
         Engine {
             page_cache,
             file_manager,
+            read_only: false,
+            preload_pages: true,
+            cache_memory_fraction: DEFAULT_CACHE_MEMORY_FRACTION,
+            current_database: RefCell::new(CurrentDatabase {
+                id: MASTER_DB_ID,
+                name: MASTER_NAME.to_string(),
+            }),
+            session_options: RefCell::new(SessionOptions::default()),
+            random: RefCell::new(Random::from_time()),
         }
     }
 
@@ -133,29 +324,80 @@ impl Engine {
         }
 
         self.validate_files();
+
+        let capacity =
+            page_cache::capacity_for_memory(util::available_memory(), self.cache_memory_fraction);
+        self.page_cache.resize(capacity);
+        log::info!("Page cache holds {capacity} pages.");
+
+        if self.preload_pages {
+            self.warm_page_cache();
+        }
+    }
+
+    /// Warm the page cache with the pages every database is read through.
+    fn warm_page_cache(&self) {
+        let ids: Vec<FilePageId> = self
+            .file_manager
+            .borrow()
+            .get_all()
+            .filter(|file| file.id.ty == FileType::Primary)
+            .flat_map(|file| {
+                [FILE_INFO_PAGE_INDEX, DATABASE_INFO_PAGE_INDEX]
+                    .map(|page_index| FilePageId::new(file.id.id, page_index))
+            })
+            .collect();
+
+        let loaded = self.page_cache.preload(&ids);
+        log::info!("Preloaded {loaded} of {} pages.", ids.len());
+    }
+
+    /// Flush and close all database files. The engine can't be used after this.
+    /// Pages with changes that aren't in the log yet can't be written without breaking the
+    /// write-ahead rule, so they're logged and left for recovery.
+    pub fn shutdown(&self) -> Result<()> {
+        log::info!("Shutting down.");
+        let flushed = self.page_cache.flush_logged_pages();
+
+        for page in self.page_cache.checkpoint().dirty_pages {
+            log::warn!(
+                "Page {}:{} has changes from LSN {} which aren't in the log, so it wasn't written.",
+                page.db_id,
+                page.page_index,
+                page.rec_lsn
+            );
+        }
+
+        // Close the files even if a page couldn't be written
+        self.file_manager.borrow_mut().close_all()?;
+
+        flushed
     }
 
     pub fn execute(&self, prog: &Program) -> Result<ExecuteResult> {
         let mut results = vec![];
-        let mut errors = vec![];
 
         match prog {
             Program::Statements(statements) => {
                 // TODO: We're looping through distinct statements, which if we supported transactions would need some care here.
-                for statement in statements {
+                for (index, statement) in statements.iter().enumerate() {
                     let result = match statement {
-                        parser::ast::Statement::User(user_statement) => {
+                        _ if self.read_only && statement.kind() != StatementKind::ReadOnly => {
+                            Err(EngineError::ReadOnly(statement.kind()).into())
+                        }
+                        Statement::User(user_statement) => {
                             self.execute_user_statement(user_statement)
                         }
-                        parser::ast::Statement::Server(server_statement) => {
+                        Statement::Server(server_statement) => {
                             self.execute_server_statement(server_statement)
                         }
                     };
 
-                    match result {
-                        Ok(statement_result) => results.push(statement_result),
-                        Err(statement_error) => errors.push(statement_error),
-                    }
+                    results.push(result.map_err(|error| ExecuteError {
+                        index,
+                        statement: statement_to_sql(statement),
+                        error,
+                    }));
                 }
             }
             Program::Empty => {
@@ -163,16 +405,17 @@ impl Engine {
             }
         }
 
-        Ok(ExecuteResult { results, errors })
+        Ok(results)
     }
 
     /// Userland statements. For example, SELECT, INSERT, etc.
     pub fn execute_user_statement(&self, statement: &UserStatement) -> Result<StatementResult> {
-        dbg!(&statement);
+        binder::bind_user_statement(statement)?;
+
         match statement {
             UserStatement::Select(select_expression_body) => {
                 log::info!("Selecting: {:?}", select_expression_body);
-                vm::execute_user_statement(statement)
+                vm::execute_user_statement(statement, &mut self.random.borrow_mut())
             }
             UserStatement::Update => {
                 log::info!("Updating");
@@ -190,13 +433,48 @@ impl Engine {
                 log::info!("Creating Table");
                 Ok(StatementResult::default())
             }
+            // Indexes aren't stored yet, so CREATE INDEX can't succeed
+            UserStatement::CreateIndex(_) => {
+                Err(EngineError::UnsupportedStatement("CREATE INDEX").into())
+            }
+            // Tables aren't stored yet, so there's never one to drop
+            UserStatement::DropTable(body) => {
+                let name = body.table_name.value.clone();
+
+                match body.if_exists {
+                    true => Ok(StatementResult::warning(EngineWarning::DoesNotExist(
+                        "Table", name,
+                    ))),
+                    false => Err(EngineError::UnknownTable(name).into()),
+                }
+            }
+            // There's no columns catalog until tables are stored, so there's no table to describe
+            UserStatement::Describe(body) => {
+                Err(EngineError::UnknownTable(body.table_name.value.clone()).into())
+            }
         }
     }
 
     /// Serverland statements. For example, CREATE DATABASE.
     pub fn execute_server_statement(&self, statement: &ServerStatement) -> Result<StatementResult> {
+        binder::bind_server_statement(statement)?;
+
         match statement {
             ServerStatement::CreateDatabase(s) => {
+                let name = &s.database_name.value;
+
+                // Checked here as well as by the file name, as files created before names were
+                // lowercased may differ only by case
+                if self.find_database(name).is_some() {
+                    return match s.if_not_exists {
+                        true => Ok(StatementResult::warning(EngineWarning::AlreadyExists(
+                            "Database",
+                            name.clone(),
+                        ))),
+                        false => Err(CreateDatabaseError::DatabaseExists(name.clone()).into()),
+                    };
+                }
+
                 let next_id = self.next_id();
 
                 let result = server::create_user_database(s, next_id)?;
@@ -212,11 +490,116 @@ impl Engine {
                 // Revalidate all files
                 self.validate_files();
 
+                Ok(StatementResult::default())
+            }
+            ServerStatement::DropDatabase(body) => {
+                let name = &body.database_name.value;
+
+                match self.find_database(name) {
+                    Some(_) => Err(EngineError::UnsupportedStatement("DROP DATABASE").into()),
+                    None if body.if_exists => Ok(StatementResult::warning(
+                        EngineWarning::DoesNotExist("Database", name.clone()),
+                    )),
+                    None => Err(EngineError::UnknownDatabase(name.clone()).into()),
+                }
+            }
+            ServerStatement::Use(body) => {
+                let name = &body.database_name.value;
+
+                let Some(database) = self.find_database(name) else {
+                    return Err(EngineError::UnknownDatabase(name.clone()).into());
+                };
+
+                log::info!("Using database {}: {}", database.id, database.name);
+                *self.current_database.borrow_mut() = database;
+
+                Ok(StatementResult::default())
+            }
+            ServerStatement::Set(body) => {
+                let name = &body.name.value;
+                let value = vm::evaluate_constant_expr(&body.value, &mut self.random.borrow_mut())?;
+                let invalid = || EngineError::InvalidSetting(name.clone(), value.to_string());
+                let mut options = self.session_options.borrow_mut();
+
+                // The binder has already checked the name is a known setting
+                match name.to_ascii_lowercase().as_str() {
+                    binder::CACHE_SIZE_SETTING => {
+                        let pages =
+                            int_setting(&value, MIN_PAGE_CACHE_CAPACITY..=MAX_PAGE_CACHE_CAPACITY)
+                                .ok_or_else(invalid)?;
+
+                        log::info!("Resizing the page cache to {pages} pages.");
+                        // Pages which can be written back don't need to stay cached
+                        self.page_cache.flush_logged_pages()?;
+                        self.page_cache.resize(pages);
+                    }
+                    binder::NULL_DISPLAY_SETTING => match &value {
+                        ExprResult::String(text) => options.null_display = text.clone(),
+                        _ => return Err(invalid().into()),
+                    },
+                    binder::TRACE_PAGE_CACHE_SETTING => match value {
+                        ExprResult::Bool(trace) => {
+                            options.trace_page_cache = trace;
+                            self.page_cache.set_trace(trace);
+                        }
+                        _ => return Err(invalid().into()),
+                    },
+                    binder::MAX_EXPRESSION_DEPTH_SETTING => {
+                        options.parser_options.max_depth =
+                            int_setting(&value, 1..=MAX_DEPTH_LIMIT).ok_or_else(invalid)?;
+                    }
+                    binder::MAX_STATEMENTS_SETTING => {
+                        options.parser_options.max_statements =
+                            int_setting(&value, 1..=usize::MAX).ok_or_else(invalid)?;
+                    }
+                    binder::MAX_SELECT_ITEMS_SETTING => {
+                        options.parser_options.max_select_items =
+                            int_setting(&value, 1..=usize::MAX).ok_or_else(invalid)?;
+                    }
+                    _ => return Err(invalid().into()),
+                }
+
                 Ok(StatementResult::default())
             }
         }
     }
 
+    pub fn current_database(&self) -> CurrentDatabase {
+        self.current_database.borrow().clone()
+    }
+
+    pub fn session_options(&self) -> SessionOptions {
+        self.session_options.borrow().clone()
+    }
+
+    /// Find an open database by name, ignoring case, using the name in its DatabaseInfo page.
+    fn find_database(&self, name: &str) -> Option<CurrentDatabase> {
+        let ids: Vec<DatabaseId> = self
+            .file_manager
+            .borrow()
+            .get_all()
+            .filter(|file| file.id.ty == FileType::Primary)
+            .map(|file| file.id.id)
+            .collect();
+
+        ids.into_iter().find_map(|id| {
+            let bytes = self
+                .page_cache
+                .get_page(&FilePageId::new(id, DATABASE_INFO_PAGE_INDEX))?;
+            let info = PageDecoder::from_bytes(&bytes)
+                .and_then(|page| page.try_read::<DatabaseInfo>(0))
+                .ok()?;
+            let database_name = String::from_utf8_lossy(&info.database_name);
+
+            database_name
+                .eq_ignore_ascii_case(name)
+                .then(|| CurrentDatabase {
+                    id,
+                    name: database_name.to_string(),
+                })
+        })
+    }
+
     /// For all files in self.file_manager, validate them
     fn validate_files(&self) {
         let fm = self.file_manager.borrow();
@@ -244,27 +627,23 @@ impl Engine {
         };
     }
 
-    pub fn open_user_dbs(&self) -> Result<Box<impl Iterator<Item = OpenDatabaseResult> + '_>> {
+    pub fn open_user_dbs(&self) -> Result<Vec<OpenDatabaseResult>> {
         let dbs = persistence::find_user_databases()?;
 
-        let results = dbs.map(|db| {
-            let user_db = persistence::open_db(&db);
-            let id = self.get_db_id(&user_db.dat);
-
-            if id.is_err() {
-                panic!("I have no idea");
-            }
-
-            log::info!("Opening user DB: {:?}", db);
+        dbs.iter()
+            .map(|path| {
+                let user_db = persistence::open_db_at(path)?;
+                let id = self.get_db_id(&user_db.dat)?;
 
-            OpenDatabaseResult {
-                id: id.unwrap(),
-                dat: user_db.dat,
-                log: user_db.log,
-            }
-        });
+                log::info!("Opening user DB: {:?}", path);
 
-        Ok(Box::new(results))
+                Ok(OpenDatabaseResult {
+                    id,
+                    dat: user_db.dat,
+                    log: user_db.log,
+                })
+            })
+            .collect()
     }
 
     fn next_id(&self) -> DatabaseId {
@@ -275,10 +654,544 @@ impl Engine {
         //Circumvent the page cache - can't use it until we have the db_id
         let page_bytes = persistence::read_page(file, DATABASE_INFO_PAGE_INDEX)?;
 
-        let page = PageDecoder::from_bytes(&page_bytes);
+        let page = PageDecoder::from_bytes(&page_bytes)?;
 
         let db_info = page.try_read::<DatabaseInfo>(0)?;
+        db_info.validate_version()?;
 
         Ok(db_info.database_id)
     }
 }
+
+/// The value of an integer setting, if it's an integer within `range`.
+fn int_setting(value: &ExprResult, range: RangeInclusive<usize>) -> Option<usize> {
+    match value {
+        ExprResult::Int(x) if range.contains(&(*x as usize)) => Some(*x as usize),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod engine_tests {
+    use lexer::Lexer;
+    use parser::{ast::StatementKind, Parser, ParserOptions};
+
+    use crate::db::{DatabaseInfo, FileType, DATABASE_INFO_PAGE_INDEX};
+    use crate::engine::{
+        ColumnType, CurrentDatabase, Engine, EngineError, EngineWarning, ExprResult,
+        SessionOptions, PAGE_SIZE_BYTES_USIZE,
+    };
+    use crate::fm::FileId;
+    use crate::page::{PageEncoder, PageHeader, PageType};
+    use crate::page_cache::FilePageId;
+    use crate::vm::ExecutionError;
+
+    /// Statements which parse, but which the engine can't execute yet.
+    /// These should all fail with an error rather than a panic.
+    const UNSUPPORTED_STATEMENTS: [&str; 13] = [
+        "SELECT t.a AS b FROM t",
+        "SELECT EXISTS (SELECT 1)",
+        "SELECT 1 WHERE 1 IN (SELECT 1)",
+        "SELECT *",
+        "SELECT approx_count(*)",
+        "SELECT COUNT(*)",
+        "SELECT 1 + SUM(DISTINCT 2)",
+        "SELECT 1 | 2",
+        "SELECT 1 - 2",
+        "SELECT 65536 * 65536",
+        "SELECT 1 / 0",
+        "SELECT 1 % 0",
+        "CREATE INDEX IX_Age ON TestTable (Age) INCLUDE (Id) WHERE Age > 18",
+    ];
+
+    /// Statements which the engine accepts.
+    const SUPPORTED_STATEMENTS: [&str; 6] = [
+        "SELECT 1",
+        "SELECT RANDOM(), RANDOM(1), UUID(), NEWID()",
+        "SELECT 1 + 2, 'a' = 'a'",
+        "SELECT NULL IS NOT TRUE",
+        "SELECT X'DEAD'",
+        "CREATE TABLE TestTable (Id INT, Age INT)",
+    ];
+
+    fn execute(query: &str) -> crate::engine::ExecuteResult {
+        let query = query.to_string();
+        let lex_result = Lexer::new(&query).lex();
+        let ast = Parser::new(lex_result.tokens, &query)
+            .parse()
+            .unwrap_or_else(|e| panic!("Failed to parse {query}: {e:?}"));
+
+        Engine::new().execute(&ast).expect("Failed to execute.")
+    }
+
+    #[test]
+    fn test_unsupported_statements_return_errors() {
+        for query in UNSUPPORTED_STATEMENTS {
+            let result = execute(query);
+            assert!(result[0].is_err(), "{query}");
+        }
+    }
+
+    #[test]
+    fn test_hex_and_binary_numbers_evaluate() {
+        let result = execute("SELECT 0xFF + 1, 0b1010");
+        let columns = &result[0].as_ref().unwrap().result_set.columns;
+
+        assert_eq!(columns[0].value, ExprResult::Int(256));
+        assert_eq!(columns[1].value, ExprResult::Int(10));
+    }
+
+    #[test]
+    fn test_supported_statements_execute() {
+        for query in SUPPORTED_STATEMENTS {
+            let result = execute(query);
+            assert!(result.iter().all(|r| r.is_ok()), "{query}: {result:?}");
+        }
+    }
+
+    #[test]
+    fn test_random_seed_belongs_to_its_engine() {
+        let first_value = |engine: &Engine, query: &str| {
+            let query = query.to_string();
+            let ast = Parser::new(Lexer::new(&query).lex().tokens, &query)
+                .parse()
+                .unwrap();
+            let result = engine.execute(&ast).unwrap();
+
+            result[0].as_ref().unwrap().result_set.columns[0]
+                .value
+                .clone()
+        };
+
+        let reference = Engine::new();
+        first_value(&reference, "SELECT RANDOM(42)");
+        let expected = first_value(&reference, "SELECT RANDOM()");
+
+        // Another engine on the same thread reseeding its generator doesn't change this one's
+        let seeded = Engine::new();
+        first_value(&seeded, "SELECT RANDOM(42)");
+        first_value(&Engine::new(), "SELECT RANDOM(7)");
+
+        assert_eq!(first_value(&seeded, "SELECT RANDOM()"), expected);
+    }
+
+    #[test]
+    fn test_read_only_engine_rejects_changes() {
+        let mut engine = Engine::new();
+        engine.read_only = true;
+
+        let query = String::from("SELECT 1; CREATE TABLE t (Id INT)");
+        let ast = Parser::new(Lexer::new(&query).lex().tokens, &query)
+            .parse()
+            .unwrap();
+
+        let result = engine.execute(&ast).unwrap();
+
+        assert!(result[0].is_ok());
+        assert_eq!(
+            result[1]
+                .as_ref()
+                .unwrap_err()
+                .error
+                .downcast_ref::<EngineError>(),
+            Some(&EngineError::ReadOnly(StatementKind::Ddl))
+        );
+    }
+
+    #[test]
+    fn test_set_cache_size() {
+        let engine = Engine::new();
+
+        let query = String::from(
+            "SET cache_size = 100 + 100; SET CACHE_SIZE = 0; SET cache_size = 127; SET page_size = 1",
+        );
+        let ast = Parser::new(Lexer::new(&query).lex().tokens, &query)
+            .parse()
+            .unwrap();
+
+        let result = engine.execute(&ast).unwrap();
+
+        assert_eq!(engine.page_cache.capacity(), 200);
+        assert!(result[0].is_ok());
+
+        // Outside of the page cache's bounds
+        for (index, name, value) in [(1, "CACHE_SIZE", "0"), (2, "cache_size", "127")] {
+            assert_eq!(
+                result[index]
+                    .as_ref()
+                    .unwrap_err()
+                    .error
+                    .downcast_ref::<EngineError>(),
+                Some(&EngineError::InvalidSetting(
+                    name.to_string(),
+                    value.to_string()
+                ))
+            );
+        }
+
+        assert_eq!(
+            result[3].as_ref().unwrap_err().error.to_string(),
+            "Unknown setting: 'page_size'"
+        );
+    }
+
+    #[test]
+    fn test_set_session_options() {
+        let engine = Engine::new();
+
+        let query = String::from(
+            "SET null_display = '(null)'; PRAGMA trace_page_cache = TRUE; SET Null_Display = 1; SET trace_page_cache = 'yes'; SET cache_size = 'big'",
+        );
+        let ast = Parser::new(Lexer::new(&query).lex().tokens, &query)
+            .parse()
+            .unwrap();
+
+        let result = engine.execute(&ast).unwrap();
+
+        assert!(result[0].is_ok());
+        assert!(result[1].is_ok());
+        assert_eq!(
+            engine.session_options(),
+            SessionOptions {
+                null_display: "(null)".to_string(),
+                trace_page_cache: true,
+                ..SessionOptions::default()
+            }
+        );
+
+        // Each setting checks its own value's type
+        for (index, name, value) in [
+            (2, "Null_Display", "1"),
+            (3, "trace_page_cache", "yes"),
+            (4, "cache_size", "big"),
+        ] {
+            assert_eq!(
+                result[index]
+                    .as_ref()
+                    .unwrap_err()
+                    .error
+                    .downcast_ref::<EngineError>(),
+                Some(&EngineError::InvalidSetting(
+                    name.to_string(),
+                    value.to_string()
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn test_set_parser_options() {
+        let engine = Engine::new();
+
+        let query = String::from(
+            "SET max_expression_depth = 10; SET MAX_STATEMENTS = 5; SET max_select_items = 0; SET max_expression_depth = 501",
+        );
+        let ast = Parser::new(Lexer::new(&query).lex().tokens, &query)
+            .parse()
+            .unwrap();
+
+        let result = engine.execute(&ast).unwrap();
+
+        assert!(result[0].is_ok());
+        assert!(result[1].is_ok());
+        assert_eq!(
+            engine.session_options().parser_options,
+            ParserOptions {
+                max_depth: 10,
+                max_statements: 5,
+                ..ParserOptions::default()
+            }
+        );
+
+        // Deeper expressions could overflow the stack
+        for (index, name, value) in [
+            (2, "max_select_items", "0"),
+            (3, "max_expression_depth", "501"),
+        ] {
+            assert_eq!(
+                result[index]
+                    .as_ref()
+                    .unwrap_err()
+                    .error
+                    .downcast_ref::<EngineError>(),
+                Some(&EngineError::InvalidSetting(
+                    name.to_string(),
+                    value.to_string()
+                ))
+            );
+        }
+    }
+
+    /// Add a database called Sales with ID 3, whose DatabaseInfo page is already cached.
+    /// Returns the path of its data file, for the test to remove.
+    fn add_sales_database(engine: &mut Engine, test: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("wackdb_{test}_{}.wak", std::process::id()));
+        let file = std::fs::File::create(&path).unwrap();
+        engine
+            .file_manager
+            .borrow_mut()
+            .add(FileId::new(3, FileType::Primary), file);
+
+        let mut page = PageEncoder::new(PageHeader::new(PageType::DatabaseInfo));
+        page.add_slot(DatabaseInfo::new("Sales", 3, 1)).unwrap();
+        engine.page_cache.put_page(
+            &FilePageId::new(3, DATABASE_INFO_PAGE_INDEX),
+            page.collect().unwrap(),
+        );
+
+        path
+    }
+
+    #[test]
+    fn test_shutdown_flushes_logged_pages() {
+        let mut engine = Engine::new();
+        let path = add_sales_database(&mut engine, "shutdown");
+        let id = FilePageId::new(3, DATABASE_INFO_PAGE_INDEX);
+
+        assert!(engine.page_cache.is_dirty(&id));
+        engine.shutdown().unwrap();
+
+        let written = std::fs::metadata(&path).unwrap().len();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(!engine.page_cache.is_dirty(&id));
+        assert_eq!(written, 2 * PAGE_SIZE_BYTES_USIZE as u64);
+    }
+
+    #[test]
+    fn test_use_switches_the_current_database() {
+        let mut engine = Engine::new();
+        assert_eq!(engine.current_database().name, "master");
+
+        let path = add_sales_database(&mut engine, "use");
+
+        let query = String::from("USE sales; USE nowhere");
+        let ast = Parser::new(Lexer::new(&query).lex().tokens, &query)
+            .parse()
+            .unwrap();
+        let result = engine.execute(&ast).unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            engine.current_database(),
+            CurrentDatabase {
+                id: 3,
+                name: "Sales".to_string()
+            }
+        );
+        assert!(result[0].is_ok());
+        assert_eq!(
+            result[1]
+                .as_ref()
+                .unwrap_err()
+                .error
+                .downcast_ref::<EngineError>(),
+            Some(&EngineError::UnknownDatabase("nowhere".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_results_are_in_statement_order() {
+        let result = execute("SELECT 1; SET nope = 1; SELECT 2");
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(
+            result[0].as_ref().unwrap().result_set.columns[0].value,
+            ExprResult::Int(1)
+        );
+        assert_eq!(
+            result[2].as_ref().unwrap().result_set.columns[0].value,
+            ExprResult::Int(2)
+        );
+
+        let err = result[1].as_ref().unwrap_err();
+        assert_eq!(err.index, 1);
+        assert_eq!(err.statement, "SET nope = 1");
+        assert_eq!(
+            err.to_string(),
+            "Statement 2 failed: Unknown setting: 'nope'"
+        );
+    }
+
+    #[test]
+    fn test_identifiers_resolve_against_derived_tables() {
+        let result = execute("SELECT a, x.b * 2, B FROM (SELECT 1 AS a, 2 AS b) x");
+        let values = result[0]
+            .as_ref()
+            .unwrap()
+            .result_set
+            .columns
+            .iter()
+            .map(|c| c.value.clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            values,
+            vec![ExprResult::Int(1), ExprResult::Int(4), ExprResult::Int(2)]
+        );
+
+        let cases = [
+            ("SELECT a", ExecutionError::UnknownColumn("a".to_string())),
+            (
+                "SELECT y.a FROM (SELECT 1 AS a) x",
+                ExecutionError::UnknownColumn("y.a".to_string()),
+            ),
+            (
+                "SELECT a FROM (SELECT 1 AS a, 2 AS A) x",
+                ExecutionError::AmbiguousColumn("a".to_string()),
+            ),
+        ];
+
+        for (query, expected) in cases {
+            let result = execute(query);
+            let err = result[0].as_ref().unwrap_err();
+
+            assert_eq!(
+                err.error.downcast_ref::<ExecutionError>(),
+                Some(&expected),
+                "{query}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_columns_have_types() {
+        let result = execute(
+            "SELECT CAST(NULL AS INT), 'a', x.n, NULL FROM (SELECT CAST(NULL AS TEXT) AS n) x",
+        );
+        let types = result[0]
+            .as_ref()
+            .unwrap()
+            .result_set
+            .columns
+            .iter()
+            .map(|c| (c.column_type, c.nullable))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            types,
+            vec![
+                (ColumnType::Int, true),
+                (ColumnType::String, false),
+                (ColumnType::String, true),
+                (ColumnType::Null, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_names_ignore_case() {
+        let mut engine = Engine::new();
+        let path = add_sales_database(&mut engine, "names_ignore_case");
+
+        let query = String::from(
+            "USE SALES; USE sales; CREATE DATABASE sAlEs; SELECT USERS.name, Users.NAME FROM (SELECT 'x' AS Name) users",
+        );
+        let ast = Parser::new(Lexer::new(&query).lex().tokens, &query)
+            .parse()
+            .unwrap();
+        let result = engine.execute(&ast).unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result[0].is_ok());
+        assert!(result[1].is_ok());
+        assert_eq!(engine.current_database().name, "Sales");
+        assert_eq!(
+            result[2].as_ref().unwrap_err().error.to_string(),
+            "Database already exists: sAlEs"
+        );
+
+        let values = result[3]
+            .as_ref()
+            .unwrap()
+            .result_set
+            .columns
+            .iter()
+            .map(|c| c.value.clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            values,
+            vec![
+                ExprResult::String("x".to_string()),
+                ExprResult::String("x".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_if_exists_options_warn_instead_of_failing() {
+        let mut engine = Engine::new();
+        let path = add_sales_database(&mut engine, "if_exists");
+
+        let query = String::from(
+            "CREATE DATABASE IF NOT EXISTS sales; DROP DATABASE IF EXISTS nowhere; DROP TABLE IF EXISTS t; DROP TABLE t; DROP DATABASE nowhere",
+        );
+        let ast = Parser::new(Lexer::new(&query).lex().tokens, &query)
+            .parse()
+            .unwrap();
+        let result = engine.execute(&ast).unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+
+        let warnings = result[..3]
+            .iter()
+            .map(|r| r.as_ref().unwrap().warnings.clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            warnings,
+            vec![
+                vec![EngineWarning::AlreadyExists(
+                    "Database",
+                    "sales".to_string()
+                )],
+                vec![EngineWarning::DoesNotExist(
+                    "Database",
+                    "nowhere".to_string()
+                )],
+                vec![EngineWarning::DoesNotExist("Table", "t".to_string())],
+            ]
+        );
+        assert_eq!(
+            result[3]
+                .as_ref()
+                .unwrap_err()
+                .error
+                .downcast_ref::<EngineError>(),
+            Some(&EngineError::UnknownTable("t".to_string()))
+        );
+        assert_eq!(
+            result[4]
+                .as_ref()
+                .unwrap_err()
+                .error
+                .downcast_ref::<EngineError>(),
+            Some(&EngineError::UnknownDatabase("nowhere".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_describe_unknown_table() {
+        let mut engine = Engine::new();
+        // DESCRIBE only reads, so it still runs
+        engine.read_only = true;
+
+        let query = String::from("DESC Users");
+        let ast = Parser::new(Lexer::new(&query).lex().tokens, &query)
+            .parse()
+            .unwrap();
+        let result = engine.execute(&ast).unwrap();
+
+        assert_eq!(
+            result[0]
+                .as_ref()
+                .unwrap_err()
+                .error
+                .downcast_ref::<EngineError>(),
+            Some(&EngineError::UnknownTable("Users".to_string()))
+        );
+    }
+}
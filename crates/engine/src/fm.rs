@@ -44,7 +44,7 @@ impl FileManager {
         self.handles.get(id)
     }
 
-    pub fn get_all(&self) -> Box<dyn Iterator<Item = IdentifiedFile> + '_> {
+    pub fn get_all(&self) -> Box<dyn Iterator<Item = IdentifiedFile<'_>> + '_> {
         Box::new(
             self.handles
                 .iter()
@@ -52,6 +52,20 @@ impl FileManager {
         )
     }
 
+    /// Flush and close every open file.
+    /// Files are closed even if one of them fails to flush; the first error is returned.
+    pub fn close_all(&mut self) -> std::io::Result<()> {
+        let mut result = Ok(());
+
+        for (_, file) in self.handles.drain() {
+            if let Err(err) = file.sync_all() {
+                result = result.and(Err(err));
+            }
+        }
+
+        result
+    }
+
     pub fn next_id(&self) -> DatabaseId {
         self.handles.keys().map(|id| id.id).max().unwrap_or(0) + 1
     }
@@ -0,0 +1,132 @@
+use serde_json::Value as JsonValue;
+
+use crate::engine::ExprResult;
+
+/// Extract a value out of a JSON document.
+/// The path is either a JSONPath-like string, such as `$.a.b[0]`,
+/// a bare object key, such as `a`, or an array index.
+/// Returns None if the document isn't valid JSON or the path doesn't exist.
+pub fn extract(document: &str, path: &ExprResult) -> Option<JsonValue> {
+    let json: JsonValue = serde_json::from_str(document).ok()?;
+
+    let pointer = match path {
+        ExprResult::String(p) if p.starts_with('$') => to_json_pointer(p)?,
+        ExprResult::String(key) => format!("/{}", escape_pointer_token(key)),
+        ExprResult::Int(index) => format!("/{index}"),
+        ExprResult::Byte(index) => format!("/{index}"),
+        _ => return None,
+    };
+
+    json.pointer(&pointer).cloned()
+}
+
+/// Convert a JSON value into the JSON text representation. Used by `->`.
+pub fn to_json_result(value: JsonValue) -> ExprResult {
+    ExprResult::String(value.to_string())
+}
+
+/// Convert a JSON value into the closest SQL value. Used by `->>`.
+/// Strings are unquoted, and objects and arrays are returned as JSON text.
+pub fn to_sql_result(value: JsonValue) -> ExprResult {
+    match value {
+        JsonValue::Null => ExprResult::Null,
+        JsonValue::Bool(b) => ExprResult::Bool(b),
+        JsonValue::Number(n) => match n.as_u64().and_then(|n| u32::try_from(n).ok()) {
+            Some(n) => ExprResult::Int(n),
+            None => ExprResult::String(n.to_string()),
+        },
+        JsonValue::String(s) => ExprResult::String(s),
+        other => ExprResult::String(other.to_string()),
+    }
+}
+
+/// Convert a path such as `$.a.b[0]` into a JSON pointer such as `/a/b/0`.
+fn to_json_pointer(path: &str) -> Option<String> {
+    let mut pointer = String::new();
+    let mut chars = path.strip_prefix('$')?.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                let mut key = String::new();
+
+                while let Some(&next) = chars.peek() {
+                    if next == '.' || next == '[' {
+                        break;
+                    }
+
+                    key.push(next);
+                    chars.next();
+                }
+
+                if key.is_empty() {
+                    return None;
+                }
+
+                pointer.push('/');
+                pointer.push_str(&escape_pointer_token(&key));
+            }
+            '[' => {
+                let index: String = chars.by_ref().take_while(|c| *c != ']').collect();
+                index.parse::<usize>().ok()?;
+
+                pointer.push('/');
+                pointer.push_str(&index);
+            }
+            _ => return None,
+        }
+    }
+
+    Some(pointer)
+}
+
+fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod json_tests {
+    use crate::engine::ExprResult;
+    use crate::json::{extract, to_json_result, to_sql_result};
+
+    const DOC: &str = r#"{"name": "wack", "tags": ["a", "b"], "meta": {"size": 3, "a/b": true}}"#;
+
+    fn path(p: &str) -> ExprResult {
+        ExprResult::String(p.to_string())
+    }
+
+    #[test]
+    fn test_extract_key() {
+        let actual = extract(DOC, &path("name")).map(to_sql_result);
+        assert_eq!(actual, Some(ExprResult::String("wack".to_string())));
+    }
+
+    #[test]
+    fn test_extract_nested_path() {
+        let actual = extract(DOC, &path("$.meta.size")).map(to_sql_result);
+        assert_eq!(actual, Some(ExprResult::Int(3)));
+    }
+
+    #[test]
+    fn test_extract_array_index() {
+        let actual = extract(DOC, &path("$.tags[1]")).map(to_json_result);
+        assert_eq!(actual, Some(ExprResult::String("\"b\"".to_string())));
+    }
+
+    #[test]
+    fn test_extract_escaped_key() {
+        let actual = extract(DOC, &path("$.meta.a/b")).map(to_sql_result);
+        assert_eq!(actual, Some(ExprResult::Bool(true)));
+    }
+
+    #[test]
+    fn test_extract_missing_path() {
+        assert_eq!(extract(DOC, &path("$.nope")), None);
+        assert_eq!(extract(DOC, &path("$.tags[x]")), None);
+    }
+
+    #[test]
+    fn test_extract_invalid_document() {
+        assert_eq!(extract("{not json", &path("name")), None);
+    }
+}
@@ -1,10 +1,17 @@
+#[cfg(feature = "arrow")]
+pub mod arrow;
+mod binder;
+mod cast;
 mod db;
 pub mod engine;
 mod fm;
+mod json;
 mod lru;
 pub mod page;
 mod page_cache;
 mod persistence;
+mod random;
 mod server;
 mod util;
 mod vm;
+mod wal;
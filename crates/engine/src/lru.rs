@@ -1,12 +1,14 @@
 use std::{
     cell::RefCell,
-    collections::{HashMap, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
 };
 
 pub struct LRUCache<K, V> {
     capacity: usize,
     map: HashMap<K, V>,
     order: RefCell<VecDeque<K>>,
+    /// Keys which are never evicted. If every item is pinned, the cache grows past its capacity.
+    pinned: HashSet<K>,
 }
 
 impl<K: std::hash::Hash + Eq + Clone, V> LRUCache<K, V> {
@@ -15,6 +17,7 @@ impl<K: std::hash::Hash + Eq + Clone, V> LRUCache<K, V> {
             capacity,
             map: HashMap::new(),
             order: RefCell::new(VecDeque::new()),
+            pinned: HashSet::new(),
         }
     }
 
@@ -30,20 +33,55 @@ impl<K: std::hash::Hash + Eq + Clone, V> LRUCache<K, V> {
         }
     }
 
-    pub fn put(&mut self, key: &K, value: V) {
-        let mut order = self.order.borrow_mut();
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
 
-        if self.map.contains_key(key) {
-            order.retain(|k| k != key);
-        } else if self.map.len() == self.capacity {
-            if let Some(old_key) = order.pop_front() {
-                self.map.remove(&old_key);
+    /// Change how many items the cache holds, evicting the least recently used items if it shrinks.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        while self.map.len() > capacity {
+            if !self.evict() {
+                break;
             }
         }
 
-        order.push_back(key.clone());
+        self.capacity = capacity;
+    }
+
+    pub fn put(&mut self, key: &K, value: V) {
+        if self.map.contains_key(key) {
+            self.order.borrow_mut().retain(|k| k != key);
+        } else if self.map.len() >= self.capacity {
+            self.evict();
+        }
+
+        self.order.borrow_mut().push_back(key.clone());
         self.map.insert(key.to_owned(), value);
     }
+
+    /// Keep an item in the cache until it's unpinned.
+    pub fn pin(&mut self, key: &K) {
+        self.pinned.insert(key.clone());
+    }
+
+    pub fn unpin(&mut self, key: &K) {
+        self.pinned.remove(key);
+    }
+
+    /// Remove the least recently used item which isn't pinned. Returns whether one was found.
+    fn evict(&mut self) -> bool {
+        let mut order = self.order.borrow_mut();
+
+        let Some(position) = order.iter().position(|k| !self.pinned.contains(k)) else {
+            return false;
+        };
+
+        if let Some(old_key) = order.remove(position) {
+            self.map.remove(&old_key);
+        }
+
+        true
+    }
 }
 
 #[cfg(test)]
@@ -83,4 +121,50 @@ mod lru_tests {
         values.sort();
         assert_eq!(values, [2, 3, 4]);
     }
+
+    #[test]
+    fn test_set_capacity_evicts_least_recently_used() {
+        let mut lru = LRUCache::<usize, usize>::new(4);
+
+        for i in 1..=4 {
+            lru.put(&i, i);
+        }
+        lru.get(&1);
+
+        lru.set_capacity(2);
+
+        assert_eq!(lru.capacity(), 2);
+        let mut keys: Vec<_> = lru.map.keys().cloned().collect();
+        keys.sort();
+        assert_eq!(keys, [1, 4]);
+
+        // Growing again doesn't evict anything
+        lru.set_capacity(3);
+        lru.put(&5, 5);
+        assert_eq!(lru.map.len(), 3);
+    }
+
+    #[test]
+    fn test_pinned_items_are_not_evicted() {
+        let mut lru = LRUCache::<usize, usize>::new(2);
+
+        lru.put(&1, 1);
+        lru.pin(&1);
+        lru.put(&2, 2);
+        lru.put(&3, 3);
+
+        let mut keys: Vec<_> = lru.map.keys().cloned().collect();
+        keys.sort();
+        assert_eq!(keys, [1, 3]);
+
+        // With every item pinned, the cache grows instead
+        lru.pin(&3);
+        lru.set_capacity(1);
+        lru.put(&4, 4);
+        assert_eq!(lru.map.len(), 3);
+
+        lru.unpin(&1);
+        lru.put(&5, 5);
+        assert_eq!(lru.get(&1), None);
+    }
 }
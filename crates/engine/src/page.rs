@@ -7,7 +7,8 @@ use crate::engine::{PAGE_HEADER_SIZE_BYTES, PAGE_SIZE_BYTES, PAGE_SIZE_BYTES_USI
 use crate::page_cache::PageBytes;
 
 /// The max, current version number for the Page Header record
-pub const CURRENT_HEADER_VERSION: u8 = 1;
+/// Version 2 added the page LSN. Version 1 pages left those bytes zeroed, so they read as LSN 0.
+pub const CURRENT_HEADER_VERSION: u8 = 2;
 
 /// The amount of bytes needed to store a slot pointer in the page.
 pub const SLOT_POINTER_SIZE: u16 = 2;
@@ -62,6 +63,10 @@ pub struct PageHeader {
 
     #[deku(bytes = 2)]
     total_allocated_bytes: u16,
+
+    /// The log sequence number of the last change made to this page.
+    #[deku(bytes = 8)]
+    lsn: u64,
 }
 
 impl PageHeader {
@@ -79,8 +84,13 @@ impl PageHeader {
             free_space_start_offset: PAGE_HEADER_SIZE_BYTES,
             free_space_end_offset: PAGE_SIZE_BYTES,
             total_allocated_bytes: PAGE_HEADER_SIZE_BYTES,
+            lsn: 0,
         }
     }
+
+    pub fn lsn(&self) -> u64 {
+        self.lsn
+    }
 }
 
 pub struct PageEncoder {
@@ -93,7 +103,6 @@ pub enum PageEncoderError {
     #[error("Not enough space for slot")]
     NotEnoughSpace,
     #[error("Failed to serialise: {0}")]
-    #[allow(dead_code)]
     FailedToSerialise(DekuError),
 }
 
@@ -111,6 +120,12 @@ impl PageEncoder {
         }
     }
 
+    /// Stamp the page with the LSN of the change being written.
+    /// LSNs only move forward, so an older LSN is ignored.
+    pub fn set_lsn(&mut self, lsn: u64) {
+        self.header.lsn = self.header.lsn.max(lsn);
+    }
+
     pub fn has_space_for(&self, len: u16) -> bool {
         // Verify if the page has space for the slot and it's pointer
         self.header.free_space >= (len + SLOT_POINTER_SIZE)
@@ -155,25 +170,18 @@ impl PageEncoder {
     /// Complete operations on the page and fetch the bytes.
     /// Computes the page hash.
     /// No other operations should be performed on the page after this function is called!
-    pub fn collect(&mut self) -> PageBytes {
-        let try_collect = self.collect_internal();
+    pub fn collect(&mut self) -> Result<PageBytes, PageEncoderError> {
+        let mut bytes = self.collect_internal()?;
 
-        match try_collect {
-            Some(mut bytes) => {
-                // Only run checksum on the body
-                let body_bytes = &bytes[PAGE_HEADER_SIZE_BYTES.into()..];
-                let body_checksum = check(body_bytes);
-                let _ = &bytes[6..8].copy_from_slice(&body_checksum);
+        // Only run checksum on the body
+        let body_bytes = &bytes[PAGE_HEADER_SIZE_BYTES.into()..];
+        let body_checksum = check(body_bytes);
+        let _ = &bytes[6..8].copy_from_slice(&body_checksum);
 
-                bytes
-            }
-            None => {
-                panic!("TODO")
-            }
-        }
+        Ok(bytes)
     }
 
-    fn collect_internal(&mut self) -> Option<PageBytes> {
+    fn collect_internal(&mut self) -> Result<PageBytes, PageEncoderError> {
         let mut full_page_vec = [0; PAGE_SIZE_BYTES_USIZE];
 
         let header_bytes = self.header.to_bytes();
@@ -211,9 +219,9 @@ impl PageEncoder {
                     self.header.free_space_end_offset = free_space_end;
                 }
 
-                Some(full_page_vec)
+                Ok(full_page_vec)
             }
-            Err(_) => None,
+            Err(err) => Err(PageEncoderError::FailedToSerialise(err)),
         }
     }
 }
@@ -235,6 +243,8 @@ pub enum PageDecoderError {
     SlotOutOfRange,
     #[error("Failed to deserialise: {0}")]
     FailedToDeserialise(DekuError),
+    #[error("Unsupported page header version: {0}")]
+    UnsupportedHeaderVersion(u8),
 }
 
 #[derive(Debug)]
@@ -247,10 +257,13 @@ pub struct ChecksumResult {
 }
 
 impl<'a> PageDecoder<'a> {
-    pub fn from_bytes(bytes: &'a PageBytes) -> Self {
+    pub fn from_bytes(bytes: &'a PageBytes) -> Result<Self, PageDecoderError> {
         let mut cursor = std::io::Cursor::new(bytes);
         let mut reader = deku::reader::Reader::new(&mut cursor);
-        let header = PageHeader::from_reader_with_ctx(&mut reader, ()).unwrap();
+        let header = PageHeader::from_reader_with_ctx(&mut reader, ())
+            .map_err(PageDecoderError::FailedToDeserialise)?;
+
+        let header = Self::upgrade_header(header)?;
 
         log::trace!("Loaded page from bytes.");
         log::trace!("|        Page Type: {:?}", header.page_type);
@@ -260,16 +273,32 @@ impl<'a> PageDecoder<'a> {
         log::trace!("|            Flags: {:?}", header.flags);
         log::trace!("|         Checksum: {:?}", header.checksum);
         log::trace!("|     Alloc. slots: {:?}", header.allocated_slot_count);
+        log::trace!("|              LSN: {:?}", header.lsn);
 
         let slot_count = header.allocated_slot_count;
 
-        PageDecoder {
+        Ok(PageDecoder {
             header,
             bytes,
             slots: Self::read_slots(slot_count, bytes),
+        })
+    }
+
+    /// Bring a header of any supported version up to the current layout.
+    /// Every version shares the same 32 bytes, so only the fields a version didn't have need fixing.
+    fn upgrade_header(header: PageHeader) -> Result<PageHeader, PageDecoderError> {
+        match header.header_version {
+            // No LSN. The bytes were meant to be zero, but don't trust whatever is there.
+            1 => Ok(PageHeader { lsn: 0, ..header }),
+            CURRENT_HEADER_VERSION => Ok(header),
+            version => Err(PageDecoderError::UnsupportedHeaderVersion(version)),
         }
     }
 
+    pub fn lsn(&self) -> u64 {
+        self.header.lsn
+    }
+
     pub fn check(&self) -> ChecksumResult {
         let body_bytes = &self.bytes[PAGE_HEADER_SIZE_BYTES.into()..];
 
@@ -354,7 +383,7 @@ mod page_encoder_tests {
     fn test_page_encoder_header_only() {
         let header = PageHeader::new(page::PageType::DatabaseInfo);
         let mut encoder = PageEncoder::new(header);
-        let bytes = encoder.collect();
+        let bytes = encoder.collect().expect("Failed to collect page.");
 
         let actual_header_bytes = &bytes[0..PAGE_HEADER_SIZE_BYTES.into()];
 
@@ -380,7 +409,7 @@ mod page_encoder_tests {
         // Ensure the body is as expected
         assert_eq!(
             body_bytes.len(),
-            (PAGE_SIZE_BYTES - PAGE_HEADER_SIZE_BYTES).into()
+            usize::from(PAGE_SIZE_BYTES - PAGE_HEADER_SIZE_BYTES)
         );
 
         // Multibyte values should be BigEndian
@@ -400,7 +429,7 @@ mod page_encoder_tests {
         ];
 
         assert_eq!(actual_header_bytes, expected_header_bytes);
-        assert_eq!(bytes.len(), PAGE_SIZE_BYTES.into());
+        assert_eq!(bytes.len(), usize::from(PAGE_SIZE_BYTES));
     }
 
     #[test]
@@ -484,3 +513,103 @@ mod page_encoder_tests {
     //     // TODO: need to be able to read slots!
     // }
 }
+
+#[cfg(test)]
+mod page_format_tests {
+    use crate::*;
+    use deku::prelude::*;
+    use page::{PageDecoder, PageDecoderError, PageEncoder, PageHeader, PageType};
+
+    // See "Golden-byte tests" in docs/file_layout.md before changing these.
+
+    #[test]
+    fn test_page_type_golden_bytes() {
+        assert_eq!(PageType::FileInfo.to_bytes().unwrap(), [0]);
+        assert_eq!(PageType::DatabaseInfo.to_bytes().unwrap(), [1]);
+    }
+
+    #[test]
+    fn test_page_header_golden_bytes() {
+        let header = PageHeader::new(PageType::DatabaseInfo);
+        let bytes = header.to_bytes().unwrap();
+
+        let expected = vec![
+            0, 0, 0, 0, // Page ID
+            2, // Header version
+            1, // Page type
+            0, 0, // Checksum
+            0, 0, // Flags
+            0, 0, // Allocated slot count
+            0x1F, 0xE0, // Free space
+            0x00, 0x20, // Free space start offset
+            0x20, 0x00, // Free space end offset
+            0x00, 0x20, // Total allocated bytes
+            0, 0, 0, 0, 0, 0, 0, 0, // LSN
+        ];
+
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn test_page_header_round_trip() {
+        let header = PageHeader::new(PageType::FileInfo);
+        let bytes = header.to_bytes().unwrap();
+
+        let (_, actual) = PageHeader::from_bytes((&bytes, 0)).unwrap();
+
+        assert_eq!(actual, header);
+    }
+
+    #[test]
+    fn test_page_lsn_round_trip() {
+        let mut encoder = PageEncoder::new(PageHeader::new(PageType::DatabaseInfo));
+        encoder.set_lsn(0x0102);
+        encoder.set_lsn(0x0101); // Older LSNs don't move the page backwards
+        let bytes = encoder.collect().unwrap();
+
+        assert_eq!(bytes[20..28], [0, 0, 0, 0, 0, 0, 0x01, 0x02]);
+        assert_eq!(PageDecoder::from_bytes(&bytes).unwrap().lsn(), 0x0102);
+    }
+
+    #[test]
+    fn test_page_decoder_reads_v1_header_as_lsn_zero() {
+        let mut bytes = PageEncoder::new(PageHeader::new(PageType::FileInfo))
+            .collect()
+            .unwrap();
+        bytes[4] = 1;
+
+        assert_eq!(PageDecoder::from_bytes(&bytes).unwrap().lsn(), 0);
+
+        // Version 1 had no LSN, so anything left in those bytes is ignored
+        bytes[27] = 0xFF;
+        assert_eq!(PageDecoder::from_bytes(&bytes).unwrap().lsn(), 0);
+    }
+
+    #[test]
+    fn test_page_decoder_rejects_header_version_zero() {
+        let header = PageHeader::new(PageType::FileInfo);
+        let mut bytes = PageEncoder::new(header).collect().unwrap();
+        bytes[4] = 0;
+
+        assert_eq!(
+            PageDecoder::from_bytes(&bytes).err(),
+            Some(PageDecoderError::UnsupportedHeaderVersion(0))
+        );
+    }
+
+    #[test]
+    fn test_page_decoder_rejects_newer_header_version() {
+        let header = PageHeader::new(PageType::FileInfo);
+        let mut bytes = PageEncoder::new(header).collect().unwrap();
+        bytes[4] = page::CURRENT_HEADER_VERSION + 1;
+
+        let actual = PageDecoder::from_bytes(&bytes);
+
+        assert_eq!(
+            actual.err(),
+            Some(PageDecoderError::UnsupportedHeaderVersion(
+                page::CURRENT_HEADER_VERSION + 1
+            ))
+        );
+    }
+}
@@ -1,14 +1,37 @@
 use crate::{
     db::FileType,
+    engine::{MAX_PAGE_CACHE_CAPACITY, MIN_PAGE_CACHE_CAPACITY, PAGE_SIZE_BYTES_USIZE},
     fm::{FileId, FileManager},
     lru::LRUCache,
+    page::PageDecoder,
     persistence,
+    wal::{Checkpoint, DirtyPage},
 };
-use std::{cell::RefCell, rc::Rc};
+use anyhow::Result;
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
+use thiserror::Error;
+
+pub type PageBytes = [u8; PAGE_SIZE_BYTES_USIZE];
 
-pub type PageBytes = [u8; 8192];
+#[derive(Debug, PartialEq, Error)]
+pub enum PageCacheError {
+    #[error("Page {page:?} has changes up to LSN {lsn}, but the log is only durable up to LSN {durable_lsn}")]
+    WriteAheadViolation {
+        page: FilePageId,
+        lsn: u64,
+        durable_lsn: u64,
+    },
+    #[error("No file is open for database {0}")]
+    FileNotOpen(u16),
+    #[error("Dirty page {0:?} isn't in the cache")]
+    DirtyPageMissing(FilePageId),
+}
 
-#[derive(Clone, Hash, Eq, PartialEq)]
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
 pub struct FilePageId {
     db_id: u16,
     page_index: u32,
@@ -25,6 +48,12 @@ pub type FilePageCache = LRUCache<FilePageId, PageBytes>;
 pub struct PageCache {
     lru_cache: Rc<RefCell<FilePageCache>>,
     file_manager: Rc<RefCell<FileManager>>,
+    /// Pages changed in the cache but not yet written back to disk, with the LSN of the oldest such change.
+    dirty_pages: RefCell<HashMap<FilePageId, u64>>,
+    /// The highest LSN known to be safely in the log on disk.
+    durable_lsn: Cell<u64>,
+    /// Log whether each page lookup was a hit or a miss.
+    trace: Cell<bool>,
 }
 
 impl PageCache {
@@ -34,14 +63,25 @@ impl PageCache {
         PageCache {
             lru_cache,
             file_manager,
+            dirty_pages: RefCell::new(HashMap::new()),
+            durable_lsn: Cell::new(0),
+            trace: Cell::new(false),
         }
     }
 
     pub fn get_page(&self, id: &FilePageId) -> Option<PageBytes> {
         if let Some(page) = self.lru_cache.borrow().get(id) {
+            if self.trace.get() {
+                log::info!("Page cache hit: {id:?}");
+            }
+
             return Some(*page);
         }
 
+        if self.trace.get() {
+            log::info!("Page cache miss: {id:?}");
+        }
+
         let fm_borrow = self.file_manager.borrow();
 
         let file = fm_borrow.get(&FileId {
@@ -71,26 +111,229 @@ impl PageCache {
         }
     }
 
+    pub fn capacity(&self) -> usize {
+        self.lru_cache.borrow().capacity()
+    }
+
+    /// Change how many pages the cache holds. Shrinking evicts the least recently used pages.
+    pub fn resize(&self, capacity: usize) {
+        self.lru_cache.borrow_mut().set_capacity(capacity);
+    }
+
+    /// Start or stop logging each page lookup.
+    pub fn set_trace(&self, trace: bool) {
+        self.trace.set(trace);
+    }
+
+    /// Read pages from disk into the cache ahead of time, so the first queries don't have to wait on them.
+    /// Pages that can't be read are skipped. Returns how many pages are now cached.
+    pub fn preload(&self, ids: &[FilePageId]) -> usize {
+        ids.iter().filter(|id| self.refresh_page(id)).count()
+    }
+
+    /// Read a page from disk, replacing the cached copy if it's stale, such as after the file
+    /// was restored. Changes which haven't been flushed are kept. Returns whether the page is cached.
+    fn refresh_page(&self, id: &FilePageId) -> bool {
+        if self.is_dirty(id) {
+            return true;
+        }
+
+        let fm_borrow = self.file_manager.borrow();
+        let disk_page = fm_borrow
+            .get(&FileId {
+                id: id.db_id,
+                ty: FileType::Primary,
+            })
+            .and_then(|file_handle| persistence::read_page(file_handle, id.page_index).ok());
+
+        let Some(disk_page) = disk_page else {
+            return self.lru_cache.borrow().get(id).is_some();
+        };
+
+        let disk_lsn = PageDecoder::from_bytes(&disk_page).map_or(0, |page| page.lsn());
+
+        if self.is_stale(id, disk_lsn) {
+            self.lru_cache.borrow_mut().put(id, disk_page);
+        }
+
+        true
+    }
+
+    /// Whether the cached copy of a page is older than the copy with `disk_lsn`.
+    /// Pages that aren't cached, or can't be decoded, are treated as stale.
+    pub fn is_stale(&self, id: &FilePageId, disk_lsn: u64) -> bool {
+        let lru = self.lru_cache.borrow();
+
+        match lru.get(id).map(PageDecoder::from_bytes) {
+            Some(Ok(page)) => page.lsn() < disk_lsn,
+            _ => true,
+        }
+    }
+
+    /// Change a page in the cache. It's written back to disk by flush_page, and stays in the
+    /// cache until then, so the change isn't lost.
     pub fn put_page(&mut self, id: &FilePageId, data: PageBytes) {
-        // TODO: This probably needs to do a lot more than just put it into the cache.
-        self.lru_cache.borrow_mut().put(id, data);
+        let mut lru = self.lru_cache.borrow_mut();
+        lru.put(id, data);
+        lru.pin(id);
+        drop(lru);
+
+        // Only the first change since the page was last flushed sets where replay has to start
+        let lsn = PageDecoder::from_bytes(&data).map_or(0, |page| page.lsn());
+        self.dirty_pages
+            .borrow_mut()
+            .entry(id.clone())
+            .or_insert(lsn);
+    }
+
+    pub fn is_dirty(&self, id: &FilePageId) -> bool {
+        self.dirty_pages.borrow().contains_key(id)
     }
+
+    /// Take a fuzzy checkpoint of the dirty page table. Nothing is flushed, so it's cheap enough
+    /// to take while pages are still changing. Pages are ordered by database, then index.
+    pub fn checkpoint(&self) -> Checkpoint {
+        let mut dirty_pages: Vec<DirtyPage> = self
+            .dirty_pages
+            .borrow()
+            .iter()
+            .map(|(id, rec_lsn)| DirtyPage {
+                db_id: id.db_id,
+                page_index: id.page_index,
+                rec_lsn: *rec_lsn,
+            })
+            .collect();
+
+        dirty_pages.sort_by_key(|page| (page.db_id, page.page_index));
+
+        Checkpoint::new(dirty_pages)
+    }
+
+    /// Record that the log is on disk up to and including `lsn`, so pages changed up to then can be flushed.
+    /// LSNs only move forward, so an older LSN is ignored.
+    pub fn set_durable_lsn(&self, lsn: u64) {
+        self.durable_lsn.set(self.durable_lsn.get().max(lsn));
+    }
+
+    /// Write back every dirty page whose changes are already in the log.
+    /// The rest stay dirty, and pinned in the cache, until the log catches up.
+    pub fn flush_logged_pages(&self) -> Result<()> {
+        let ids: Vec<FilePageId> = self.dirty_pages.borrow().keys().cloned().collect();
+
+        for id in ids {
+            let lsn = match self.lru_cache.borrow().get(&id) {
+                Some(page) => PageDecoder::from_bytes(page)?.lsn(),
+                None => return Err(PageCacheError::DirtyPageMissing(id).into()),
+            };
+
+            if lsn <= self.durable_lsn.get() {
+                self.flush_page(&id)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write a dirty page back to its file. Pages which aren't dirty are left alone.
+    /// Enforces the write-ahead rule: a page can't reach disk before the log records of its changes,
+    /// or a crash could leave changes on disk that recovery knows nothing about.
+    pub fn flush_page(&self, id: &FilePageId) -> Result<()> {
+        if !self.is_dirty(id) {
+            return Ok(());
+        }
+
+        // Dirty pages are pinned, so this only fails if something forgot to pin one
+        let Some(page) = self.lru_cache.borrow().get(id).copied() else {
+            return Err(PageCacheError::DirtyPageMissing(id.clone()).into());
+        };
+
+        let lsn = PageDecoder::from_bytes(&page)?.lsn();
+        let durable_lsn = self.durable_lsn.get();
+
+        debug_assert!(
+            lsn <= durable_lsn,
+            "Write-ahead rule broken: page {id:?} has LSN {lsn}, but the log is only durable up to LSN {durable_lsn}"
+        );
+
+        if lsn > durable_lsn {
+            return Err(PageCacheError::WriteAheadViolation {
+                page: id.clone(),
+                lsn,
+                durable_lsn,
+            }
+            .into());
+        }
+
+        let fm = self.file_manager.borrow();
+        let file = fm
+            .get(&FileId::new(id.db_id, FileType::Primary))
+            .ok_or(PageCacheError::FileNotOpen(id.db_id))?;
+
+        persistence::write_page(file, &page, id.page_index)?;
+        self.dirty_pages.borrow_mut().remove(id);
+        self.lru_cache.borrow_mut().unpin(id);
+
+        Ok(())
+    }
+}
+
+/// How many pages to cache, given the share of `available_bytes` the cache may use.
+/// Bounded by MIN_PAGE_CACHE_CAPACITY and MAX_PAGE_CACHE_CAPACITY. Unknown memory gets the minimum.
+pub fn capacity_for_memory(available_bytes: Option<u64>, fraction: f64) -> usize {
+    let Some(available_bytes) = available_bytes else {
+        return MIN_PAGE_CACHE_CAPACITY;
+    };
+
+    let pages = (available_bytes as f64 * fraction) / PAGE_SIZE_BYTES_USIZE as f64;
+
+    (pages as usize).clamp(MIN_PAGE_CACHE_CAPACITY, MAX_PAGE_CACHE_CAPACITY)
 }
 
 #[cfg(test)]
 mod page_cache_tests {
-    use std::{cell::RefCell, rc::Rc};
+    use std::{cell::RefCell, io::Write, rc::Rc};
+
+    use crate::{
+        db::FileType,
+        engine::{MAX_PAGE_CACHE_CAPACITY, MIN_PAGE_CACHE_CAPACITY, PAGE_SIZE_BYTES_USIZE},
+        fm::{FileId, FileManager},
+        page::{PageEncoder, PageHeader, PageType},
+        page_cache::FilePageId,
+        persistence,
+        wal::DirtyPage,
+    };
+
+    use super::{capacity_for_memory, PageBytes, PageCache, PageCacheError};
+
+    /// A page cache over a single, empty database file, with ID 0.
+    fn page_cache_over_temp_file(name: &str) -> (PageCache, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("wackdb_{name}_{}.wak", std::process::id()));
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .read(true)
+            .write(true)
+            .open(&path)
+            .unwrap();
 
-    use crate::{fm::FileManager, page_cache::FilePageId};
+        let fm = Rc::new(RefCell::new(FileManager::new()));
+        fm.borrow_mut().add(FileId::new(0, FileType::Primary), file);
+
+        (PageCache::new(3, fm), path)
+    }
 
-    use super::{PageBytes, PageCache};
+    fn page_with_lsn(lsn: u64) -> PageBytes {
+        let mut page = PageEncoder::new(PageHeader::new(PageType::DatabaseInfo));
+        page.set_lsn(lsn);
+        page.collect().unwrap()
+    }
 
     #[test]
     fn test_put_and_get() {
         let fm = Rc::new(RefCell::new(FileManager::new()));
         let mut page_cache = PageCache::new(3, Rc::clone(&fm));
 
-        let mut page: PageBytes = [0; 8192];
+        let mut page: PageBytes = [0; PAGE_SIZE_BYTES_USIZE];
         page[0] = 5;
 
         let ix = FilePageId::new(0, 1);
@@ -102,20 +345,220 @@ mod page_cache_tests {
 
     #[test]
     fn test_capacity() {
-        let fm = Rc::new(RefCell::new(FileManager::new()));
-        let mut page_cache = PageCache::new(3, Rc::clone(&fm));
+        let (page_cache, path) = page_cache_over_temp_file("capacity");
+        {
+            let fm = page_cache.file_manager.borrow();
+            let mut file = fm.get(&FileId::new(0, FileType::Primary)).unwrap();
+            file.write_all(&[7; PAGE_SIZE_BYTES_USIZE * 4]).unwrap();
+        }
 
-        let page: PageBytes = [0; 8192];
+        for page_index in 0..4 {
+            page_cache.get_page(&FilePageId::new(0, page_index));
+        }
 
-        page_cache.put_page(&FilePageId::new(0, 1), page);
-        page_cache.put_page(&FilePageId::new(0, 2), page);
-        page_cache.put_page(&FilePageId::new(0, 3), page);
-        page_cache.put_page(&FilePageId::new(0, 4), page);
+        // Once the file is gone, only the pages still cached can be read
+        page_cache.file_manager.borrow_mut().close_all().unwrap();
+        std::fs::remove_file(&path).unwrap();
 
-        let read_value_evicted = page_cache.get_page(&FilePageId::new(0, 1));
+        let read_value_evicted = page_cache.get_page(&FilePageId::new(0, 0));
         assert_eq!(read_value_evicted, None);
 
-        let read_value_exists = page_cache.get_page(&FilePageId::new(0, 2));
-        assert_eq!(read_value_exists.unwrap(), page);
+        let read_value_exists = page_cache.get_page(&FilePageId::new(0, 1));
+        assert_eq!(read_value_exists.unwrap(), [7; PAGE_SIZE_BYTES_USIZE]);
+    }
+
+    #[test]
+    fn test_preload() {
+        let path = std::env::temp_dir().join(format!("wackdb_preload_{}.wak", std::process::id()));
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .read(true)
+            .write(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(&[7; PAGE_SIZE_BYTES_USIZE * 2]).unwrap();
+
+        let fm = Rc::new(RefCell::new(FileManager::new()));
+        fm.borrow_mut().add(FileId::new(0, FileType::Primary), file);
+        let page_cache = PageCache::new(3, Rc::clone(&fm));
+
+        let ids = [
+            FilePageId::new(0, 0),
+            FilePageId::new(0, 1),
+            FilePageId::new(0, 2), // Past the end of the file
+            FilePageId::new(1, 0), // No such database
+        ];
+        assert_eq!(page_cache.preload(&ids), 2);
+
+        // Once the file is gone, preloaded pages are still served from the cache
+        fm.borrow_mut().close_all().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            page_cache.get_page(&FilePageId::new(0, 1)),
+            Some([7; PAGE_SIZE_BYTES_USIZE])
+        );
+    }
+
+    #[test]
+    fn test_preload_replaces_stale_pages() {
+        let (page_cache, path) = page_cache_over_temp_file("preload_stale");
+        let id = FilePageId::new(0, 0);
+
+        std::fs::write(&path, page_with_lsn(5)).unwrap();
+        assert_eq!(page_cache.get_page(&id), Some(page_with_lsn(5)));
+
+        // The file was restored from a newer copy
+        std::fs::write(&path, page_with_lsn(9)).unwrap();
+        assert!(page_cache.is_stale(&id, 9));
+        assert_eq!(page_cache.preload(std::slice::from_ref(&id)), 1);
+        assert_eq!(page_cache.get_page(&id), Some(page_with_lsn(9)));
+
+        // An older copy on disk doesn't replace the cached one
+        std::fs::write(&path, page_with_lsn(3)).unwrap();
+        assert_eq!(page_cache.preload(std::slice::from_ref(&id)), 1);
+        assert_eq!(page_cache.get_page(&id), Some(page_with_lsn(9)));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_capacity_for_memory() {
+        let gb = 1024 * 1024 * 1024;
+
+        // A quarter of 1GB is 32,768 pages of 8KB
+        assert_eq!(capacity_for_memory(Some(gb), 0.25), 32_768);
+        assert_eq!(
+            capacity_for_memory(Some(64 * gb), 0.5),
+            MAX_PAGE_CACHE_CAPACITY
+        );
+        assert_eq!(
+            capacity_for_memory(Some(1024), 0.25),
+            MIN_PAGE_CACHE_CAPACITY
+        );
+        assert_eq!(capacity_for_memory(None, 0.25), MIN_PAGE_CACHE_CAPACITY);
+    }
+
+    #[test]
+    fn test_flush_page_once_logged() {
+        let (mut page_cache, path) = page_cache_over_temp_file("flush");
+        let id = FilePageId::new(0, 1);
+        let page = page_with_lsn(5);
+
+        page_cache.put_page(&id, page);
+        assert!(page_cache.is_dirty(&id));
+
+        page_cache.set_durable_lsn(5);
+        page_cache.flush_page(&id).unwrap();
+        assert!(!page_cache.is_dirty(&id));
+
+        let file = std::fs::File::open(&path).unwrap();
+        let on_disk = persistence::read_page(&file, 1).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(on_disk, page);
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "Write-ahead rule broken"))]
+    fn test_flush_page_before_logged() {
+        let (mut page_cache, path) = page_cache_over_temp_file("flush_unlogged");
+        std::fs::remove_file(&path).unwrap();
+
+        let id = FilePageId::new(0, 1);
+        page_cache.put_page(&id, page_with_lsn(5));
+        page_cache.set_durable_lsn(4);
+
+        // Only reached in release builds, which skip the debug assertion
+        let err = page_cache.flush_page(&id).unwrap_err();
+
+        assert_eq!(
+            err.downcast_ref::<PageCacheError>(),
+            Some(&PageCacheError::WriteAheadViolation {
+                page: id.clone(),
+                lsn: 5,
+                durable_lsn: 4
+            })
+        );
+        assert!(page_cache.is_dirty(&id));
+    }
+
+    #[test]
+    fn test_checkpoint_dirty_page_table() {
+        let (mut page_cache, path) = page_cache_over_temp_file("checkpoint");
+
+        page_cache.put_page(&FilePageId::new(0, 2), page_with_lsn(7));
+        page_cache.put_page(&FilePageId::new(0, 1), page_with_lsn(3));
+        // A later change keeps the LSN of the first one
+        page_cache.put_page(&FilePageId::new(0, 1), page_with_lsn(8));
+
+        let dirty_page = |page_index, rec_lsn| DirtyPage {
+            db_id: 0,
+            page_index,
+            rec_lsn,
+        };
+
+        let checkpoint = page_cache.checkpoint();
+        assert_eq!(
+            checkpoint.dirty_pages,
+            vec![dirty_page(1, 3), dirty_page(2, 7)]
+        );
+        assert_eq!(checkpoint.redo_lsn(10), 3);
+
+        page_cache.set_durable_lsn(8);
+        page_cache.flush_page(&FilePageId::new(0, 1)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(page_cache.checkpoint().dirty_pages, vec![dirty_page(2, 7)]);
+    }
+
+    #[test]
+    fn test_dirty_pages_are_not_evicted() {
+        let (mut page_cache, path) = page_cache_over_temp_file("dirty_eviction");
+        let id = FilePageId::new(0, 1);
+        let page = page_with_lsn(5);
+
+        {
+            let fm = page_cache.file_manager.borrow();
+            let mut file = fm.get(&FileId::new(0, FileType::Primary)).unwrap();
+            file.write_all(&[0; PAGE_SIZE_BYTES_USIZE * 6]).unwrap();
+        }
+        page_cache.put_page(&id, page);
+
+        // Read more clean pages than the cache holds, then shrink it
+        for page_index in 2..6 {
+            assert!(page_cache
+                .get_page(&FilePageId::new(0, page_index))
+                .is_some());
+        }
+        page_cache.resize(1);
+
+        page_cache.set_durable_lsn(5);
+        page_cache.flush_page(&id).unwrap();
+
+        let file = std::fs::File::open(&path).unwrap();
+        let on_disk = persistence::read_page(&file, 1).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(on_disk, page);
+        assert!(!page_cache.is_dirty(&id));
+    }
+
+    #[test]
+    fn test_flush_logged_pages() {
+        let (mut page_cache, path) = page_cache_over_temp_file("flush_logged");
+        let logged = FilePageId::new(0, 1);
+        let unlogged = FilePageId::new(0, 2);
+
+        page_cache.put_page(&logged, page_with_lsn(5));
+        page_cache.put_page(&unlogged, page_with_lsn(9));
+        page_cache.set_durable_lsn(5);
+
+        page_cache.flush_logged_pages().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(!page_cache.is_dirty(&logged));
+        assert!(page_cache.is_dirty(&unlogged));
     }
 }
@@ -1,5 +1,5 @@
 use std::{
-    ffi::OsStr,
+    ffi::OsString,
     fs::File,
     io::{Read, Seek, Write},
     path::{Path, PathBuf},
@@ -11,7 +11,10 @@ use thiserror::Error;
 
 use crate::{
     db::FileType,
-    engine::{DATA_FILE_EXT, LOG_FILE_EXT, PAGE_SIZE_BYTES, PAGE_SIZE_BYTES_USIZE, WACK_DIRECTORY},
+    engine::{
+        DATA_DIRECTORY_ENV, DATA_FILE_EXT, LOG_FILE_EXT, PAGE_SIZE_BYTES, PAGE_SIZE_BYTES_USIZE,
+        WACK_DIRECTORY,
+    },
     page_cache::PageBytes,
     server::MASTER_NAME,
     util,
@@ -29,13 +32,13 @@ pub enum PersistenceError {
 
 // Returns true if the given file exists
 pub fn check_db_exists(db_name: &str, file_type: FileType) -> Result<bool> {
-    let path = get_db_path(db_name, file_type);
+    let path = get_db_path(db_name, file_type)?;
     util::file_exists(&path)
 }
 
 /// Create a database file, empty.
 pub fn create_db_file_empty(db_name: &str, file_type: FileType) -> Result<File> {
-    let master_path = get_db_path(db_name, file_type);
+    let master_path = get_db_path(db_name, file_type)?;
 
     util::file_exists(&master_path)?;
     util::ensure_path_exists(&master_path)?;
@@ -44,19 +47,35 @@ pub fn create_db_file_empty(db_name: &str, file_type: FileType) -> Result<File>
 }
 
 // Get a PathBuf to a file with the given name and extension
-pub fn get_db_path(db_name: &str, file_type: FileType) -> PathBuf {
+pub fn get_db_path(db_name: &str, file_type: FileType) -> Result<PathBuf> {
     let ext = match file_type {
         FileType::Primary => DATA_FILE_EXT,
         FileType::Log => LOG_FILE_EXT,
     };
 
-    let base_path = util::get_base_path();
-    let mut data_path = Path::join(&base_path, std::path::Path::new(WACK_DIRECTORY));
+    // Names aren't case sensitive, so neither are file names, whatever the file system.
+    // Not with_extension, as that would replace anything after a dot in the name
+    let file_name = format!("{}.{ext}", db_name.to_ascii_lowercase());
 
-    let file_name = db_name.to_owned() + "." + ext;
-    PathBuf::push(&mut data_path, file_name);
+    Ok(get_data_path()?.join(file_name))
+}
+
+/// Get the directory database files are kept in.
+/// This is WACKDB_DATA if it's set, otherwise the data directory next to the executable.
+pub fn get_data_path() -> Result<PathBuf> {
+    let base_path = util::get_base_path()?;
 
-    data_path
+    Ok(data_path_from(
+        std::env::var_os(DATA_DIRECTORY_ENV),
+        &base_path,
+    ))
+}
+
+fn data_path_from(env_path: Option<OsString>, base_path: &Path) -> PathBuf {
+    match env_path {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => base_path.join(WACK_DIRECTORY),
+    }
 }
 
 /// Seek to a specific page index in the file and write the given data
@@ -92,36 +111,32 @@ pub fn seek_page_index(mut file: &std::fs::File, page_index: u32) -> Result<()>
     }
 }
 
-pub fn find_user_databases() -> Result<Box<impl Iterator<Item = String>>> {
-    let base_path = util::get_base_path();
-    let data_path = Path::join(&base_path, std::path::Path::new(WACK_DIRECTORY));
-
-    let files = std::fs::read_dir(data_path);
+/// Find the data file of every user database, by its path on disk.
+pub fn find_user_databases() -> Result<Vec<PathBuf>> {
+    find_data_files(&get_data_path()?)
+}
 
-    let unique_file_names = files?.filter_map(|entry| {
-        let entry = entry.ok()?;
-        let path = entry.path();
+/// Find the data files in a directory, other than master's.
+/// Files are returned as they are on disk, as ones created before names were lowercased may not be.
+fn find_data_files(directory: &Path) -> Result<Vec<PathBuf>> {
+    let files = std::fs::read_dir(directory)?.filter_map(|entry| {
+        let path = entry.ok()?.path();
 
         if path.is_dir() {
             return None;
         }
 
-        if let Some(filename) = path.file_stem() {
-            if filename == MASTER_NAME {
-                return None;
-            }
-        }
+        let is_master = path
+            .file_stem()
+            .is_some_and(|stem| stem.eq_ignore_ascii_case(MASTER_NAME));
+        let is_data_file = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(DATA_FILE_EXT));
 
-        path.extension()
-            .filter(|e| is_wack_file(e))
-            .and_then(|_| path.file_stem().and_then(OsStr::to_str).map(str::to_owned))
+        (is_data_file && !is_master).then_some(path)
     });
 
-    Ok(Box::new(unique_file_names))
-}
-
-fn is_wack_file(extension: &OsStr) -> bool {
-    extension.eq_ignore_ascii_case(DATA_FILE_EXT) || extension.eq_ignore_ascii_case(LOG_FILE_EXT)
+    Ok(files.collect())
 }
 
 pub struct OpenDatabaseResult {
@@ -129,28 +144,40 @@ pub struct OpenDatabaseResult {
     pub log: File,
 }
 
-pub fn open_db(database_name: &str) -> OpenDatabaseResult {
-    let dat = open_db_of_type(database_name, FileType::Primary);
-    let log = open_db_of_type(database_name, FileType::Log);
+pub fn open_db(database_name: &str) -> Result<OpenDatabaseResult> {
+    let dat = open_db_of_type(database_name, FileType::Primary)?;
+    let log = open_db_of_type(database_name, FileType::Log)?;
 
-    OpenDatabaseResult { dat, log }
+    Ok(OpenDatabaseResult { dat, log })
 }
 
-fn open_db_of_type(database_name: &str, file_type: FileType) -> File {
-    let path = get_db_path(database_name, file_type);
-    util::open_file(&path).expect("Failed to open database.")
+fn open_db_of_type(database_name: &str, file_type: FileType) -> Result<File> {
+    let path = get_db_path(database_name, file_type)?;
+    util::open_file(&path)
+}
+
+/// Open a database from the path of its data file, with its log file next to it.
+pub fn open_db_at(data_path: &Path) -> Result<OpenDatabaseResult> {
+    let dat = util::open_file(&data_path.to_path_buf())?;
+    let log = util::open_file(&data_path.with_extension(LOG_FILE_EXT))?;
+
+    Ok(OpenDatabaseResult { dat, log })
 }
 
 #[cfg(test)]
 mod persistence_tests {
     use crate::*;
 
+    use db::FileType;
     use engine::PAGE_SIZE_BYTES;
-    use persistence::{read_page, write_page};
+    use persistence::{
+        data_path_from, find_data_files, get_db_path, open_db_at, read_page, write_page,
+    };
     use std::{
         env::temp_dir,
+        ffi::OsString,
         fs::{File, OpenOptions},
-        path::PathBuf,
+        path::{Path, PathBuf},
     };
     use uuid::Uuid;
 
@@ -238,4 +265,83 @@ mod persistence_tests {
         // Clean down
         std::fs::remove_file(temp_path).expect("Unable to clear down test.");
     }
+
+    #[test]
+    fn test_data_path_defaults_to_base_path() {
+        let base = temp_dir();
+
+        assert_eq!(data_path_from(None, &base), base.join("data"));
+        assert_eq!(
+            data_path_from(Some(OsString::new()), &base),
+            base.join("data")
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_data_path_from_env_unix() {
+        let path = data_path_from(Some(OsString::from("/var/lib/wack/")), Path::new("/opt"));
+
+        assert_eq!(path, PathBuf::from("/var/lib/wack"));
+        assert_eq!(
+            path.join("test.wak"),
+            PathBuf::from("/var/lib/wack/test.wak")
+        );
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn test_data_path_from_env_windows() {
+        let path = data_path_from(Some(OsString::from(r"D:\Wack Data\")), Path::new(r"C:\"));
+
+        assert_eq!(path, PathBuf::from(r"D:\Wack Data"));
+        assert_eq!(
+            path.join("test.wak"),
+            PathBuf::from(r"D:\Wack Data\test.wak")
+        );
+    }
+
+    #[test]
+    fn test_db_path_keeps_dots_in_name() {
+        let path = get_db_path("sales.2024", FileType::Primary).unwrap();
+
+        assert_eq!(path.file_name().unwrap(), "sales.2024.wak");
+        assert_eq!(
+            path.parent().unwrap(),
+            persistence::get_data_path().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_db_path_is_lowercase() {
+        for name in ["Users", "users", "USERS"] {
+            let path = get_db_path(name, FileType::Log).unwrap();
+
+            assert_eq!(path.file_name().unwrap(), "users.wal");
+        }
+    }
+
+    #[test]
+    fn test_mixed_case_files_are_opened_by_their_path() {
+        let directory = temp_dir_path();
+        std::fs::create_dir(&directory).unwrap();
+
+        for file_name in [
+            "Sales.wak",
+            "Sales.wal",
+            "master.wak",
+            "master.wal",
+            "notes.txt",
+        ] {
+            File::create(directory.join(file_name)).unwrap();
+        }
+
+        let found = find_data_files(&directory).unwrap();
+        let opened = found.iter().all(|path| open_db_at(path).is_ok());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(found, vec![directory.join("Sales.wak")]);
+        assert!(opened);
+    }
 }
@@ -37,7 +37,7 @@ pub fn open_or_create_master_db() -> Result<OpenDatabaseResult> {
     let exists = persistence::check_db_exists(MASTER_NAME, FileType::Primary)?;
 
     if exists {
-        let db = persistence::open_db(MASTER_NAME);
+        let db = persistence::open_db(MASTER_NAME)?;
 
         log::info!("Opened existing master DB.");
 
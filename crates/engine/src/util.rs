@@ -13,6 +13,23 @@ pub fn file_exists(path: &Path) -> Result<bool> {
     Ok(Path::try_exists(path)?)
 }
 
+/// How much memory the system can give to new allocations, in bytes.
+/// Only known on Linux, where it's read from /proc/meminfo.
+pub fn available_memory() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+
+    let kb = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemAvailable:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+
+    Some(kb * 1024)
+}
+
 pub fn ensure_path_exists(path: &Path) -> Result<()> {
     let dir = match path.is_dir() {
         true => path,
@@ -41,14 +58,11 @@ pub fn open_file(path: &PathBuf) -> Result<std::fs::File> {
         .open(path)?)
 }
 
-pub fn get_base_path() -> std::path::PathBuf {
-    match std::env::current_exe() {
-        Ok(mut path) => {
-            path.pop();
-            path
-        }
-        Err(err) => panic!("Error: Unable to read filesystem. See: {}", err),
-    }
+pub fn get_base_path() -> Result<std::path::PathBuf> {
+    let mut path = std::env::current_exe()?;
+    path.pop();
+
+    Ok(path)
 }
 
 #[cfg(test)]
@@ -1,42 +1,190 @@
 #![allow(unused_variables)]
 
 use anyhow::Result;
-use parser::ast::{Expr, Identifier, UserStatement, Value};
+use parser::ast::{
+    Expr, FromClause, Identifier, SelectExpressionBody, TableSource, UnaryOperator, UserStatement,
+    Value,
+};
+use thiserror::Error;
 
-use crate::engine::{ColumnResult, ExprResult, ResultSet, StatementResult};
+use crate::cast;
+use crate::engine::{ColumnResult, ColumnType, ExprResult, ResultSet, StatementResult};
+use crate::json;
+use crate::random::Random;
 
-pub fn execute_user_statement(statement: &UserStatement) -> Result<StatementResult> {
-    let is_const_expr = is_constant_statement(statement);
+#[derive(Debug, PartialEq, Error)]
+pub enum ExecutionError {
+    #[error("Unsupported statement: {0}")]
+    UnsupportedStatement(&'static str),
+    #[error("Unsupported expression: {0}")]
+    UnsupportedExpression(String),
+    #[error("Aggregate functions aren't supported yet: {0}")]
+    UnsupportedAggregate(String),
+    #[error("Subqueries aren't supported yet: {0}")]
+    UnsupportedSubquery(String),
+    #[error("Invalid arguments: {0}")]
+    InvalidArguments(String),
+    #[error("Can't convert {0} to {1}")]
+    InvalidCast(String, String),
+    #[error("Arithmetic overflow")]
+    ArithmeticOverflow,
+    #[error("Division by zero")]
+    DivisionByZero,
+    #[error("Unknown column: '{0}'")]
+    UnknownColumn(String),
+    #[error("Column name is ambiguous: '{0}'")]
+    AmbiguousColumn(String),
+}
 
-    if is_const_expr {
-        log::debug!("Statement is constant");
-        return evaluate_constant_statement(statement);
+pub fn execute_user_statement(
+    statement: &UserStatement,
+    random: &mut Random,
+) -> Result<StatementResult> {
+    match statement {
+        UserStatement::Select(body) => execute_select(body, random),
+        _ => Err(ExecutionError::UnsupportedStatement(statement_name(statement)).into()),
     }
+}
 
-    match statement {
-        UserStatement::Select(_) => todo!(),
-        UserStatement::Update => todo!(),
-        UserStatement::Insert => todo!(),
-        UserStatement::Delete => todo!(),
-        UserStatement::CreateTable(_) => todo!(),
+fn execute_select(body: &SelectExpressionBody, random: &mut Random) -> Result<StatementResult> {
+    if let Some(aggregate) = find_expr(body, &Expr::is_aggregate) {
+        return Err(ExecutionError::UnsupportedAggregate(aggregate.to_string()).into());
     }
+
+    if let Some(subquery) = find_expr(body, &is_subquery) {
+        return Err(ExecutionError::UnsupportedSubquery(subquery.to_string()).into());
+    }
+
+    let row = match &body.from_clause {
+        None => Row::default(),
+        Some(FromClause {
+            source: TableSource::Derived(derived),
+            alias,
+            joins,
+        }) if joins.is_empty() => Row {
+            table: alias.as_ref().map(|alias| alias.value.as_str()),
+            columns: execute_select(derived, random)?.result_set.columns,
+        },
+        // Tables can't be read yet, so only items which don't need a row can be evaluated
+        Some(_) if is_constant_select(body) => {
+            log::debug!("Statement is constant");
+            Row::default()
+        }
+        Some(_) => return Err(ExecutionError::UnsupportedStatement("SELECT").into()),
+    };
+
+    let columns = body
+        .select_item_list
+        .item_list
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let value = evaluate_expr(&item.expr, &row, random)?;
+
+            Ok(ColumnResult {
+                name: evaluate_column_name(&item.alias, index),
+                column_type: column_type(&item.expr, &value, &row),
+                nullable: value == ExprResult::Null,
+                value,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(StatementResult {
+        result_set: ResultSet { columns },
+        warnings: vec![],
+    })
 }
 
-// todo: type?
-fn is_constant_statement(statement: &UserStatement) -> bool {
-    match statement {
-        UserStatement::Select(select_expression_body) => select_expression_body
-            .select_item_list
-            .item_list
+/// The row a SELECT's items are evaluated against, read from its FROM clause.
+/// Only derived tables can be read so far, and those always give a single row.
+#[derive(Default)]
+struct Row<'a> {
+    /// The FROM clause's alias, which qualified names must use.
+    table: Option<&'a str>,
+    columns: Vec<ColumnResult>,
+}
+
+impl Row<'_> {
+    /// Find the value of the column a name refers to, such as Age or u.Age.
+    fn resolve(&self, name: &[Identifier]) -> Result<ExprResult> {
+        self.find(name).map(|column| column.value.clone())
+    }
+
+    fn find(&self, name: &[Identifier]) -> Result<&ColumnResult> {
+        let full_name = || {
+            let parts = name.iter().map(|x| x.value.as_str()).collect::<Vec<_>>();
+            parts.join(".")
+        };
+
+        let column = match name {
+            [column] => column,
+            [table, column]
+                if self
+                    .table
+                    .is_some_and(|t| t.eq_ignore_ascii_case(&table.value)) =>
+            {
+                column
+            }
+            _ => return Err(ExecutionError::UnknownColumn(full_name()).into()),
+        };
+
+        let mut matches = self
+            .columns
             .iter()
-            .all(|item| is_const_exp(&item.expr)),
-        UserStatement::Update => todo!(),
-        UserStatement::Insert => todo!(),
-        UserStatement::Delete => todo!(),
-        UserStatement::CreateTable(_) => todo!(),
+            .filter(|c| c.name.eq_ignore_ascii_case(&column.value));
+
+        match (matches.next(), matches.next()) {
+            (Some(found), None) => Ok(found),
+            (Some(_), Some(_)) => Err(ExecutionError::AmbiguousColumn(full_name()).into()),
+            (None, _) => Err(ExecutionError::UnknownColumn(full_name()).into()),
+        }
     }
 }
 
+/// The type of a select item's column. CAST declares a type, and a column read from the FROM
+/// clause keeps its type, even when the value is NULL. Otherwise the type is the value's.
+fn column_type(expr: &Expr, value: &ExprResult, row: &Row) -> ColumnType {
+    let column = match expr {
+        Expr::Cast { data_type, .. } => return ColumnType::from(data_type),
+        Expr::Identifier(identifier) => row.find(std::slice::from_ref(identifier)),
+        Expr::QualifiedIdentifier(identifiers) => row.find(identifiers),
+        _ => return ColumnType::of(value),
+    };
+
+    column.map_or_else(|_| ColumnType::of(value), |column| column.column_type)
+}
+
+/// Find the first expression in a SELECT which matches the predicate.
+fn find_expr<'a>(
+    body: &'a SelectExpressionBody,
+    predicate: &dyn Fn(&Expr) -> bool,
+) -> Option<&'a Expr> {
+    body.select_item_list
+        .item_list
+        .iter()
+        .map(|item| &item.expr)
+        .chain(body.where_clause.iter().map(|w| &w.expr))
+        .find_map(|expr| expr.find(predicate))
+}
+
+fn is_subquery(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Subquery(_)
+            | Expr::Exists(_)
+            | Expr::IsInSubquery { .. }
+            | Expr::IsNotInSubquery { .. }
+    )
+}
+
+fn is_constant_select(body: &SelectExpressionBody) -> bool {
+    body.select_item_list
+        .item_list
+        .iter()
+        .all(|item| is_const_exp(&item.expr))
+}
+
 fn is_const_exp(expr: &Expr) -> bool {
     match expr {
         Expr::Between {
@@ -45,42 +193,36 @@ fn is_const_exp(expr: &Expr) -> bool {
             higher,
         } => is_const_exp(expr) && is_const_exp(lower) && is_const_exp(higher),
         Expr::BinaryOperator { left, right, .. } => is_const_exp(left) && is_const_exp(right),
+        Expr::UnaryOperator { expr, .. } | Expr::Cast { expr, .. } => is_const_exp(expr),
         Expr::IsFalse(expr) => is_const_exp(expr),
         Expr::IsTrue(expr) => is_const_exp(expr),
         Expr::IsNull(expr) => is_const_exp(expr),
         Expr::IsNotNull(expr) => is_const_exp(expr),
-        Expr::Like { expr, pattern } => is_const_exp(expr) && is_const_exp(pattern),
+        Expr::Like { expr, pattern } | Expr::NotLike { expr, pattern } => {
+            is_const_exp(expr) && is_const_exp(pattern)
+        }
         Expr::IsIn { expr, list } => is_const_exp(expr) && list.iter().all(is_const_exp),
         Expr::IsNotFalse(expr) => is_const_exp(expr),
         Expr::IsNotTrue(expr) => is_const_exp(expr),
         Expr::Value(_) => true,
+        Expr::Function { name, args, .. } if is_builtin_function(name) => {
+            args.iter().all(is_const_exp)
+        }
         Expr::Identifier(_) => false,
         _ => false,
     }
 }
 
-fn evaluate_constant_statement(statement: &UserStatement) -> Result<StatementResult> {
+fn statement_name(statement: &UserStatement) -> &'static str {
     match statement {
-        UserStatement::Select(select_expression_body) => {
-            let columns = select_expression_body
-                .select_item_list
-                .item_list
-                .iter()
-                .enumerate()
-                .map(|(index, item)| ColumnResult {
-                    name: evaluate_column_name(&item.alias, index),
-                    value: evaluate_constant_expr(&item.expr),
-                })
-                .collect();
-
-            Ok(StatementResult {
-                result_set: ResultSet { columns },
-            })
-        }
-        UserStatement::Update => todo!(),
-        UserStatement::Insert => todo!(),
-        UserStatement::Delete => todo!(),
-        UserStatement::CreateTable(_) => todo!(),
+        UserStatement::Select(_) => "SELECT",
+        UserStatement::Update => "UPDATE",
+        UserStatement::Insert => "INSERT",
+        UserStatement::Delete => "DELETE",
+        UserStatement::CreateTable(_) => "CREATE TABLE",
+        UserStatement::CreateIndex(_) => "CREATE INDEX",
+        UserStatement::DropTable(_) => "DROP TABLE",
+        UserStatement::Describe(_) => "DESCRIBE",
     }
 }
 
@@ -91,41 +233,73 @@ fn evaluate_column_name(identifier: &Option<Identifier>, index: usize) -> String
     }
 }
 
-fn evaluate_constant_expr(expr: &Expr) -> ExprResult {
+/// Evaluate an expression which doesn't refer to any columns.
+pub fn evaluate_constant_expr(expr: &Expr, random: &mut Random) -> Result<ExprResult> {
+    evaluate_expr(expr, &Row::default(), random)
+}
+
+fn evaluate_expr(expr: &Expr, row: &Row, random: &mut Random) -> Result<ExprResult> {
     match expr {
-        Expr::Value(value) => evaluate_value(value),
-        Expr::IsTrue(_) => todo!(),
-        Expr::IsNotTrue(_) => todo!(),
-        Expr::IsFalse(_) => todo!(),
-        Expr::IsNotFalse(_) => todo!(),
-        Expr::IsNull(_) => todo!(),
-        Expr::IsNotNull(_) => todo!(),
-        Expr::IsIn { expr, list } => todo!(),
-        Expr::IsNotIn { expr, list } => todo!(),
-        Expr::Between {
-            expr,
-            lower,
-            higher,
-        } => todo!(),
-        Expr::NotBetween {
-            expr,
-            lower,
-            higher,
-        } => todo!(),
-        Expr::Like { expr, pattern } => todo!(),
-        Expr::NotLike { expr, pattern } => todo!(),
-        Expr::BinaryOperator { left, op, right } => match op {
+        Expr::Value(value) => Ok(evaluate_value(value)),
+        Expr::IsTrue(expr) => {
+            let result = evaluate_expr(expr, row, random)?;
+            Ok(ExprResult::Bool(result == ExprResult::Bool(true)))
+        }
+        Expr::IsNotTrue(expr) => {
+            let result = evaluate_expr(expr, row, random)?;
+            Ok(ExprResult::Bool(result != ExprResult::Bool(true)))
+        }
+        Expr::IsFalse(expr) => {
+            let result = evaluate_expr(expr, row, random)?;
+            Ok(ExprResult::Bool(result == ExprResult::Bool(false)))
+        }
+        Expr::IsNotFalse(expr) => {
+            let result = evaluate_expr(expr, row, random)?;
+            Ok(ExprResult::Bool(result != ExprResult::Bool(false)))
+        }
+        Expr::IsNull(expr) => Ok(ExprResult::Bool(
+            evaluate_expr(expr, row, random)? == ExprResult::Null,
+        )),
+        Expr::IsNotNull(expr) => Ok(ExprResult::Bool(
+            evaluate_expr(expr, row, random)? != ExprResult::Null,
+        )),
+        Expr::UnaryOperator { op, expr } => evaluate_unary(*op, expr, row, random),
+        Expr::Cast { expr, data_type } => {
+            let value = evaluate_expr(expr, row, random)?;
+
+            cast::cast(value.clone(), data_type).ok_or_else(|| {
+                ExecutionError::InvalidCast(value.to_string(), data_type.to_string()).into()
+            })
+        }
+        Expr::Like { expr, pattern } => evaluate_like(expr, pattern, false, row, random),
+        Expr::NotLike { expr, pattern } => evaluate_like(expr, pattern, true, row, random),
+        Expr::IsIn { .. }
+        | Expr::IsNotIn { .. }
+        | Expr::IsInSubquery { .. }
+        | Expr::IsNotInSubquery { .. }
+        | Expr::Exists(_)
+        | Expr::Subquery(_)
+        | Expr::Between { .. }
+        | Expr::NotBetween { .. } => {
+            Err(ExecutionError::UnsupportedExpression(expr.to_string()).into())
+        }
+        Expr::BinaryOperator { left, op, right } => Ok(match op {
             parser::ast::BinaryOperator::Plus => {
-                let left = evaluate_constant_expr(left);
-                let right = evaluate_constant_expr(right);
+                let left = evaluate_expr(left, row, random)?;
+                let right = evaluate_expr(right, row, random)?;
 
                 if left == ExprResult::Null || right == ExprResult::Null {
-                    return ExprResult::Null;
+                    return Ok(ExprResult::Null);
                 }
 
-                match (left, right) {
-                    (ExprResult::Int(l), ExprResult::Int(r)) => ExprResult::Int(l + r),
-                    (ExprResult::Byte(l), ExprResult::Byte(r)) => ExprResult::Byte(l + r),
+                match promote_numeric(left, right) {
+                    (ExprResult::Int(l), ExprResult::Int(r)) => {
+                        ExprResult::Int(l.checked_add(r).ok_or(ExecutionError::ArithmeticOverflow)?)
+                    }
+                    (ExprResult::Byte(l), ExprResult::Byte(r)) => ExprResult::Byte(
+                        l.checked_add(r).ok_or(ExecutionError::ArithmeticOverflow)?,
+                    ),
+                    (ExprResult::Float(l), ExprResult::Float(r)) => ExprResult::Float(l + r),
                     (ExprResult::String(l), ExprResult::String(r)) => {
                         ExprResult::String(format!("{}{}", l, r))
                     }
@@ -133,177 +307,401 @@ fn evaluate_constant_expr(expr: &Expr) -> ExprResult {
                 }
             }
             parser::ast::BinaryOperator::Minus => {
-                let left = evaluate_constant_expr(left);
-                let right = evaluate_constant_expr(right);
+                let left = evaluate_expr(left, row, random)?;
+                let right = evaluate_expr(right, row, random)?;
 
                 if left == ExprResult::Null || right == ExprResult::Null {
-                    return ExprResult::Null;
+                    return Ok(ExprResult::Null);
                 }
 
-                match (left, right) {
-                    (ExprResult::Int(l), ExprResult::Int(r)) => ExprResult::Int(l - r),
-                    (ExprResult::Byte(l), ExprResult::Byte(r)) => ExprResult::Byte(l - r),
+                match promote_numeric(left, right) {
+                    (ExprResult::Int(l), ExprResult::Int(r)) => {
+                        ExprResult::Int(l.checked_sub(r).ok_or(ExecutionError::ArithmeticOverflow)?)
+                    }
+                    (ExprResult::Byte(l), ExprResult::Byte(r)) => ExprResult::Byte(
+                        l.checked_sub(r).ok_or(ExecutionError::ArithmeticOverflow)?,
+                    ),
+                    (ExprResult::Float(l), ExprResult::Float(r)) => ExprResult::Float(l - r),
                     // Cannot negate strings
                     _ => ExprResult::Null,
                 }
             }
             parser::ast::BinaryOperator::Multiply => {
-                let left = evaluate_constant_expr(left);
-                let right = evaluate_constant_expr(right);
+                let left = evaluate_expr(left, row, random)?;
+                let right = evaluate_expr(right, row, random)?;
 
                 if left == ExprResult::Null || right == ExprResult::Null {
-                    return ExprResult::Null;
+                    return Ok(ExprResult::Null);
                 }
 
-                match (left, right) {
-                    (ExprResult::Int(l), ExprResult::Int(r)) => ExprResult::Int(l * r),
-                    (ExprResult::Byte(l), ExprResult::Byte(r)) => ExprResult::Byte(l * r),
+                match promote_numeric(left, right) {
+                    (ExprResult::Int(l), ExprResult::Int(r)) => {
+                        ExprResult::Int(l.checked_mul(r).ok_or(ExecutionError::ArithmeticOverflow)?)
+                    }
+                    (ExprResult::Byte(l), ExprResult::Byte(r)) => ExprResult::Byte(
+                        l.checked_mul(r).ok_or(ExecutionError::ArithmeticOverflow)?,
+                    ),
+                    (ExprResult::Float(l), ExprResult::Float(r)) => ExprResult::Float(l * r),
                     // Cannot multiply strings
                     _ => ExprResult::Null,
                 }
             }
             parser::ast::BinaryOperator::Divide => {
-                let left = evaluate_constant_expr(left);
-                let right = evaluate_constant_expr(right);
+                let left = evaluate_expr(left, row, random)?;
+                let right = evaluate_expr(right, row, random)?;
 
                 if left == ExprResult::Null || right == ExprResult::Null {
-                    return ExprResult::Null;
+                    return Ok(ExprResult::Null);
                 }
 
-                match (left, right) {
+                match promote_numeric(left, right) {
                     (ExprResult::Int(l), ExprResult::Int(r)) => {
-                        if r == 0 {
-                            ExprResult::Int(0)
-                        } else {
-                            ExprResult::Int(l / r)
-                        }
+                        ExprResult::Int(l.checked_div(r).ok_or(ExecutionError::DivisionByZero)?)
                     }
                     (ExprResult::Byte(l), ExprResult::Byte(r)) => {
-                        if r == 0 {
-                            ExprResult::Byte(0)
-                        } else {
-                            ExprResult::Byte(l / r)
-                        }
+                        ExprResult::Byte(l.checked_div(r).ok_or(ExecutionError::DivisionByZero)?)
+                    }
+                    (ExprResult::Float(_), ExprResult::Float(0.0)) => {
+                        return Err(ExecutionError::DivisionByZero.into())
                     }
+                    (ExprResult::Float(l), ExprResult::Float(r)) => ExprResult::Float(l / r),
                     // Cannot divide strings
                     _ => ExprResult::Null,
                 }
             }
             parser::ast::BinaryOperator::Modulo => {
-                let left = evaluate_constant_expr(left);
-                let right = evaluate_constant_expr(right);
+                let left = evaluate_expr(left, row, random)?;
+                let right = evaluate_expr(right, row, random)?;
 
                 if left == ExprResult::Null || right == ExprResult::Null {
-                    return ExprResult::Null;
+                    return Ok(ExprResult::Null);
                 }
 
-                match (left, right) {
-                    (ExprResult::Int(l), ExprResult::Int(r)) => ExprResult::Int(l % r),
-                    (ExprResult::Byte(l), ExprResult::Byte(r)) => ExprResult::Byte(l % r),
+                match promote_numeric(left, right) {
+                    (ExprResult::Int(l), ExprResult::Int(r)) => {
+                        ExprResult::Int(l.checked_rem(r).ok_or(ExecutionError::DivisionByZero)?)
+                    }
+                    (ExprResult::Byte(l), ExprResult::Byte(r)) => {
+                        ExprResult::Byte(l.checked_rem(r).ok_or(ExecutionError::DivisionByZero)?)
+                    }
+                    (ExprResult::Float(_), ExprResult::Float(0.0)) => {
+                        return Err(ExecutionError::DivisionByZero.into())
+                    }
+                    (ExprResult::Float(l), ExprResult::Float(r)) => ExprResult::Float(l % r),
                     // Cannot modulo strings
                     _ => ExprResult::Null,
                 }
             }
             parser::ast::BinaryOperator::GreaterThan => {
-                let left = evaluate_constant_expr(left);
-                let right = evaluate_constant_expr(right);
+                let left = evaluate_expr(left, row, random)?;
+                let right = evaluate_expr(right, row, random)?;
 
                 if left == ExprResult::Null || right == ExprResult::Null {
-                    return ExprResult::Bool(false);
+                    return Ok(ExprResult::Bool(false));
                 }
 
-                match (left, right) {
+                match promote_numeric(left, right) {
                     (ExprResult::Int(l), ExprResult::Int(r)) => ExprResult::Bool(l > r),
                     (ExprResult::Byte(l), ExprResult::Byte(r)) => ExprResult::Bool(l > r),
+                    (ExprResult::Float(l), ExprResult::Float(r)) => ExprResult::Bool(l > r),
                     // Cannot compare strings
                     _ => ExprResult::Null,
                 }
             }
             parser::ast::BinaryOperator::GreaterThanOrEqual => {
-                let left = evaluate_constant_expr(left);
-                let right = evaluate_constant_expr(right);
+                let left = evaluate_expr(left, row, random)?;
+                let right = evaluate_expr(right, row, random)?;
 
                 if left == ExprResult::Null || right == ExprResult::Null {
-                    return ExprResult::Bool(false);
+                    return Ok(ExprResult::Bool(false));
                 }
 
-                match (left, right) {
+                match promote_numeric(left, right) {
                     (ExprResult::Int(l), ExprResult::Int(r)) => ExprResult::Bool(l >= r),
                     (ExprResult::Byte(l), ExprResult::Byte(r)) => ExprResult::Bool(l >= r),
+                    (ExprResult::Float(l), ExprResult::Float(r)) => ExprResult::Bool(l >= r),
                     // Cannot compare strings
                     _ => ExprResult::Null,
                 }
             }
             parser::ast::BinaryOperator::LessThan => {
-                let left = evaluate_constant_expr(left);
-                let right = evaluate_constant_expr(right);
+                let left = evaluate_expr(left, row, random)?;
+                let right = evaluate_expr(right, row, random)?;
 
                 if left == ExprResult::Null || right == ExprResult::Null {
-                    return ExprResult::Bool(false);
+                    return Ok(ExprResult::Bool(false));
                 }
 
-                match (left, right) {
+                match promote_numeric(left, right) {
                     (ExprResult::Int(l), ExprResult::Int(r)) => ExprResult::Bool(l < r),
                     (ExprResult::Byte(l), ExprResult::Byte(r)) => ExprResult::Bool(l < r),
+                    (ExprResult::Float(l), ExprResult::Float(r)) => ExprResult::Bool(l < r),
                     // Cannot compare strings
                     _ => ExprResult::Null,
                 }
             }
             parser::ast::BinaryOperator::LessThanOrEqual => {
-                let left = evaluate_constant_expr(left);
-                let right = evaluate_constant_expr(right);
+                let left = evaluate_expr(left, row, random)?;
+                let right = evaluate_expr(right, row, random)?;
 
                 if left == ExprResult::Null || right == ExprResult::Null {
-                    return ExprResult::Bool(false);
+                    return Ok(ExprResult::Bool(false));
                 }
 
-                match (left, right) {
+                match promote_numeric(left, right) {
                     (ExprResult::Int(l), ExprResult::Int(r)) => ExprResult::Bool(l <= r),
                     (ExprResult::Byte(l), ExprResult::Byte(r)) => ExprResult::Bool(l <= r),
+                    (ExprResult::Float(l), ExprResult::Float(r)) => ExprResult::Bool(l <= r),
                     // Cannot compare strings
                     _ => ExprResult::Null,
                 }
             }
             parser::ast::BinaryOperator::Equal => {
-                let left = evaluate_constant_expr(left);
-                let right = evaluate_constant_expr(right);
+                let left = evaluate_expr(left, row, random)?;
+                let right = evaluate_expr(right, row, random)?;
 
                 if left == ExprResult::Null || right == ExprResult::Null {
-                    return ExprResult::Bool(false);
+                    return Ok(ExprResult::Bool(false));
                 }
 
-                match (left, right) {
+                match promote_numeric(left, right) {
                     (ExprResult::Int(l), ExprResult::Int(r)) => ExprResult::Bool(l == r),
                     (ExprResult::Byte(l), ExprResult::Byte(r)) => ExprResult::Bool(l == r),
+                    (ExprResult::Float(l), ExprResult::Float(r)) => ExprResult::Bool(l == r),
                     (ExprResult::String(l), ExprResult::String(r)) => ExprResult::Bool(l == r),
+                    (ExprResult::Bool(l), ExprResult::Bool(r)) => ExprResult::Bool(l == r),
+                    (ExprResult::Blob(l), ExprResult::Blob(r)) => ExprResult::Bool(l == r),
                     _ => ExprResult::Null,
                 }
             }
             parser::ast::BinaryOperator::NotEqual => {
-                let left = evaluate_constant_expr(left);
-                let right = evaluate_constant_expr(right);
+                let left = evaluate_expr(left, row, random)?;
+                let right = evaluate_expr(right, row, random)?;
 
                 if left == ExprResult::Null || right == ExprResult::Null {
-                    return ExprResult::Bool(false);
+                    return Ok(ExprResult::Bool(false));
                 }
 
-                match (left, right) {
+                match promote_numeric(left, right) {
                     (ExprResult::Int(l), ExprResult::Int(r)) => ExprResult::Bool(l != r),
                     (ExprResult::Byte(l), ExprResult::Byte(r)) => ExprResult::Bool(l != r),
+                    (ExprResult::Float(l), ExprResult::Float(r)) => ExprResult::Bool(l != r),
                     (ExprResult::String(l), ExprResult::String(r)) => ExprResult::Bool(l != r),
+                    (ExprResult::Bool(l), ExprResult::Bool(r)) => ExprResult::Bool(l != r),
+                    (ExprResult::Blob(l), ExprResult::Blob(r)) => ExprResult::Bool(l != r),
                     _ => ExprResult::Null,
                 }
             }
-            parser::ast::BinaryOperator::And => todo!(),
-            parser::ast::BinaryOperator::Or => todo!(),
-            parser::ast::BinaryOperator::Xor => todo!(),
-            parser::ast::BinaryOperator::BitwiseOr => todo!(),
-            parser::ast::BinaryOperator::BitwiseAnd => todo!(),
-            parser::ast::BinaryOperator::BitwiseXor => todo!(),
-        },
-        Expr::Identifier(_) => todo!(),
-        Expr::QualifiedIdentifier(_) => todo!(),
-        Expr::Wildcard => todo!(),
+            parser::ast::BinaryOperator::And => {
+                let left = evaluate_expr(left, row, random)?;
+                let right = evaluate_expr(right, row, random)?;
+
+                // Three-valued logic: FALSE wins over NULL
+                match (left, right) {
+                    (ExprResult::Bool(false), _) | (_, ExprResult::Bool(false)) => {
+                        ExprResult::Bool(false)
+                    }
+                    (ExprResult::Bool(true), ExprResult::Bool(true)) => ExprResult::Bool(true),
+                    _ => ExprResult::Null,
+                }
+            }
+            parser::ast::BinaryOperator::Or => {
+                let left = evaluate_expr(left, row, random)?;
+                let right = evaluate_expr(right, row, random)?;
+
+                // Three-valued logic: TRUE wins over NULL
+                match (left, right) {
+                    (ExprResult::Bool(true), _) | (_, ExprResult::Bool(true)) => {
+                        ExprResult::Bool(true)
+                    }
+                    (ExprResult::Bool(false), ExprResult::Bool(false)) => ExprResult::Bool(false),
+                    _ => ExprResult::Null,
+                }
+            }
+            parser::ast::BinaryOperator::Xor => {
+                let left = evaluate_expr(left, row, random)?;
+                let right = evaluate_expr(right, row, random)?;
+
+                match (left, right) {
+                    (ExprResult::Bool(l), ExprResult::Bool(r)) => ExprResult::Bool(l ^ r),
+                    _ => ExprResult::Null,
+                }
+            }
+            parser::ast::BinaryOperator::JsonExtract => {
+                let left = evaluate_expr(left, row, random)?;
+                let right = evaluate_expr(right, row, random)?;
+
+                match left {
+                    ExprResult::String(document) => json::extract(&document, &right)
+                        .map(json::to_json_result)
+                        .unwrap_or(ExprResult::Null),
+                    _ => ExprResult::Null,
+                }
+            }
+            parser::ast::BinaryOperator::JsonExtractText => {
+                let left = evaluate_expr(left, row, random)?;
+                let right = evaluate_expr(right, row, random)?;
+
+                match left {
+                    ExprResult::String(document) => json::extract(&document, &right)
+                        .map(json::to_sql_result)
+                        .unwrap_or(ExprResult::Null),
+                    _ => ExprResult::Null,
+                }
+            }
+            parser::ast::BinaryOperator::BitwiseOr | parser::ast::BinaryOperator::BitwiseAnd => {
+                return Err(ExecutionError::UnsupportedExpression(expr.to_string()).into())
+            }
+            parser::ast::BinaryOperator::BitwiseXor => {
+                return Err(ExecutionError::UnsupportedExpression(expr.to_string()).into())
+            }
+        }),
+        Expr::Function {
+            name,
+            args,
+            distinct: false,
+        } if is_builtin_function(name) => evaluate_function(expr, name, args, row, random),
+        Expr::Identifier(identifier) => row.resolve(std::slice::from_ref(identifier)),
+        Expr::QualifiedIdentifier(identifiers) => row.resolve(identifiers),
+        Expr::Function { .. } | Expr::Parameter(_) | Expr::Wildcard => {
+            Err(ExecutionError::UnsupportedExpression(expr.to_string()).into())
+        }
+    }
+}
+
+fn evaluate_unary(
+    op: UnaryOperator,
+    expr: &Expr,
+    row: &Row,
+    random: &mut Random,
+) -> Result<ExprResult> {
+    let value = evaluate_expr(expr, row, random)?;
+
+    Ok(match (op, value) {
+        // Three-valued logic: NOT NULL is NULL
+        (UnaryOperator::Not, ExprResult::Bool(b)) => ExprResult::Bool(!b),
+        // Integers are unsigned, so a negative result is a float, as with negative literals
+        (UnaryOperator::Minus, ExprResult::Int(0) | ExprResult::Byte(0)) => ExprResult::Int(0),
+        (UnaryOperator::Minus, ExprResult::Int(x)) => ExprResult::Float(-f64::from(x)),
+        (UnaryOperator::Minus, ExprResult::Byte(x)) => ExprResult::Float(-f64::from(x)),
+        (UnaryOperator::Minus, ExprResult::Float(x)) => ExprResult::Float(-x),
+        (
+            UnaryOperator::Plus,
+            value @ (ExprResult::Int(_) | ExprResult::Byte(_) | ExprResult::Float(_)),
+        ) => value,
+        // Cannot negate strings
+        _ => ExprResult::Null,
+    })
+}
+
+fn evaluate_like(
+    expr: &Expr,
+    pattern: &Expr,
+    negated: bool,
+    row: &Row,
+    random: &mut Random,
+) -> Result<ExprResult> {
+    let expr = evaluate_expr(expr, row, random)?;
+    let pattern = evaluate_expr(pattern, row, random)?;
+
+    Ok(match (expr, pattern) {
+        // Like the comparison operators, NULL never matches
+        (ExprResult::Null, _) | (_, ExprResult::Null) => ExprResult::Bool(false),
+        (ExprResult::String(text), ExprResult::String(pattern)) => {
+            ExprResult::Bool(is_like(&text, &pattern) != negated)
+        }
+        _ => ExprResult::Null,
+    })
+}
+
+/// Match text against a LIKE pattern, where % matches any run of characters (including none)
+/// and _ matches exactly one. Matching is case sensitive.
+fn is_like(text: &str, pattern: &str) -> bool {
+    let text: Vec<char> = text.chars().collect();
+    let pattern: Vec<char> = pattern.chars().collect();
+
+    let (mut t, mut p) = (0, 0);
+    // The most recent %, and the text position it's currently matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('%') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some('_') => {
+                t += 1;
+                p += 1;
+            }
+            Some(c) if *c == text[t] => {
+                t += 1;
+                p += 1;
+            }
+            // Mismatch, so let the last % swallow one more character
+            _ => match backtrack {
+                Some((percent, matched)) => {
+                    backtrack = Some((percent, matched + 1));
+                    t = matched + 1;
+                    p = percent + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '%')
+}
+
+/// Scalar functions which the VM can evaluate.
+const BUILTIN_FUNCTIONS: [&str; 3] = ["RANDOM", "UUID", "NEWID"];
+
+fn is_builtin_function(name: &Identifier) -> bool {
+    BUILTIN_FUNCTIONS
+        .iter()
+        .any(|builtin| builtin.eq_ignore_ascii_case(&name.value))
+}
+
+fn evaluate_function(
+    expr: &Expr,
+    name: &Identifier,
+    args: &[Expr],
+    row: &Row,
+    random: &mut Random,
+) -> Result<ExprResult> {
+    let args = args
+        .iter()
+        .map(|arg| evaluate_expr(arg, row, random))
+        .collect::<Result<Vec<_>>>()?;
+
+    match (name.value.to_uppercase().as_str(), args.as_slice()) {
+        ("RANDOM", []) => Ok(ExprResult::Float(random.next_f64())),
+        // Seeding makes every later RANDOM() and UUID() in the session reproducible
+        ("RANDOM", [ExprResult::Int(seed)]) => {
+            *random = Random::new((*seed).into());
+            Ok(ExprResult::Float(random.next_f64()))
+        }
+        ("UUID" | "NEWID", []) => Ok(ExprResult::Uuid(random.next_uuid())),
+        _ => Err(ExecutionError::InvalidArguments(expr.to_string()).into()),
+    }
+}
+
+/// If one side of a numeric operation is a float, convert the other side to a float too.
+fn promote_numeric(left: ExprResult, right: ExprResult) -> (ExprResult, ExprResult) {
+    match (left, right) {
+        (ExprResult::Float(l), ExprResult::Int(r)) => {
+            (ExprResult::Float(l), ExprResult::Float(r.into()))
+        }
+        (ExprResult::Float(l), ExprResult::Byte(r)) => {
+            (ExprResult::Float(l), ExprResult::Float(r.into()))
+        }
+        (ExprResult::Int(l), ExprResult::Float(r)) => {
+            (ExprResult::Float(l.into()), ExprResult::Float(r))
+        }
+        (ExprResult::Byte(l), ExprResult::Float(r)) => {
+            (ExprResult::Float(l.into()), ExprResult::Float(r))
+        }
+        other => other,
     }
 }
 
@@ -312,6 +710,7 @@ fn evaluate_value(value: &Value) -> ExprResult {
         Value::Number(n) => evaluate_number(n),
         Value::String(s, _quote_type) => ExprResult::String(s.to_string()),
         Value::Boolean(b) => ExprResult::Bool(*b),
+        Value::Blob(b) => ExprResult::Blob(b.clone()),
         Value::Null => ExprResult::Null,
     }
 }
@@ -321,5 +720,438 @@ fn evaluate_number(number: &str) -> ExprResult {
         return ExprResult::Int(parse);
     }
 
+    if let Ok(parse) = number.parse() {
+        return ExprResult::Float(parse);
+    }
+
     ExprResult::Null
 }
+
+#[cfg(test)]
+mod vm_tests {
+    use parser::ast::{
+        BinaryOperator, DataType, Expr, Identifier, QuoteType, SelectExpressionBody, SelectItem,
+        SelectItemList, UnaryOperator, UserStatement, Value,
+    };
+
+    use crate::engine::ExprResult;
+    use crate::random::Random;
+    use crate::vm::{execute_user_statement, is_like, ExecutionError};
+
+    fn evaluate(expr: Expr) -> ExprResult {
+        evaluate_with(expr, &mut Random::new(0))
+    }
+
+    fn evaluate_with(expr: Expr, random: &mut Random) -> ExprResult {
+        let statement = UserStatement::Select(SelectExpressionBody {
+            select_item_list: SelectItemList::from(vec![SelectItem::new(expr)]),
+            from_clause: None,
+            where_clause: None,
+            order_by_clause: None,
+            group_by_clause: None,
+        });
+
+        let result = execute_user_statement(&statement, random).expect("Failed to execute.");
+        result.result_set.columns[0].value.clone()
+    }
+
+    fn boolean(b: bool) -> Box<Expr> {
+        Box::new(Expr::Value(Value::Boolean(b)))
+    }
+
+    fn null() -> Box<Expr> {
+        Box::new(Expr::Value(Value::Null))
+    }
+
+    #[test]
+    fn test_boolean_literal() {
+        assert_eq!(evaluate(*boolean(true)), ExprResult::Bool(true));
+        assert_eq!(evaluate(*boolean(false)), ExprResult::Bool(false));
+    }
+
+    #[test]
+    fn test_is_true_and_is_false() {
+        assert_eq!(
+            evaluate(Expr::IsTrue(boolean(true))),
+            ExprResult::Bool(true)
+        );
+        assert_eq!(
+            evaluate(Expr::IsTrue(boolean(false))),
+            ExprResult::Bool(false)
+        );
+        assert_eq!(evaluate(Expr::IsTrue(null())), ExprResult::Bool(false));
+        assert_eq!(evaluate(Expr::IsNotTrue(null())), ExprResult::Bool(true));
+        assert_eq!(
+            evaluate(Expr::IsFalse(boolean(false))),
+            ExprResult::Bool(true)
+        );
+        assert_eq!(
+            evaluate(Expr::IsNotFalse(boolean(true))),
+            ExprResult::Bool(true)
+        );
+    }
+
+    #[test]
+    fn test_is_null() {
+        assert_eq!(evaluate(Expr::IsNull(null())), ExprResult::Bool(true));
+        assert_eq!(
+            evaluate(Expr::IsNotNull(boolean(true))),
+            ExprResult::Bool(true)
+        );
+    }
+
+    #[test]
+    fn test_boolean_logic_uses_three_valued_logic() {
+        let and = |l, r| Expr::BinaryOperator {
+            left: l,
+            op: BinaryOperator::And,
+            right: r,
+        };
+        let or = |l, r| Expr::BinaryOperator {
+            left: l,
+            op: BinaryOperator::Or,
+            right: r,
+        };
+
+        assert_eq!(
+            evaluate(and(boolean(true), boolean(true))),
+            ExprResult::Bool(true)
+        );
+        assert_eq!(
+            evaluate(and(null(), boolean(false))),
+            ExprResult::Bool(false)
+        );
+        assert_eq!(evaluate(and(null(), boolean(true))), ExprResult::Null);
+        assert_eq!(evaluate(or(null(), boolean(true))), ExprResult::Bool(true));
+        assert_eq!(
+            evaluate(or(boolean(false), boolean(false))),
+            ExprResult::Bool(false)
+        );
+        assert_eq!(evaluate(or(null(), boolean(false))), ExprResult::Null);
+    }
+
+    #[test]
+    fn test_blob_equality() {
+        let blob = |b: Vec<u8>| Box::new(Expr::Value(Value::Blob(b)));
+
+        let equal = Expr::BinaryOperator {
+            left: blob(vec![0xDE, 0xAD]),
+            op: BinaryOperator::Equal,
+            right: blob(vec![0xDE, 0xAD]),
+        };
+        let not_equal = Expr::BinaryOperator {
+            left: blob(vec![0xDE, 0xAD]),
+            op: BinaryOperator::NotEqual,
+            right: blob(vec![0xBE, 0xEF]),
+        };
+
+        assert_eq!(evaluate(equal), ExprResult::Bool(true));
+        assert_eq!(evaluate(not_equal), ExprResult::Bool(true));
+    }
+
+    #[test]
+    fn test_json_extract_operators() {
+        let string =
+            |s: &str| Box::new(Expr::Value(Value::String(s.to_string(), QuoteType::Single)));
+
+        let extract = Expr::BinaryOperator {
+            left: string(r#"{"a": {"b": "c"}}"#),
+            op: BinaryOperator::JsonExtract,
+            right: string("a"),
+        };
+        let extract_text = Expr::BinaryOperator {
+            left: string(r#"{"a": {"b": "c"}}"#),
+            op: BinaryOperator::JsonExtractText,
+            right: string("$.a.b"),
+        };
+
+        assert_eq!(
+            evaluate(extract),
+            ExprResult::String(r#"{"b":"c"}"#.to_string())
+        );
+        assert_eq!(evaluate(extract_text), ExprResult::String("c".to_string()));
+    }
+
+    #[test]
+    fn test_boolean_equality() {
+        let expr = Expr::BinaryOperator {
+            left: boolean(true),
+            op: BinaryOperator::Equal,
+            right: boolean(true),
+        };
+
+        assert_eq!(evaluate(expr), ExprResult::Bool(true));
+    }
+
+    #[test]
+    fn test_float_numbers() {
+        let number = |n: &str| Box::new(Expr::Value(Value::Number(n.to_string())));
+
+        let plus = Expr::BinaryOperator {
+            left: number("1.5"),
+            op: BinaryOperator::Plus,
+            right: number("1"),
+        };
+        let greater_than = Expr::BinaryOperator {
+            left: number("2.5e0"),
+            op: BinaryOperator::GreaterThan,
+            right: number("2"),
+        };
+
+        assert_eq!(evaluate(*number("1.5e3")), ExprResult::Float(1500.0));
+        assert_eq!(evaluate(plus), ExprResult::Float(2.5));
+        assert_eq!(evaluate(greater_than), ExprResult::Bool(true));
+    }
+
+    #[test]
+    fn test_aggregates_are_rejected() {
+        let count = Expr::Function {
+            name: Identifier::from("count".to_string()),
+            args: vec![Expr::Wildcard],
+            distinct: false,
+        };
+
+        let statement = UserStatement::Select(SelectExpressionBody {
+            select_item_list: SelectItemList::from(vec![SelectItem::new(Expr::BinaryOperator {
+                left: Box::new(count),
+                op: BinaryOperator::Plus,
+                right: Box::new(Expr::Value(Value::Number("1".to_string()))),
+            })]),
+            from_clause: None,
+            where_clause: None,
+            order_by_clause: None,
+            group_by_clause: None,
+        });
+
+        let err = execute_user_statement(&statement, &mut Random::new(0)).unwrap_err();
+
+        assert_eq!(
+            err.downcast_ref::<ExecutionError>(),
+            Some(&ExecutionError::UnsupportedAggregate(String::from(
+                "count(*)"
+            )))
+        );
+    }
+
+    fn function(name: &str, args: Vec<Expr>) -> Expr {
+        Expr::Function {
+            name: Identifier::from(name.to_string()),
+            args,
+            distinct: false,
+        }
+    }
+
+    #[test]
+    fn test_seeded_random_is_reproducible() {
+        let seed = || vec![Expr::Value(Value::Number("42".to_string()))];
+
+        let mut random = Random::from_time();
+        let first = evaluate_with(function("RANDOM", seed()), &mut random);
+        let second = evaluate_with(function("random", vec![]), &mut random);
+
+        assert_eq!(
+            evaluate_with(function("RANDOM", seed()), &mut random),
+            first
+        );
+        assert_eq!(
+            evaluate_with(function("random", vec![]), &mut random),
+            second
+        );
+        assert_ne!(first, second);
+
+        let ExprResult::Float(x) = first else {
+            panic!("RANDOM returned {first:?}");
+        };
+        assert!((0.0..1.0).contains(&x));
+    }
+
+    #[test]
+    fn test_uuid_functions() {
+        let mut random = Random::from_time();
+        let ExprResult::Uuid(uuid) = evaluate_with(function("UUID", vec![]), &mut random) else {
+            panic!("UUID didn't return a UUID");
+        };
+
+        assert_eq!(uuid.get_version_num(), 4);
+        assert_ne!(
+            evaluate_with(function("NEWID", vec![]), &mut random),
+            ExprResult::Uuid(uuid)
+        );
+    }
+
+    #[test]
+    fn test_invalid_function_arguments_are_rejected() {
+        let statement = UserStatement::Select(SelectExpressionBody {
+            select_item_list: SelectItemList::from(vec![SelectItem::new(function(
+                "UUID",
+                vec![Expr::Value(Value::Number("1".to_string()))],
+            ))]),
+            from_clause: None,
+            where_clause: None,
+            order_by_clause: None,
+            group_by_clause: None,
+        });
+
+        let err = execute_user_statement(&statement, &mut Random::new(0)).unwrap_err();
+
+        assert_eq!(
+            err.downcast_ref::<ExecutionError>(),
+            Some(&ExecutionError::InvalidArguments(String::from("UUID(1)")))
+        );
+    }
+
+    #[test]
+    fn test_is_like() {
+        let cases = [
+            ("abc", "abc", true),
+            ("abc", "ABC", false),
+            ("abc", "a%", true),
+            ("abc", "%c", true),
+            ("abc", "%b%", true),
+            ("abc", "a_c", true),
+            ("abc", "a_", false),
+            ("", "%", true),
+            ("", "_", false),
+            ("aXbXc", "a%b%c", true),
+            ("ab", "a%b%c", false),
+            ("mississippi", "%ss%ppi", true),
+            ("héllo", "h_llo", true),
+        ];
+
+        for (text, pattern, expected) in cases {
+            assert_eq!(is_like(text, pattern), expected, "{text} LIKE {pattern}");
+        }
+    }
+
+    #[test]
+    fn test_like_evaluation() {
+        let string =
+            |s: &str| Box::new(Expr::Value(Value::String(s.to_string(), QuoteType::Single)));
+
+        assert_eq!(
+            evaluate(Expr::Like {
+                expr: string("WackDB"),
+                pattern: string("Wack%"),
+            }),
+            ExprResult::Bool(true)
+        );
+        assert_eq!(
+            evaluate(Expr::NotLike {
+                expr: string("WackDB"),
+                pattern: string("Wack%"),
+            }),
+            ExprResult::Bool(false)
+        );
+        assert_eq!(
+            evaluate(Expr::NotLike {
+                expr: null(),
+                pattern: string("%"),
+            }),
+            ExprResult::Bool(false)
+        );
+    }
+
+    #[test]
+    fn test_unary_operators() {
+        let unary = |op, expr: Box<Expr>| Expr::UnaryOperator { op, expr };
+        let number = |n: &str| Box::new(Expr::Value(Value::Number(n.to_string())));
+
+        assert_eq!(
+            evaluate(unary(UnaryOperator::Not, boolean(true))),
+            ExprResult::Bool(false)
+        );
+        assert_eq!(
+            evaluate(unary(UnaryOperator::Not, null())),
+            ExprResult::Null
+        );
+        assert_eq!(
+            evaluate(unary(UnaryOperator::Minus, number("3"))),
+            ExprResult::Float(-3.0)
+        );
+        assert_eq!(
+            evaluate(unary(UnaryOperator::Minus, number("-1.5"))),
+            ExprResult::Float(1.5)
+        );
+        assert_eq!(
+            evaluate(unary(UnaryOperator::Minus, number("0"))),
+            ExprResult::Int(0)
+        );
+        assert_eq!(
+            evaluate(unary(UnaryOperator::Plus, number("4"))),
+            ExprResult::Int(4)
+        );
+        assert_eq!(
+            evaluate(unary(
+                UnaryOperator::Minus,
+                Box::new(Expr::Value(Value::String(
+                    "a".to_string(),
+                    QuoteType::Single
+                )))
+            )),
+            ExprResult::Null
+        );
+    }
+
+    #[test]
+    fn test_cast() {
+        let cast = |value: &str, data_type| Expr::Cast {
+            expr: Box::new(Expr::Value(Value::String(
+                value.to_string(),
+                QuoteType::Single,
+            ))),
+            data_type,
+        };
+
+        assert_eq!(evaluate(cast("12", DataType::Int)), ExprResult::Int(12));
+
+        let statement = UserStatement::Select(SelectExpressionBody {
+            select_item_list: SelectItemList::from(vec![SelectItem::new(cast("x", DataType::Int))]),
+            from_clause: None,
+            where_clause: None,
+            order_by_clause: None,
+            group_by_clause: None,
+        });
+
+        let err = execute_user_statement(&statement, &mut Random::new(0)).unwrap_err();
+
+        assert_eq!(
+            err.downcast_ref::<ExecutionError>(),
+            Some(&ExecutionError::InvalidCast(
+                String::from("x"),
+                String::from("INT")
+            ))
+        );
+    }
+
+    #[test]
+    fn test_subqueries_are_rejected() {
+        let subquery = SelectExpressionBody {
+            select_item_list: SelectItemList::from(vec![SelectItem::new(Expr::Value(
+                Value::Number("1".to_string()),
+            ))]),
+            from_clause: None,
+            where_clause: None,
+            order_by_clause: None,
+            group_by_clause: None,
+        };
+
+        let statement = UserStatement::Select(SelectExpressionBody {
+            select_item_list: SelectItemList::from(vec![SelectItem::new(Expr::Exists(Box::new(
+                subquery,
+            )))]),
+            from_clause: None,
+            where_clause: None,
+            order_by_clause: None,
+            group_by_clause: None,
+        });
+
+        let err = execute_user_statement(&statement, &mut Random::new(0)).unwrap_err();
+
+        assert_eq!(
+            err.downcast_ref::<ExecutionError>(),
+            Some(&ExecutionError::UnsupportedSubquery(String::from(
+                "EXISTS (SELECT [1] )"
+            )))
+        );
+    }
+}
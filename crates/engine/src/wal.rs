@@ -0,0 +1,313 @@
+use deku::prelude::*;
+use thiserror::Error;
+
+#[derive(Debug, PartialEq, Error)]
+pub enum WalError {
+    #[error("A log record payload of {0} bytes doesn't fit in a record")]
+    PayloadTooLarge(usize),
+    #[error("A checkpoint of {0} dirty pages doesn't fit in a record")]
+    TooManyDirtyPages(usize),
+    #[error("Failed to serialise: {0}")]
+    FailedToSerialise(DekuError),
+}
+
+/// The number of bytes in front of every record's payload.
+pub const LOG_RECORD_HEADER_SIZE_BYTES: usize = 16;
+
+/// Frames every record in the Log File.
+/// Allocated length: 16 bytes.
+#[derive(DekuRead, DekuWrite, Debug, PartialEq)]
+#[deku(endian = "big")]
+struct LogRecordHeader {
+    #[deku(bytes = 4)]
+    length: u32, // Payload length, excluding this header
+
+    #[deku(bytes = 8)]
+    lsn: u64,
+
+    #[deku(bytes = 4)]
+    checksum: u32, // Covers the length, LSN and payload
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct LogRecord {
+    pub lsn: u64,
+    pub payload: Vec<u8>,
+}
+
+/// A page which had changes not yet on disk when a checkpoint was taken.
+#[derive(DekuRead, DekuWrite, Debug, PartialEq, Clone)]
+#[deku(endian = "endian", ctx = "endian: deku::ctx::Endian")]
+pub struct DirtyPage {
+    pub db_id: u16,
+    pub page_index: u32,
+    /// The LSN of the oldest change to the page that isn't on disk. Replaying the page starts here.
+    pub rec_lsn: u64,
+}
+
+/// The payload of a fuzzy checkpoint record: the dirty page table as it was when the checkpoint was taken.
+/// Pages aren't flushed first, so writers carry on while it's taken, but recovery only has to
+/// replay from the oldest change in the table rather than from the start of the log.
+#[derive(DekuRead, DekuWrite, Debug, PartialEq)]
+#[deku(endian = "big")]
+pub struct Checkpoint {
+    #[deku(update = "self.dirty_pages.len()")]
+    count: u32,
+    #[deku(count = "count")]
+    pub dirty_pages: Vec<DirtyPage>,
+}
+
+impl Checkpoint {
+    pub fn new(dirty_pages: Vec<DirtyPage>) -> Self {
+        Checkpoint {
+            // A count that doesn't fit is rejected by to_payload
+            count: u32::try_from(dirty_pages.len()).unwrap_or(u32::MAX),
+            dirty_pages,
+        }
+    }
+
+    pub fn to_payload(&self) -> Result<Vec<u8>, WalError> {
+        let count = self.dirty_pages.len();
+
+        if u32::try_from(count).is_err() {
+            return Err(WalError::TooManyDirtyPages(count));
+        }
+
+        self.to_bytes().map_err(WalError::FailedToSerialise)
+    }
+
+    /// Returns None if the payload isn't a whole checkpoint.
+    pub fn from_payload(payload: &[u8]) -> Option<Self> {
+        match Checkpoint::from_bytes((payload, 0)) {
+            Ok(((&[], _), checkpoint)) => Some(checkpoint),
+            _ => None,
+        }
+    }
+
+    /// Where recovery has to start replaying the log, given the LSN of this checkpoint's own record.
+    /// Every change before it is already on disk.
+    pub fn redo_lsn(&self, checkpoint_lsn: u64) -> u64 {
+        self.dirty_pages
+            .iter()
+            .map(|page| page.rec_lsn)
+            .min()
+            .map_or(checkpoint_lsn, |lsn| lsn.min(checkpoint_lsn))
+    }
+}
+
+/// Why reading the log stopped before the end of the input.
+#[derive(Debug, PartialEq)]
+pub enum LogReadStop {
+    /// The record at this offset runs past the end of the log, i.e. a partial write.
+    Truncated(usize),
+    /// The record at this offset doesn't match its checksum.
+    ChecksumMismatch(usize),
+    /// The record at this offset doesn't have a higher LSN than the one before it.
+    OutOfSequence(usize),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct LogReadResult {
+    /// Every valid record, up to the first bad one.
+    pub records: Vec<LogRecord>,
+    /// Set if a bad record was found. Nothing at or after it should be replayed.
+    pub stop: Option<LogReadStop>,
+}
+
+#[allow(dead_code)] // Nothing appends to the log yet
+impl LogRecord {
+    pub fn new(lsn: u64, payload: Vec<u8>) -> Self {
+        LogRecord { lsn, payload }
+    }
+
+    /// Frame the record, ready to be appended to the log. Fails if the payload is too long for its length field.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, WalError> {
+        let length = u32::try_from(self.payload.len())
+            .map_err(|_| WalError::PayloadTooLarge(self.payload.len()))?;
+
+        let header = LogRecordHeader {
+            length,
+            lsn: self.lsn,
+            checksum: check(length, self.lsn, &self.payload),
+        };
+
+        let mut bytes = header.to_bytes().map_err(WalError::FailedToSerialise)?;
+        bytes.extend_from_slice(&self.payload);
+
+        Ok(bytes)
+    }
+}
+
+/// Read records from the start of a log, stopping at the first record that's
+/// incomplete, corrupt or out of sequence.
+#[allow(dead_code)] // Nothing replays the log yet
+pub fn read_log_records(bytes: &[u8]) -> LogReadResult {
+    let mut records: Vec<LogRecord> = vec![];
+    let mut offset = 0;
+
+    let stop = loop {
+        if offset == bytes.len() {
+            break None;
+        }
+
+        let header_end = offset + LOG_RECORD_HEADER_SIZE_BYTES;
+
+        if header_end > bytes.len() {
+            break Some(LogReadStop::Truncated(offset));
+        }
+
+        let header = match LogRecordHeader::from_bytes((&bytes[offset..header_end], 0)) {
+            Ok((_, header)) => header,
+            Err(_) => break Some(LogReadStop::Truncated(offset)),
+        };
+
+        let payload_end = header_end + header.length as usize;
+
+        if payload_end > bytes.len() {
+            break Some(LogReadStop::Truncated(offset));
+        }
+
+        let payload = &bytes[header_end..payload_end];
+
+        if check(header.length, header.lsn, payload) != header.checksum {
+            break Some(LogReadStop::ChecksumMismatch(offset));
+        }
+
+        if records.last().is_some_and(|prev| prev.lsn >= header.lsn) {
+            break Some(LogReadStop::OutOfSequence(offset));
+        }
+
+        records.push(LogRecord::new(header.lsn, payload.to_vec()));
+        offset = payload_end;
+    };
+
+    LogReadResult { records, stop }
+}
+
+fn check(length: u32, lsn: u64, payload: &[u8]) -> u32 {
+    let crc = crc::Crc::<u32>::new(&crc::CRC_32_ISCSI);
+    let mut digest = crc.digest();
+
+    digest.update(&length.to_be_bytes());
+    digest.update(&lsn.to_be_bytes());
+    digest.update(payload);
+
+    digest.finalize()
+}
+
+#[cfg(test)]
+mod wal_tests {
+    use crate::wal::{
+        read_log_records, Checkpoint, DirtyPage, LogReadStop, LogRecord,
+        LOG_RECORD_HEADER_SIZE_BYTES,
+    };
+
+    fn log_of(records: &[LogRecord]) -> Vec<u8> {
+        records
+            .iter()
+            .flat_map(|record| record.to_bytes().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_log_record_golden_bytes() {
+        let bytes = LogRecord::new(0x0102, vec![0xAB]).to_bytes().unwrap();
+
+        assert_eq!(bytes.len(), LOG_RECORD_HEADER_SIZE_BYTES + 1);
+        assert_eq!(bytes[0..4], [0, 0, 0, 1]); // Length
+        assert_eq!(bytes[4..12], [0, 0, 0, 0, 0, 0, 0x01, 0x02]); // LSN
+        assert_eq!(bytes[16], 0xAB); // Payload
+    }
+
+    #[test]
+    fn test_read_log_records_round_trip() {
+        let records = vec![
+            LogRecord::new(1, vec![1, 2, 3]),
+            LogRecord::new(2, vec![]),
+            LogRecord::new(5, vec![4]),
+        ];
+
+        let actual = read_log_records(&log_of(&records));
+
+        assert_eq!(actual.records, records);
+        assert_eq!(actual.stop, None);
+    }
+
+    #[test]
+    fn test_read_log_records_stops_at_partial_write() {
+        let records = vec![LogRecord::new(1, vec![1, 2]), LogRecord::new(2, vec![3, 4])];
+        let mut bytes = log_of(&records);
+        bytes.pop();
+
+        let actual = read_log_records(&bytes);
+
+        assert_eq!(actual.records, records[0..1]);
+        assert_eq!(actual.stop, Some(LogReadStop::Truncated(18)));
+    }
+
+    #[test]
+    fn test_read_log_records_stops_at_corrupt_record() {
+        let records = vec![
+            LogRecord::new(1, vec![1]),
+            LogRecord::new(2, vec![2]),
+            LogRecord::new(3, vec![3]),
+        ];
+        let mut bytes = log_of(&records);
+        bytes[33] ^= 0xFF; // Payload of the second record
+
+        let actual = read_log_records(&bytes);
+
+        assert_eq!(actual.records, records[0..1]);
+        assert_eq!(actual.stop, Some(LogReadStop::ChecksumMismatch(17)));
+    }
+
+    #[test]
+    fn test_read_log_records_stops_at_out_of_sequence_record() {
+        let records = vec![LogRecord::new(2, vec![]), LogRecord::new(2, vec![])];
+
+        let actual = read_log_records(&log_of(&records));
+
+        assert_eq!(actual.records, records[0..1]);
+        assert_eq!(actual.stop, Some(LogReadStop::OutOfSequence(16)));
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let checkpoint = Checkpoint::new(vec![
+            DirtyPage {
+                db_id: 1,
+                page_index: 7,
+                rec_lsn: 12,
+            },
+            DirtyPage {
+                db_id: 2,
+                page_index: 0,
+                rec_lsn: 9,
+            },
+        ]);
+
+        let payload = checkpoint.to_payload().unwrap();
+
+        assert_eq!(payload.len(), 4 + 2 * 14);
+        assert_eq!(Checkpoint::from_payload(&payload), Some(checkpoint));
+        assert_eq!(
+            Checkpoint::from_payload(&payload[..payload.len() - 1]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_redo_lsn() {
+        let dirty_page = |rec_lsn| DirtyPage {
+            db_id: 1,
+            page_index: 1,
+            rec_lsn,
+        };
+
+        assert_eq!(
+            Checkpoint::new(vec![dirty_page(12), dirty_page(9)]).redo_lsn(20),
+            9
+        );
+        assert_eq!(Checkpoint::new(vec![]).redo_lsn(20), 20);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::token::{Ident, LocatableToken, Token, Value};
+use crate::Lexer;
+
+/// Normalise a statement to its shape, so statements which only differ in their literal values,
+/// spacing, comments or the case of their names give the same text. For example, both
+///     select Name from Users where Id = 1
+///     SELECT name FROM users -- by id
+///     WHERE id = 42;
+/// normalise to `SELECT name FROM users WHERE id = ?`, as does `SELECT [Name] FROM "Users" ...`.
+/// Parameters are replaced too, so a prepared statement matches its literal form.
+pub fn normalize(sql: &str) -> String {
+    let sql = sql.to_string();
+    let tokens = Lexer::new(&sql).lex().tokens;
+
+    let mut significant: Vec<&LocatableToken> = tokens
+        .iter()
+        .filter(|t| !matches!(t.token, Token::Space | Token::NewLine | Token::Comment(_)))
+        .filter(|t| t.token != Token::EOF)
+        .collect();
+
+    while significant
+        .last()
+        .is_some_and(|t| t.token == Token::Semicolon)
+    {
+        significant.pop();
+    }
+
+    let mut normalized = String::new();
+    let mut previous = None;
+
+    for token in significant {
+        let needs_space = match (previous, token.token) {
+            (None, _) => false,
+            (Some(Token::Dot | Token::ParenOpen), _) => false,
+            (_, Token::Dot | Token::Comma | Token::ParenClose | Token::Semicolon) => false,
+            // A function call, such as COUNT(*)
+            (Some(Token::Identifier(_)), Token::ParenOpen) => false,
+            _ => true,
+        };
+
+        if needs_space {
+            normalized.push(' ');
+        }
+
+        let text = &sql[token.span.start..token.span.end];
+
+        match token.token {
+            Token::Numeric(_)
+            | Token::RadixNumeric(..)
+            | Token::Value(Value::SingleQuoted(_) | Value::Hex(_) | Value::Raw(_))
+            | Token::Parameter(_) => normalized.push('?'),
+            // Names aren't case sensitive, and their [] or "" delimiters don't change them
+            Token::Identifier(Ident { value: name }) | Token::Value(Value::DoubleQuoted(name)) => {
+                normalized.push_str(&sql[name.start..name.end].to_lowercase())
+            }
+            _ => normalized.push_str(&text.to_uppercase()),
+        }
+
+        previous = Some(token.token);
+    }
+
+    normalized
+}
+
+/// A stable ID for a statement's shape: the 64-bit FNV-1a hash of its normalised text.
+/// Statements with the same shape share an ID, so they can be counted and timed together.
+pub fn query_id(sql: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    normalize(sql).bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod fingerprint_tests {
+    use crate::fingerprint::{normalize, query_id};
+
+    #[test]
+    fn test_normalize() {
+        let cases = [
+            (
+                "select Name from Users where Id = 1",
+                "SELECT name FROM users WHERE id = ?",
+            ),
+            (
+                "SELECT  u.Name, COUNT(*)\nFROM [Users] u -- all of them\nWHERE u.Id IN (1, 0x2) AND Tag = 'x';",
+                "SELECT u.name, count(*) FROM users u WHERE u.id IN (?, ?) AND tag = ?",
+            ),
+            ("SELECT ?, $1, :name, X'BEEF', NULL", "SELECT ?, ?, ?, ?, NULL"),
+        ];
+
+        for (sql, expected) in cases {
+            assert_eq!(normalize(sql), expected, "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_query_id_groups_statements_by_shape() {
+        let id = query_id("SELECT Name FROM Users WHERE Id = 1");
+
+        assert_eq!(id, query_id("select name\nfrom users where id = 42;"));
+        assert_eq!(id, query_id("SELECT Name FROM Users WHERE Id = ?"));
+        assert_eq!(id, query_id("SELECT [Name] FROM \"users\" WHERE [ID] = 7"));
+        assert_eq!(query_id("SELECT [Users].Id"), query_id("SELECT Users.Id"));
+        assert_ne!(id, query_id("SELECT Name FROM Users WHERE Age = 1"));
+        assert_ne!(id, query_id("SELECT Name FROM Orders WHERE Id = 1"));
+    }
+}
@@ -1,291 +1,442 @@
 use token::*;
+pub mod fingerprint;
+pub mod statements;
 pub mod token;
 
 pub struct Lexer<'a> {
     buf: &'a String,
-    chars: Vec<(usize, char)>,
-    len: usize,
-    pos: usize,
+    chars: Vec<(usize, char)>, // Byte offset and char, for each char in the buffer
+    len: usize,                // Number of chars, not bytes
+    pos: usize,                // Index into chars
+    errors: Vec<LexError>,
+    finished: bool,
 }
 
 pub struct LexResult<'a> {
     pub tokens: Vec<LocatableToken>,
+    pub errors: Vec<LexError>,
     pub buf: &'a String,
 }
 
+/// Input the lexer couldn't make sense of. Emitted alongside a `Token::Unknown`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LexError {
+    pub position: usize, // Byte offset of the start of the offending text
+    pub text: String,
+}
+
+/// A lazy stream of tokens, created by `Lexer::iter`.
+pub struct Tokens<'l, 'a> {
+    lexer: &'l mut Lexer<'a>,
+}
+
+impl Iterator for Tokens<'_, '_> {
+    type Item = LocatableToken;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lexer.next_token()
+    }
+}
+
 impl<'a> Lexer<'a> {
     pub fn new(buf: &'a String) -> Lexer<'a> {
-        let len = buf.len();
-        let chars = buf.char_indices().collect();
+        let chars: Vec<(usize, char)> = buf.char_indices().collect();
+        let len = chars.len();
 
         Lexer {
             buf,
             chars,
             len,
             pos: 0,
+            errors: vec![],
+            finished: false,
         }
     }
 
     pub fn lex(mut self) -> LexResult<'a> {
-        let mut tokens = Vec::new();
-        let mut prev_index = self.pos;
+        let tokens = self.iter().collect();
 
-        loop {
-            if self.pos >= self.len {
-                tokens.push(LocatableToken::at_position(Token::EOF, self.len));
-                break;
-            }
+        LexResult {
+            buf: self.buf,
+            tokens,
+            errors: self.errors,
+        }
+    }
 
-            let (curr_offset, curr_ch) = self.chars[self.pos];
+    /// Lex the input lazily, one token at a time. The last token is always `Token::EOF`.
+    /// Errors for any unknown tokens are available from `errors` as lexing progresses.
+    pub fn iter(&mut self) -> Tokens<'_, 'a> {
+        Tokens { lexer: self }
+    }
 
-            let token = match curr_ch {
-                // Single-quote String
-                '\'' => {
-                    let end_pos = self.scan_to(curr_offset + 1, '\'') + 1;
+    /// Errors found in the input lexed so far.
+    pub fn errors(&self) -> &[LexError] {
+        &self.errors
+    }
 
-                    let slice = &self.buf[curr_offset..end_pos];
+    fn next_token(&mut self) -> Option<LocatableToken> {
+        if self.finished {
+            return None;
+        }
 
-                    self.pos += slice.len();
+        if self.pos >= self.len {
+            self.finished = true;
+            let end = self.buf.len();
+            return Some(LocatableToken::new(Token::EOF, Slice::new(end, end)));
+        }
 
+        let prev_index = self.pos;
+
+        let (curr_offset, curr_ch) = self.chars[self.pos];
+
+        let token = match curr_ch {
+            // Single-quote String
+            '\'' => {
+                let end = self.scan_to(self.pos + 1, '\'');
+
+                if end >= self.len {
+                    self.recover()
+                } else {
+                    self.pos = end + 1;
                     Token::Value(Value::SingleQuoted(Slice::new(
                         curr_offset + 1,
-                        end_pos - 1,
+                        self.offset_at(end),
                     )))
                 }
-                // Space
-                ' ' => {
-                    self.pos += 1;
-                    Token::Space
+            }
+            // Double-quoted, delimited identifier, e.g. "My Table"
+            '"' => {
+                let mut end = self.scan_to(self.pos + 1, '"');
+                let mut escaped = false;
+
+                // Quotes escaped by doubling, e.g. "a""b", aren't supported, so the whole
+                // identifier is reported rather than ending at the first quote
+                while end + 1 < self.len && self.chars[end + 1].1 == '"' {
+                    escaped = true;
+                    end = self.scan_to(end + 2, '"');
                 }
-                // NewLine
-                '\n' | '\r' => {
-                    self.pos += 1;
-                    Token::NewLine
+
+                if end >= self.len {
+                    self.recover()
+                } else if escaped {
+                    self.pos = end + 1;
+                    Token::Unknown
+                } else {
+                    self.pos = end + 1;
+                    Token::Value(Value::DoubleQuoted(Slice::new(
+                        curr_offset + 1,
+                        self.offset_at(end),
+                    )))
                 }
-                //Dot - only if the next char isn't numeric
-                '.' if self.pos + 1 < self.len && !self.chars[self.pos + 1].1.is_numeric() => {
-                    self.pos += 1;
-                    Token::Dot
+            }
+            // Hex string, e.g. X'DEADBEEF'
+            'x' | 'X' if self.pos + 1 < self.len && self.chars[self.pos + 1].1 == '\'' => {
+                let end = self.scan_to(self.pos + 2, '\'');
+
+                if end >= self.len {
+                    self.recover()
+                } else {
+                    self.pos = end + 1;
+                    Token::Value(Value::Hex(Slice::new(curr_offset + 2, self.offset_at(end))))
                 }
-                // Comment, double dashed
-                '-' if self.pos + 1 < self.len && self.chars[self.pos + 1].1 == '-' => {
-                    let end_pos = self.scan_until(curr_offset, |c| c == '\r' || c == '\n');
+            }
+            // Space
+            ' ' => {
+                self.pos += 1;
+                Token::Space
+            }
+            // NewLine
+            '\n' | '\r' => {
+                self.pos += 1;
+                Token::NewLine
+            }
+            // Any other whitespace, such as a tab, separates tokens like a space
+            c if c.is_whitespace() => {
+                self.pos += 1;
+                Token::Space
+            }
+            //Dot - only if the next char isn't numeric
+            '.' if self.pos + 1 < self.len && !self.chars[self.pos + 1].1.is_numeric() => {
+                self.pos += 1;
+                Token::Dot
+            }
+            // Comment, double dashed
+            '-' if self.pos + 1 < self.len && self.chars[self.pos + 1].1 == '-' => {
+                let end = self.scan_until(self.pos, |c| c == '\r' || c == '\n');
+                let end_pos = self.offset_at(end);
+                self.pos = end;
 
-                    let slice = &self.buf[curr_offset..end_pos];
-                    self.pos += slice.len();
+                Token::Comment(Slice::new(curr_offset, end_pos))
+            }
+            //Comma
+            ',' => {
+                self.pos += 1;
+                Token::Comma
+            }
+            //ParenOpen
+            '(' => {
+                self.pos += 1;
+                Token::ParenOpen
+            }
+            //ParenClose
+            ')' => {
+                self.pos += 1;
+                Token::ParenClose
+            }
+            // Bracket delimited identifier, e.g. [My Table]. Empty or unclosed brackets aren't identifiers.
+            '[' if self.pos + 1 < self.len
+                && self.chars[self.pos + 1].1 != ']'
+                && self.scan_to(self.pos + 1, ']') < self.len =>
+            {
+                let end = self.scan_to(self.pos + 1, ']');
+                self.pos = end + 1;
+
+                Token::Identifier(Ident::new(Slice::new(curr_offset + 1, self.offset_at(end))))
+            }
+            //SquareOpen
+            '[' => {
+                self.pos += 1;
+                Token::SquareOpen
+            }
+            //SquareClose
+            ']' => {
+                self.pos += 1;
+                Token::SquareClose
+            }
+            //SquiglyOpen
+            '{' => {
+                self.pos += 1;
+                Token::SquiglyOpen
+            }
+            //SquiglyClose
+            '}' => {
+                self.pos += 1;
+                Token::SquiglyClose
+            }
+            // Parameter placeholders, e.g. ?, $1 and :name
+            '?' => {
+                self.pos += 1;
+                Token::Parameter(Parameter::Anonymous)
+            }
+            '$' if self.pos + 1 < self.len && self.chars[self.pos + 1].1.is_ascii_digit() => {
+                let end = self.scan_until(self.pos + 1, |c| !c.is_ascii_digit());
+                let end_pos = self.offset_at(end);
+                self.pos = end;
 
-                    Token::Comment(Slice::new(curr_offset, end_pos))
-                }
-                //Comma
-                ',' => {
-                    self.pos += 1;
-                    Token::Comma
-                }
-                //ParenOpen
-                '(' => {
-                    self.pos += 1;
-                    Token::ParenOpen
-                }
-                //ParenClose
-                ')' => {
-                    self.pos += 1;
-                    Token::ParenClose
-                }
-                //SquareOpen
-                '[' => {
-                    self.pos += 1;
-                    Token::SquareOpen
-                }
-                //SquareClose
-                ']' => {
-                    self.pos += 1;
-                    Token::SquareClose
-                }
-                //SquiglyOpen
-                '{' => {
-                    self.pos += 1;
-                    Token::SquiglyOpen
-                }
-                //SquiglyClose
-                '}' => {
-                    self.pos += 1;
-                    Token::SquiglyClose
-                }
-                //Colon
-                ':' => {
-                    self.pos += 1;
-                    Token::Colon
-                }
-                //Semicolon
-                ';' => {
-                    self.pos += 1;
-                    Token::Semicolon
-                }
-                // Arithmetic
-                '*' => {
-                    self.pos += 1;
-                    Token::Arithmetic(Arithmetic::Multiply)
-                }
-                '/' => {
-                    self.pos += 1;
-                    Token::Arithmetic(Arithmetic::Divide)
-                }
-                '%' => {
-                    self.pos += 1;
-                    Token::Arithmetic(Arithmetic::Modulo)
-                }
-                '+' => {
-                    self.pos += 1;
-                    Token::Arithmetic(Arithmetic::Plus)
-                }
-                // Comparison and Bitwise
-                '|' => {
-                    self.pos += 1;
-                    Token::Bitwise(Bitwise::Or)
-                }
-                '&' => {
-                    self.pos += 1;
-                    Token::Bitwise(Bitwise::And)
+                Token::Parameter(Parameter::Positional(Slice::new(curr_offset + 1, end_pos)))
+            }
+            ':' if self.pos + 1 < self.len
+                && (self.chars[self.pos + 1].1.is_alphabetic()
+                    || self.chars[self.pos + 1].1 == '_') =>
+            {
+                let end = self.scan_until(self.pos + 1, |c| !(c.is_alphanumeric() || c == '_'));
+                let end_pos = self.offset_at(end);
+                self.pos = end;
+
+                Token::Parameter(Parameter::Named(Slice::new(curr_offset + 1, end_pos)))
+            }
+            //Colon
+            ':' => {
+                self.pos += 1;
+                Token::Colon
+            }
+            //Semicolon
+            ';' => {
+                self.pos += 1;
+                Token::Semicolon
+            }
+            // Arithmetic
+            '*' => {
+                self.pos += 1;
+                Token::Arithmetic(Arithmetic::Multiply)
+            }
+            // Comment, block. Block comments can be nested, e.g. /* a /* b */ c */
+            '/' if self.pos + 1 < self.len && self.chars[self.pos + 1].1 == '*' => {
+                match self.scan_block_comment(self.pos) {
+                    Some(end) => {
+                        let end_pos = self.offset_at(end);
+                        self.pos = end;
+
+                        Token::Comment(Slice::new(curr_offset, end_pos))
+                    }
+                    // Unterminated, so the rest of the input is part of the comment.
+                    None => {
+                        self.pos = self.len;
+                        Token::Unknown
+                    }
                 }
-                '^' => {
-                    self.pos += 1;
-                    Token::Bitwise(Bitwise::Xor)
+            }
+            '/' => {
+                self.pos += 1;
+                Token::Arithmetic(Arithmetic::Divide)
+            }
+            '%' => {
+                self.pos += 1;
+                Token::Arithmetic(Arithmetic::Modulo)
+            }
+            '+' => {
+                self.pos += 1;
+                Token::Arithmetic(Arithmetic::Plus)
+            }
+            // Comparison and Bitwise
+            '|' => {
+                self.pos += 1;
+                Token::Bitwise(Bitwise::Or)
+            }
+            '&' => {
+                self.pos += 1;
+                Token::Bitwise(Bitwise::And)
+            }
+            '^' => {
+                self.pos += 1;
+                Token::Bitwise(Bitwise::Xor)
+            }
+            '=' | '!' | '>' | '<' => {
+                let end =
+                    self.scan_until(self.pos, |c| c != '=' && c != '!' && c != '>' && c != '<');
+
+                let slice = &self.buf[curr_offset..self.offset_at(end)];
+                self.pos = end;
+
+                match slice {
+                    ">=" => Token::Comparison(Comparison::GreaterThanOrEqual),
+                    "<=" => Token::Comparison(Comparison::LessThanOrEqual),
+                    "<>" => Token::Comparison(Comparison::NotEqual),
+                    ">" => Token::Comparison(Comparison::GreaterThan),
+                    "<" => Token::Comparison(Comparison::LessThan),
+                    "==" => Token::Comparison(Comparison::Equal2),
+                    "=" => Token::Comparison(Comparison::Equal),
+                    ">>" => Token::Bitwise(Bitwise::RightShift),
+                    "<<" => Token::Bitwise(Bitwise::LeftShift),
+                    _ => Token::Unknown,
                 }
-                '=' | '!' | '>' | '<' => {
-                    let end_pos = self.scan_until(curr_offset, |c| {
-                        c != '=' && c != '!' && c != '>' && c != '<'
-                    });
-
-                    let slice = &self.buf[curr_offset..end_pos];
-                    self.pos += slice.len();
-
-                    match slice {
-                        ">=" => Token::Comparison(Comparison::GreaterThanOrEqual),
-                        "<=" => Token::Comparison(Comparison::LessThanOrEqual),
-                        "<>" => Token::Comparison(Comparison::NotEqual),
-                        ">" => Token::Comparison(Comparison::GreaterThan),
-                        "<" => Token::Comparison(Comparison::LessThan),
-                        "==" => Token::Comparison(Comparison::Equal2),
-                        "=" => Token::Comparison(Comparison::Equal),
-                        ">>" => Token::Bitwise(Bitwise::RightShift),
-                        "<<" => Token::Bitwise(Bitwise::LeftShift),
-                        _ => break,
-                    }
+            }
+            // JSON extraction operators, -> and ->>
+            '-' if self.pos + 1 < self.len && self.chars[self.pos + 1].1 == '>' => {
+                if self.pos + 2 < self.len && self.chars[self.pos + 2].1 == '>' {
+                    self.pos += 3;
+                    Token::DoubleArrow
+                } else {
+                    self.pos += 2;
+                    Token::Arrow
                 }
-                // Only include minus if the next char isn't a number
-                '-' if !(self.pos + 1 < self.len && self.chars[self.pos + 1].1.is_numeric()) => {
-                    self.pos += 1;
-                    Token::Arithmetic(Arithmetic::Minus)
+            }
+            // A minus before a number is an operator too, so 5-3 is a subtraction.
+            // Negative numbers are parsed as a unary minus.
+            '-' => {
+                self.pos += 1;
+                Token::Arithmetic(Arithmetic::Minus)
+            }
+            // Alphabetical (can start with _, # or @)
+            c if c.is_alphabetic() || c == '_' || c == '#' || c == '@' => {
+                let end = self.scan_until(self.pos, |c| {
+                    !(c.is_alphanumeric() || c == '_' || c == '#' || c == '@' || c == '$')
+                });
+
+                let end_pos = self.offset_at(end);
+                let slice = &self.buf[curr_offset..end_pos];
+                self.pos = end;
+
+                match KEYWORDS.get(slice.to_ascii_lowercase().as_str()) {
+                    Some(token) => *token,
+                    None => Token::Identifier(Ident::new(Slice::new(curr_offset, end_pos))),
                 }
-                // Alphabetical (can start with _, # or @)
-                c if c.is_alphabetic() || c == '_' || c == '#' || c == '@' => {
-                    let end_pos = self.scan_until(curr_offset, |c| {
-                        c == ' ' || c == ',' || c == ';' || c == ')'
-                    });
-
-                    let slice = &self.buf[curr_offset..end_pos];
-                    self.pos += slice.len();
-
-                    match slice {
-                        // Keywords
-                        s if s.eq_ignore_ascii_case("select") => Token::Keyword(Keyword::Select),
-                        s if s.eq_ignore_ascii_case("insert") => Token::Keyword(Keyword::Insert),
-                        s if s.eq_ignore_ascii_case("where") => Token::Keyword(Keyword::Where),
-
-                        s if s.eq_ignore_ascii_case("as") => Token::Keyword(Keyword::As),
-                        s if s.eq_ignore_ascii_case("from") => Token::Keyword(Keyword::From),
-                        s if s.eq_ignore_ascii_case("and") => Token::Keyword(Keyword::And),
-                        s if s.eq_ignore_ascii_case("or") => Token::Keyword(Keyword::Or),
-                        s if s.eq_ignore_ascii_case("xor") => Token::Keyword(Keyword::Xor),
-                        s if s.eq_ignore_ascii_case("update") => Token::Keyword(Keyword::Update),
-                        s if s.eq_ignore_ascii_case("delete") => Token::Keyword(Keyword::Delete),
-                        s if s.eq_ignore_ascii_case("set") => Token::Keyword(Keyword::Set),
-                        s if s.eq_ignore_ascii_case("into") => Token::Keyword(Keyword::Into),
-                        s if s.eq_ignore_ascii_case("values") => Token::Keyword(Keyword::Values),
-                        s if s.eq_ignore_ascii_case("inner") => Token::Keyword(Keyword::Inner),
-                        s if s.eq_ignore_ascii_case("join") => Token::Keyword(Keyword::Join),
-                        s if s.eq_ignore_ascii_case("left") => Token::Keyword(Keyword::Left),
-                        s if s.eq_ignore_ascii_case("right") => Token::Keyword(Keyword::Right),
-                        s if s.eq_ignore_ascii_case("on") => Token::Keyword(Keyword::On),
-                        s if s.eq_ignore_ascii_case("limit") => Token::Keyword(Keyword::Limit),
-                        s if s.eq_ignore_ascii_case("offset") => Token::Keyword(Keyword::Offset),
-                        s if s.eq_ignore_ascii_case("between") => Token::Keyword(Keyword::Between),
-                        s if s.eq_ignore_ascii_case("array") => Token::Keyword(Keyword::Array),
-                        s if s.eq_ignore_ascii_case("order") => Token::Keyword(Keyword::Order),
-                        s if s.eq_ignore_ascii_case("group") => Token::Keyword(Keyword::Group),
-                        s if s.eq_ignore_ascii_case("by") => Token::Keyword(Keyword::By),
-                        s if s.eq_ignore_ascii_case("asc") => Token::Keyword(Keyword::Asc),
-                        s if s.eq_ignore_ascii_case("desc") => Token::Keyword(Keyword::Desc),
-                        s if s.eq_ignore_ascii_case("create") => Token::Keyword(Keyword::Create),
-                        s if s.eq_ignore_ascii_case("table") => Token::Keyword(Keyword::Table),
-                        s if s.eq_ignore_ascii_case("database") => {
-                            Token::Keyword(Keyword::Database)
-                        }
-                        // Logical
-                        s if s.eq_ignore_ascii_case("is") => Token::Logical(Logical::Is),
-                        s if s.eq_ignore_ascii_case("in") => Token::Logical(Logical::In),
-                        s if s.eq_ignore_ascii_case("not") => Token::Logical(Logical::Not),
-                        s if s.eq_ignore_ascii_case("like") => Token::Logical(Logical::Like),
-                        s if s.eq_ignore_ascii_case("then") => Token::Logical(Logical::Then),
-                        s if s.eq_ignore_ascii_case("else") => Token::Logical(Logical::Else),
-                        // Datatypes
-                        s if s.eq_ignore_ascii_case("int") => Token::Keyword(Keyword::Int),
-                        // Other
-                        s if s.eq_ignore_ascii_case("null") => Token::Null,
-                        s if s.eq_ignore_ascii_case("true") => Token::Keyword(Keyword::True),
-                        s if s.eq_ignore_ascii_case("false") => Token::Keyword(Keyword::False),
-                        _ => Token::Identifier(Ident::new(Slice::new(curr_offset, end_pos))),
+            }
+            // Hex and binary numbers, e.g. 0x1F and 0b1010.
+            // Digits are validated by the parser.
+            '0' if self.pos + 1 < self.len
+                && matches!(self.chars[self.pos + 1].1, 'x' | 'X' | 'b' | 'B') =>
+            {
+                let radix = match self.chars[self.pos + 1].1 {
+                    'x' | 'X' => Radix::Hexadecimal,
+                    _ => Radix::Binary,
+                };
+
+                let end = self.scan_until(self.pos + 2, |c| !c.is_alphanumeric());
+                let end_pos = self.offset_at(end);
+                self.pos = end;
+
+                Token::RadixNumeric(Slice::new(curr_offset + 2, end_pos), radix)
+            }
+            c if c == '.' || c.is_numeric() => {
+                // Very greedily collect the number and include alphabetical to be handled later.
+                // A + or - is only collected as the sign of an exponent, e.g. 1.5e-10.
+                let mut end = self.pos + 1;
+
+                while end < self.len {
+                    let (_, c) = self.chars[end];
+                    let is_exponent_sign =
+                        matches!(c, '+' | '-') && matches!(self.chars[end - 1].1, 'e' | 'E');
+
+                    if !(c.is_numeric() || c.is_alphabetic() || c == '.' || is_exponent_sign) {
+                        break;
                     }
+
+                    end += 1;
                 }
-                c if c == '-' || c == '.' || c.is_numeric() => {
-                    // Very greedily collect the number and include alphabetical to be handled later.
-                    let end_pos = self.scan_until(curr_offset, |c| {
-                        !c.is_numeric() && !c.is_alphabetic() && c != '.' && c != '-'
-                    });
-
-                    self.pos += end_pos - curr_offset;
-
-                    let mut seen_dot = false;
-                    let mut is_unknown = false;
-
-                    for i in self.buf[curr_offset + 1..end_pos].to_string().chars() {
-                        if i == '.' {
-                            if seen_dot {
-                                is_unknown = true;
-                            }
-                            seen_dot = true;
-                        }
 
-                        if i.is_alphabetic() {
+                let end_pos = self.offset_at(end);
+                self.pos = end;
+
+                let mut seen_dot = false;
+                let mut is_unknown = false;
+
+                let number = &self.buf[curr_offset..end_pos];
+                let has_exponent = number.contains(['e', 'E']);
+
+                for i in number.chars().skip(1) {
+                    if i == '.' {
+                        if seen_dot {
                             is_unknown = true;
                         }
+                        seen_dot = true;
                     }
 
-                    match is_unknown {
-                        true => Token::Unknown,
-                        false => Token::Numeric(Slice::new(curr_offset, end_pos)),
+                    if i.is_alphabetic() && i != 'e' && i != 'E' {
+                        is_unknown = true;
                     }
                 }
-                _ => Token::Unknown,
-            };
 
-            tokens.push(LocatableToken::at_position(token, curr_offset));
+                // Scientific notation, e.g. 1.5e10, must be a well-formed float.
+                if has_exponent && number.parse::<f64>().is_err() {
+                    is_unknown = true;
+                }
 
-            if prev_index == self.pos {
-                panic!("Critical Lexer Error: Lexer iteration did not collect a token and is stuck. This is a bug.");
+                match is_unknown {
+                    true => self.recover(),
+                    false => Token::Numeric(Slice::new(curr_offset, end_pos)),
+                }
             }
+            _ => {
+                self.pos += 1;
+                Token::Unknown
+            }
+        };
 
-            prev_index = self.pos;
+        if token == Token::Unknown {
+            self.errors.push(LexError {
+                position: curr_offset,
+                text: self.buf[curr_offset..self.offset_at(self.pos)].to_string(),
+            });
         }
 
-        LexResult {
-            buf: self.buf,
-            tokens,
+        if prev_index == self.pos {
+            panic!("Critical Lexer Error: Lexer iteration did not collect a token and is stuck. This is a bug.");
         }
+
+        let span = Slice::new(curr_offset, self.offset_at(self.pos));
+        Some(LocatableToken::new(token, span))
     }
 
-    /// Given a start point and a char to find, scan until the char is found
-    /// and return the end_offset.
+    /// Skip past malformed input, such as an unterminated string, to the next
+    /// whitespace or semicolon so lexing can carry on from a sensible place.
+    fn recover(&mut self) -> Token {
+        self.pos = self.scan_until(self.pos, |c| c.is_whitespace() || c == ';');
+        Token::Unknown
+    }
+
+    /// Given a start index and a char to find, scan until the char is found
+    /// and return its index.
     /// Only really works when we expect to end the current token by one and one
     /// character only. For more complex scenarios, use scan_until.
     fn scan_to(&self, start_offset: usize, char: char) -> usize {
@@ -308,6 +459,43 @@ impl<'a> Lexer<'a> {
         cursor
     }
 
+    /// Given the index of the opening `/*` of a block comment, scan to the end of
+    /// the matching `*/`, returning the index after it.
+    /// Nested block comments must also be closed.
+    /// Returns None if the comment is never closed.
+    fn scan_block_comment(&self, start: usize) -> Option<usize> {
+        let mut cursor = start + 2;
+        let mut depth = 1;
+
+        while cursor + 1 < self.len {
+            match (self.chars[cursor].1, self.chars[cursor + 1].1) {
+                ('/', '*') => {
+                    depth += 1;
+                    cursor += 2;
+                }
+                ('*', '/') => {
+                    depth -= 1;
+                    cursor += 2;
+
+                    if depth == 0 {
+                        return Some(cursor);
+                    }
+                }
+                _ => cursor += 1,
+            }
+        }
+
+        None
+    }
+
+    /// Get the byte offset of the char at the given index.
+    /// The end of the input is the length of the buffer.
+    fn offset_at(&self, index: usize) -> usize {
+        self.chars
+            .get(index)
+            .map_or(self.buf.len(), |(offset, _)| *offset)
+    }
+
     /// Given the function end_func, scan the input until the func returns true,
     /// returning the index at that point.
     fn scan_until<F>(&self, start_offset: usize, end_func: F) -> usize
@@ -393,12 +581,12 @@ mod lexer_tests {
         let actual = lexer.tokens;
 
         let expected = vec![
-            LocatableToken::at_position(Token::Arithmetic(Arithmetic::Multiply), 0),
-            LocatableToken::at_position(Token::Arithmetic(Arithmetic::Divide), 1),
-            LocatableToken::at_position(Token::Arithmetic(Arithmetic::Modulo), 2),
-            LocatableToken::at_position(Token::Arithmetic(Arithmetic::Minus), 3),
-            LocatableToken::at_position(Token::Arithmetic(Arithmetic::Plus), 4),
-            LocatableToken::at_position(Token::EOF, 5),
+            LocatableToken::new(Token::Arithmetic(Arithmetic::Multiply), Slice::new(0, 1)),
+            LocatableToken::new(Token::Arithmetic(Arithmetic::Divide), Slice::new(1, 2)),
+            LocatableToken::new(Token::Arithmetic(Arithmetic::Modulo), Slice::new(2, 3)),
+            LocatableToken::new(Token::Arithmetic(Arithmetic::Minus), Slice::new(3, 4)),
+            LocatableToken::new(Token::Arithmetic(Arithmetic::Plus), Slice::new(4, 5)),
+            LocatableToken::new(Token::EOF, Slice::new(5, 5)),
         ];
 
         assert_eq!(actual, expected);
@@ -406,7 +594,7 @@ mod lexer_tests {
 
     #[test]
     fn test_keywords() {
-        let str = String::from("select from inSERt WHERE AS Update and or xor set into values inner left right join on limit offset between array order group by asc desc True FALSE CREATE TABLE Database");
+        let str = String::from("select from inSERt WHERE AS Update and or xor set into values inner left right join on limit offset between array order group by asc desc True FALSE CREATE TABLE Database index include partition range");
         let lexer = Lexer::new(&str).lex();
         let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
 
@@ -472,6 +660,14 @@ mod lexer_tests {
             Token::Keyword(Keyword::Table),
             Token::Space,
             Token::Keyword(Keyword::Database),
+            Token::Space,
+            Token::Keyword(Keyword::Index),
+            Token::Space,
+            Token::Keyword(Keyword::Include),
+            Token::Space,
+            Token::Keyword(Keyword::Partition),
+            Token::Space,
+            Token::Keyword(Keyword::Range),
             Token::EOF,
         ];
 
@@ -480,11 +676,25 @@ mod lexer_tests {
 
     #[test]
     fn test_datatypes() {
-        let str = String::from("INT ");
+        let str = String::from("INT BOOLEAN bool BLOB varbinary JSON ");
         let lexer = Lexer::new(&str).lex();
         let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
 
-        let expected = vec![Token::Keyword(Keyword::Int), Token::Space, Token::EOF];
+        let expected = vec![
+            Token::Keyword(Keyword::Int),
+            Token::Space,
+            Token::Keyword(Keyword::Boolean),
+            Token::Space,
+            Token::Keyword(Keyword::Boolean),
+            Token::Space,
+            Token::Keyword(Keyword::Blob),
+            Token::Space,
+            Token::Keyword(Keyword::Blob),
+            Token::Space,
+            Token::Keyword(Keyword::Json),
+            Token::Space,
+            Token::EOF,
+        ];
 
         assert_eq!(actual_without_locations, expected);
     }
@@ -555,6 +765,29 @@ mod lexer_tests {
         assert_eq!(actual_without_locations, expected);
     }
 
+    #[test]
+    fn test_json_arrows() {
+        let str = String::from("a->'b' a->>'b' ->-");
+        let lexer = Lexer::new(&str).lex();
+        let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
+
+        let expected = vec![
+            Token::Identifier(Ident::new(Slice::new(0, 1))),
+            Token::Arrow,
+            Token::Value(Value::SingleQuoted(Slice::new(4, 5))),
+            Token::Space,
+            Token::Identifier(Ident::new(Slice::new(7, 8))),
+            Token::DoubleArrow,
+            Token::Value(Value::SingleQuoted(Slice::new(12, 13))),
+            Token::Space,
+            Token::Arrow,
+            Token::Arithmetic(Arithmetic::Minus),
+            Token::EOF,
+        ];
+
+        assert_eq!(actual_without_locations, expected);
+    }
+
     #[test]
     fn test_logical() {
         let str = String::from("Is In Not THEN like elSE");
@@ -595,6 +828,138 @@ mod lexer_tests {
         assert_eq!(actual_without_locations, expected);
     }
 
+    #[test]
+    fn test_double_quoted_identifier() {
+        let str = String::from("select \"My Table\".a");
+        let lexer = Lexer::new(&str).lex();
+        let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
+
+        let expected = vec![
+            Token::Keyword(Keyword::Select),
+            Token::Space,
+            Token::Value(Value::DoubleQuoted(Slice::new(8, 16))),
+            Token::Dot,
+            Token::Identifier(Ident::new(Slice::new(18, 19))),
+            Token::EOF,
+        ];
+
+        assert_eq!(actual_without_locations, expected);
+        assert_eq!(&str[8..16], "My Table");
+    }
+
+    #[test]
+    fn test_unterminated_double_quoted_identifier() {
+        let str = String::from("select \"a");
+        let lexer = Lexer::new(&str).lex();
+        let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
+
+        let expected = vec![
+            Token::Keyword(Keyword::Select),
+            Token::Space,
+            Token::Unknown,
+            Token::EOF,
+        ];
+
+        assert_eq!(actual_without_locations, expected);
+    }
+
+    #[test]
+    fn test_escaped_quote_in_double_quoted_identifier() {
+        let str = String::from("select \"x\"\"y\" from t");
+        let lexer = Lexer::new(&str).lex();
+        let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
+
+        let expected = vec![
+            Token::Keyword(Keyword::Select),
+            Token::Space,
+            Token::Unknown,
+            Token::Space,
+            Token::Keyword(Keyword::From),
+            Token::Space,
+            Token::Identifier(Ident::new(Slice::new(19, 20))),
+            Token::EOF,
+        ];
+
+        assert_eq!(actual_without_locations, expected);
+        assert_eq!(
+            lexer.errors,
+            vec![LexError {
+                position: 7,
+                text: String::from("\"x\"\"y\""),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parameters() {
+        let str = String::from("? $12 :name : $");
+        let lexer = Lexer::new(&str).lex();
+        let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
+
+        let expected = vec![
+            Token::Parameter(Parameter::Anonymous),
+            Token::Space,
+            Token::Parameter(Parameter::Positional(Slice::new(3, 5))),
+            Token::Space,
+            Token::Parameter(Parameter::Named(Slice::new(7, 11))),
+            Token::Space,
+            Token::Colon,
+            Token::Space,
+            Token::Unknown,
+            Token::EOF,
+        ];
+
+        assert_eq!(actual_without_locations, expected);
+    }
+
+    #[test]
+    fn test_block_comment() {
+        let str = String::from("*/* a\n b */+");
+        let lexer = Lexer::new(&str).lex();
+        let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
+
+        let expected = vec![
+            Token::Arithmetic(Arithmetic::Multiply),
+            Token::Comment(Slice::new(1, 11)),
+            Token::Arithmetic(Arithmetic::Plus),
+            Token::EOF,
+        ];
+
+        assert_eq!(actual_without_locations, expected);
+    }
+
+    #[test]
+    fn test_nested_block_comment() {
+        let str = String::from("/* a /* b */ c */ /");
+        let lexer = Lexer::new(&str).lex();
+        let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
+
+        let expected = vec![
+            Token::Comment(Slice::new(0, 17)),
+            Token::Space,
+            Token::Arithmetic(Arithmetic::Divide),
+            Token::EOF,
+        ];
+
+        assert_eq!(actual_without_locations, expected);
+    }
+
+    #[test]
+    fn test_unterminated_block_comment() {
+        let str = String::from("select /* a /* b */");
+        let lexer = Lexer::new(&str).lex();
+        let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
+
+        let expected = vec![
+            Token::Keyword(Keyword::Select),
+            Token::Space,
+            Token::Unknown,
+            Token::EOF,
+        ];
+
+        assert_eq!(actual_without_locations, expected);
+    }
+
     #[test]
     fn test_doubledash_comment_multiline() {
         let str = String::from(
@@ -627,12 +992,12 @@ mod lexer_tests {
         let actual = lexer.tokens;
 
         let expected = vec![
-            LocatableToken::at_position(Token::Keyword(Keyword::Select), 0),
-            LocatableToken::at_position(Token::Space, 6),
-            LocatableToken::at_position(Token::Keyword(Keyword::Insert), 7),
-            LocatableToken::at_position(Token::Space, 13),
-            LocatableToken::at_position(Token::Keyword(Keyword::Where), 14),
-            LocatableToken::at_position(Token::EOF, 19),
+            LocatableToken::new(Token::Keyword(Keyword::Select), Slice::new(0, 6)),
+            LocatableToken::new(Token::Space, Slice::new(6, 7)),
+            LocatableToken::new(Token::Keyword(Keyword::Insert), Slice::new(7, 13)),
+            LocatableToken::new(Token::Space, Slice::new(13, 14)),
+            LocatableToken::new(Token::Keyword(Keyword::Where), Slice::new(14, 19)),
+            LocatableToken::new(Token::EOF, Slice::new(19, 19)),
         ];
 
         assert_eq!(actual, expected);
@@ -674,6 +1039,24 @@ mod lexer_tests {
         assert_eq!(actual_without_locations, expected);
     }
 
+    #[test]
+    fn test_identifier_not_greedily_consuming_operators() {
+        let str = String::from("a.b+c");
+        let lexer = Lexer::new(&str).lex();
+        let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
+
+        let expected = vec![
+            Token::Identifier(Ident::new(Slice::new(0, 1))),
+            Token::Dot,
+            Token::Identifier(Ident::new(Slice::new(2, 3))),
+            Token::Arithmetic(Arithmetic::Plus),
+            Token::Identifier(Ident::new(Slice::new(4, 5))),
+            Token::EOF,
+        ];
+
+        assert_eq!(actual_without_locations, expected);
+    }
+
     #[test]
     fn test_keywords_not_greedy() {
         let str = String::from("selecting");
@@ -740,6 +1123,182 @@ mod lexer_tests {
         assert_eq!(actual_without_locations, expected);
     }
 
+    #[test]
+    fn test_unknown_tokens_report_errors() {
+        let str = String::from("SELECT 12a0, ~ \"abc");
+        let lexer = Lexer::new(&str).lex();
+
+        let expected = vec![
+            LexError {
+                position: 7,
+                text: String::from("12a0,"),
+            },
+            LexError {
+                position: 13,
+                text: String::from("~"),
+            },
+            LexError {
+                position: 15,
+                text: String::from("\"abc"),
+            },
+        ];
+
+        assert_eq!(lexer.errors, expected);
+    }
+
+    #[test]
+    fn test_valid_input_reports_no_errors() {
+        let str = String::from("SELECT 1 FROM t;");
+        let lexer = Lexer::new(&str).lex();
+
+        assert!(lexer.errors.is_empty());
+    }
+
+    #[test]
+    fn test_iter_matches_lex() {
+        let str = String::from("SELECT 12a0 FROM t;");
+        let expected = Lexer::new(&str).lex();
+
+        let mut lexer = Lexer::new(&str);
+        let actual: Vec<LocatableToken> = lexer.iter().collect();
+
+        assert_eq!(actual, expected.tokens);
+        assert_eq!(lexer.errors(), expected.errors);
+        assert_eq!(lexer.iter().next(), None);
+    }
+
+    #[test]
+    fn test_unicode_identifiers() {
+        let str = String::from("SELECT größe FROM 表");
+        let lexer = Lexer::new(&str).lex();
+
+        let expected = vec![
+            LocatableToken::new(Token::Keyword(Keyword::Select), Slice::new(0, 6)),
+            LocatableToken::new(Token::Space, Slice::new(6, 7)),
+            LocatableToken::new(
+                Token::Identifier(Ident::new(Slice::new(7, 14))),
+                Slice::new(7, 14),
+            ),
+            LocatableToken::new(Token::Space, Slice::new(14, 15)),
+            LocatableToken::new(Token::Keyword(Keyword::From), Slice::new(15, 19)),
+            LocatableToken::new(Token::Space, Slice::new(19, 20)),
+            LocatableToken::new(
+                Token::Identifier(Ident::new(Slice::new(20, 23))),
+                Slice::new(20, 23),
+            ),
+            LocatableToken::new(Token::EOF, Slice::new(23, 23)),
+        ];
+
+        assert_eq!(lexer.tokens, expected);
+        assert_eq!(&str[7..14], "größe");
+    }
+
+    #[test]
+    fn test_multibyte_chars_in_strings_and_comments() {
+        let str = String::from("'héllo' \"naïve\" -- ünïcode\n/* € */€");
+        let lexer = Lexer::new(&str).lex();
+        let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
+
+        let expected = vec![
+            Token::Value(Value::SingleQuoted(Slice::new(1, 7))),
+            Token::Space,
+            Token::Value(Value::DoubleQuoted(Slice::new(10, 16))),
+            Token::Space,
+            Token::Comment(Slice::new(18, 30)),
+            Token::NewLine,
+            Token::Comment(Slice::new(31, 40)),
+            Token::Unknown,
+            Token::EOF,
+        ];
+
+        assert_eq!(actual_without_locations, expected);
+        assert_eq!(
+            lexer.errors,
+            vec![LexError {
+                position: 40,
+                text: String::from("€"),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_unterminated_single_quoted_string() {
+        let str = String::from("'abc");
+        let lexer = Lexer::new(&str).lex();
+        let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
+
+        let expected = vec![Token::Unknown, Token::EOF];
+
+        assert_eq!(actual_without_locations, expected);
+    }
+
+    #[test]
+    fn test_recovery_reports_every_problem() {
+        let str = String::from("SELECT 'abc FROM t;\nSELECT 1.2.3, \"x;\nSELECT 1;");
+        let lexer = Lexer::new(&str).lex();
+
+        let expected = vec![
+            LexError {
+                position: 7,
+                text: String::from("'abc"),
+            },
+            LexError {
+                position: 27,
+                text: String::from("1.2.3,"),
+            },
+            LexError {
+                position: 34,
+                text: String::from("\"x"),
+            },
+        ];
+
+        assert_eq!(lexer.errors, expected);
+        assert_eq!(
+            lexer.tokens[lexer.tokens.len() - 5..]
+                .iter()
+                .map(|t| t.token)
+                .collect::<Vec<Token>>(),
+            vec![
+                Token::Keyword(Keyword::Select),
+                Token::Space,
+                Token::Numeric(Slice::new(45, 46)),
+                Token::Semicolon,
+                Token::EOF,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keyword_reservation() {
+        assert!(Keyword::Select.is_reserved());
+        assert!(Keyword::From.is_reserved());
+        assert!(!Keyword::Index.is_reserved());
+        assert!(!Keyword::Json.is_reserved());
+        assert!(!Keyword::Date.is_reserved());
+    }
+
+    #[test]
+    fn test_bracketed_identifier() {
+        let str = String::from("[Order] [My Table] [] [x");
+        let lexer = Lexer::new(&str).lex();
+        let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
+
+        let expected = vec![
+            Token::Identifier(Ident::new(Slice::new(1, 6))),
+            Token::Space,
+            Token::Identifier(Ident::new(Slice::new(9, 17))),
+            Token::Space,
+            Token::SquareOpen,
+            Token::SquareClose,
+            Token::Space,
+            Token::SquareOpen,
+            Token::Identifier(Ident::new(Slice::new(23, 24))),
+            Token::EOF,
+        ];
+
+        assert_eq!(actual_without_locations, expected);
+    }
+
     #[test]
     fn test_identifier_starts_with_underscore() {
         let str = String::from("_hello");
@@ -806,14 +1365,36 @@ mod lexer_tests {
         assert_eq!(actual_without_locations, expected);
     }
 
+    #[test]
+    fn test_whitespace() {
+        let str = String::from("select\t1\u{a0}\r\n");
+        let lexer = Lexer::new(&str).lex();
+        let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
+
+        let expected = vec![
+            Token::Keyword(Keyword::Select),
+            Token::Space,
+            Token::Numeric(Slice::new(7, 8)),
+            Token::Space,
+            Token::NewLine,
+            Token::NewLine,
+            Token::EOF,
+        ];
+
+        assert_eq!(actual_without_locations, expected);
+        assert!(lexer.errors.is_empty());
+    }
+
     #[test]
     fn test_numeric_negative() {
         let str = String::from("-12 4");
         let lexer = Lexer::new(&str).lex();
         let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
 
+        // The parser makes the number negative
         let expected = vec![
-            Token::Numeric(Slice::new(0, 3)),
+            Token::Arithmetic(Arithmetic::Minus),
+            Token::Numeric(Slice::new(1, 3)),
             Token::Space,
             Token::Numeric(Slice::new(4, 5)),
             Token::EOF,
@@ -822,6 +1403,26 @@ mod lexer_tests {
         assert_eq!(actual_without_locations, expected);
     }
 
+    #[test]
+    fn test_numeric_subtraction() {
+        let str = String::from("5-3 10-1e-2");
+        let lexer = Lexer::new(&str).lex();
+        let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
+
+        let expected = vec![
+            Token::Numeric(Slice::new(0, 1)),
+            Token::Arithmetic(Arithmetic::Minus),
+            Token::Numeric(Slice::new(2, 3)),
+            Token::Space,
+            Token::Numeric(Slice::new(4, 6)),
+            Token::Arithmetic(Arithmetic::Minus),
+            Token::Numeric(Slice::new(7, 11)),
+            Token::EOF,
+        ];
+
+        assert_eq!(actual_without_locations, expected);
+    }
+
     #[test]
     fn test_numeric_float() {
         let str = String::from("12.1 1.9");
@@ -838,6 +1439,28 @@ mod lexer_tests {
         assert_eq!(actual_without_locations, expected);
     }
 
+    #[test]
+    fn test_numeric_scientific_notation() {
+        let str = String::from("1.5e10 2E-3 4e+2 1e 1e5e");
+        let lexer = Lexer::new(&str).lex();
+        let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
+
+        let expected = vec![
+            Token::Numeric(Slice::new(0, 6)),
+            Token::Space,
+            Token::Numeric(Slice::new(7, 11)),
+            Token::Space,
+            Token::Numeric(Slice::new(12, 16)),
+            Token::Space,
+            Token::Unknown,
+            Token::Space,
+            Token::Unknown,
+            Token::EOF,
+        ];
+
+        assert_eq!(actual_without_locations, expected);
+    }
+
     #[test]
     fn test_numeric_float_short_syntax() {
         let str = String::from(".1");
@@ -881,6 +1504,51 @@ mod lexer_tests {
         assert_eq!(actual_without_locations, expected);
     }
 
+    #[test]
+    fn test_numeric_hex_and_binary() {
+        let str = String::from("0x1F 0B1010 0xZZ");
+        let lexer = Lexer::new(&str).lex();
+        let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
+
+        let expected = vec![
+            Token::RadixNumeric(Slice::new(2, 4), Radix::Hexadecimal),
+            Token::Space,
+            Token::RadixNumeric(Slice::new(7, 11), Radix::Binary),
+            Token::Space,
+            Token::RadixNumeric(Slice::new(14, 16), Radix::Hexadecimal),
+            Token::EOF,
+        ];
+
+        assert_eq!(actual_without_locations, expected);
+    }
+
+    #[test]
+    fn test_hex_string() {
+        let str = String::from("X'DEADBEEF' x'01'");
+        let lexer = Lexer::new(&str).lex();
+        let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
+
+        let expected = vec![
+            Token::Value(Value::Hex(Slice::new(2, 10))),
+            Token::Space,
+            Token::Value(Value::Hex(Slice::new(14, 16))),
+            Token::EOF,
+        ];
+
+        assert_eq!(actual_without_locations, expected);
+    }
+
+    #[test]
+    fn test_identifier_starting_with_x() {
+        let str = String::from("xyz");
+        let lexer = Lexer::new(&str).lex();
+        let actual_without_locations = to_token_vec_without_locations(lexer.tokens);
+
+        let expected = vec![Token::Identifier(Ident::new(Slice::new(0, 3))), Token::EOF];
+
+        assert_eq!(actual_without_locations, expected);
+    }
+
     #[test]
     fn test_string_indexing() {
         let str = String::from("insert users ");
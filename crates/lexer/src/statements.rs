@@ -0,0 +1,182 @@
+use std::io::{self, BufRead};
+
+/// Where the reader is within the text of a statement.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum State {
+    Code,
+    /// Inside '...', "..." or [...], which end at the given char.
+    Quoted(char),
+    LineComment,
+    /// Inside a block comment, nested to the given depth.
+    BlockComment(usize),
+}
+
+/// Splits a script into statements, reading only as much as each statement needs.
+/// Statements end at a semicolon that isn't inside a string, delimited identifier or comment.
+/// Used to run scripts too big to hold in memory at once.
+/// Each statement comes with the byte offset it starts at, so errors can point into the script.
+pub struct StatementReader<R> {
+    reader: R,
+    state: State,
+    line: String,
+    /// How many bytes of the script have been used up by the statements read so far.
+    offset: usize,
+    finished: bool,
+}
+
+impl<R: BufRead> StatementReader<R> {
+    pub fn new(reader: R) -> Self {
+        StatementReader {
+            reader,
+            state: State::Code,
+            line: String::new(),
+            offset: 0,
+            finished: false,
+        }
+    }
+
+    /// Read the next statement, without its semicolon.
+    /// Returns None once the input is used up. Text after the last semicolon counts as a statement,
+    /// as long as it isn't only whitespace.
+    fn read_statement(&mut self) -> io::Result<Option<(usize, String)>> {
+        let mut statement = String::new();
+        let mut start = self.offset;
+
+        loop {
+            if self.line.is_empty() && self.reader.read_line(&mut self.line)? == 0 {
+                self.finished = true;
+
+                return Ok(match statement.trim().is_empty() {
+                    true => None,
+                    false => Some((start, statement)),
+                });
+            }
+
+            match self.find_end() {
+                Some(end) => {
+                    statement.push_str(&self.line[..end]);
+                    self.line.drain(..=end);
+                    self.offset += end + 1;
+
+                    if !statement.trim().is_empty() {
+                        return Ok(Some((start, statement)));
+                    }
+
+                    statement.clear();
+                    start = self.offset;
+                }
+                None => {
+                    statement.push_str(&self.line);
+                    self.offset += self.line.len();
+                    self.line.clear();
+                }
+            }
+        }
+    }
+
+    /// Find the byte offset of the semicolon ending the statement in the current line, if any.
+    /// Updates the state as it goes, so it carries over to the next line.
+    fn find_end(&mut self) -> Option<usize> {
+        let mut chars = self.line.char_indices().peekable();
+
+        while let Some((offset, c)) = chars.next() {
+            let next = chars.peek().map(|(_, c)| *c);
+
+            self.state = match (self.state, c, next) {
+                (State::Code, ';', _) => return Some(offset),
+                (State::Code, '\'' | '"', _) => State::Quoted(c),
+                // An empty [] isn't a delimited identifier, see Lexer::next_token
+                (State::Code, '[', Some(next)) if next != ']' => State::Quoted(']'),
+                (State::Code, '-', Some('-')) => {
+                    chars.next();
+                    State::LineComment
+                }
+                (State::Code, '/', Some('*')) => {
+                    chars.next();
+                    State::BlockComment(1)
+                }
+                (State::Quoted(end), c, _) if c == end => State::Code,
+                (State::LineComment, '\n' | '\r', _) => State::Code,
+                (State::BlockComment(depth), '/', Some('*')) => {
+                    chars.next();
+                    State::BlockComment(depth + 1)
+                }
+                (State::BlockComment(depth), '*', Some('/')) => {
+                    chars.next();
+
+                    match depth {
+                        1 => State::Code,
+                        _ => State::BlockComment(depth - 1),
+                    }
+                }
+                (state, _, _) => state,
+            };
+        }
+
+        None
+    }
+}
+
+impl<R: BufRead> Iterator for StatementReader<R> {
+    type Item = io::Result<(usize, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        self.read_statement().transpose()
+    }
+}
+
+#[cfg(test)]
+mod statements_tests {
+    use crate::statements::StatementReader;
+
+    fn split(script: &str) -> Vec<String> {
+        StatementReader::new(script.as_bytes())
+            .map(|statement| statement.unwrap().1.trim().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_split_on_semicolons() {
+        assert_eq!(
+            split("SELECT 1; SELECT 2;\nSELECT\n  3;;\n"),
+            vec!["SELECT 1", "SELECT 2", "SELECT\n  3"]
+        );
+        assert_eq!(split("SELECT 1;\nSELECT 2"), vec!["SELECT 1", "SELECT 2"]);
+        assert_eq!(split(" \n ;\n"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_semicolons_in_strings_and_identifiers() {
+        assert_eq!(
+            split("SELECT 'a;b', \"c;\nd\" FROM [e;f]; SELECT 1"),
+            vec!["SELECT 'a;b', \"c;\nd\" FROM [e;f]", "SELECT 1"]
+        );
+    }
+
+    #[test]
+    fn test_semicolons_in_comments() {
+        assert_eq!(
+            split("SELECT 1 -- one; two\n; /* a /* ; */ ; */ SELECT 2;"),
+            vec!["SELECT 1 -- one; two", "/* a /* ; */ ; */ SELECT 2"]
+        );
+    }
+
+    #[test]
+    fn test_statement_offsets() {
+        let script = "SELECT 1;\n;  SELECT\n 'é';SELECT 3";
+        let statements: Vec<(usize, String)> = StatementReader::new(script.as_bytes())
+            .map(Result::unwrap)
+            .collect();
+
+        for (offset, statement) in &statements {
+            assert!(script[*offset..].starts_with(statement.as_str()));
+        }
+
+        let offsets: Vec<usize> = statements.iter().map(|(offset, _)| *offset).collect();
+        assert_eq!(offsets, vec![0, 11, 26]);
+    }
+}
@@ -1,3 +1,5 @@
+use std::{collections::HashMap, sync::LazyLock};
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Keyword {
     Select,
@@ -8,12 +10,21 @@ pub enum Keyword {
     Delete,
     Where,
     Create,
+    Drop,
+    Describe,
+    If,
     Table,
     Database,
+    Index,
+    Include,
+    Partition,
+    Range,
     And,
     Or,
     Xor,
     Set,
+    Pragma,
+    Use,
     Into,
     Values,
     Inner,
@@ -24,6 +35,13 @@ pub enum Keyword {
     Limit,
     Offset,
     Between,
+    Cast,
+    Distinct,
+    Exists,
+    Primary,
+    Key,
+    Unique,
+    Default,
     Array,
     Order,
     Group,
@@ -33,8 +51,133 @@ pub enum Keyword {
     True,
     False,
     Int,
+    Boolean,
+    Blob,
+    Json,
+    Uuid,
+    Varchar,
+    Text,
+    Float,
+    Date,
+    BigInt,
+    Generated,
+    Always,
+    Stored,
+    Virtual,
+}
+
+impl Keyword {
+    /// Reserved keywords can never be used as identifiers.
+    /// Non-reserved keywords, such as INDEX or JSON, only have meaning in certain places,
+    /// so can also be used to name tables, columns and so on.
+    pub fn is_reserved(&self) -> bool {
+        !matches!(
+            self,
+            Keyword::Database
+                | Keyword::Index
+                | Keyword::Include
+                | Keyword::Partition
+                | Keyword::Range
+                | Keyword::Key
+                | Keyword::Int
+                | Keyword::Boolean
+                | Keyword::Blob
+                | Keyword::Json
+                | Keyword::Uuid
+                | Keyword::Varchar
+                | Keyword::Text
+                | Keyword::Float
+                | Keyword::Date
+                | Keyword::BigInt
+                | Keyword::Generated
+                | Keyword::Always
+                | Keyword::Stored
+                | Keyword::Virtual
+        )
+    }
 }
 
+/// Every word with a special meaning, keyed by its lowercase text.
+pub static KEYWORDS: LazyLock<HashMap<&'static str, Token>> = LazyLock::new(|| {
+    HashMap::from([
+        ("select", Token::Keyword(Keyword::Select)),
+        ("insert", Token::Keyword(Keyword::Insert)),
+        ("where", Token::Keyword(Keyword::Where)),
+        ("as", Token::Keyword(Keyword::As)),
+        ("from", Token::Keyword(Keyword::From)),
+        ("and", Token::Keyword(Keyword::And)),
+        ("or", Token::Keyword(Keyword::Or)),
+        ("xor", Token::Keyword(Keyword::Xor)),
+        ("update", Token::Keyword(Keyword::Update)),
+        ("delete", Token::Keyword(Keyword::Delete)),
+        ("set", Token::Keyword(Keyword::Set)),
+        ("pragma", Token::Keyword(Keyword::Pragma)),
+        ("use", Token::Keyword(Keyword::Use)),
+        ("into", Token::Keyword(Keyword::Into)),
+        ("values", Token::Keyword(Keyword::Values)),
+        ("inner", Token::Keyword(Keyword::Inner)),
+        ("join", Token::Keyword(Keyword::Join)),
+        ("left", Token::Keyword(Keyword::Left)),
+        ("right", Token::Keyword(Keyword::Right)),
+        ("on", Token::Keyword(Keyword::On)),
+        ("limit", Token::Keyword(Keyword::Limit)),
+        ("offset", Token::Keyword(Keyword::Offset)),
+        ("between", Token::Keyword(Keyword::Between)),
+        ("distinct", Token::Keyword(Keyword::Distinct)),
+        ("cast", Token::Keyword(Keyword::Cast)),
+        ("exists", Token::Keyword(Keyword::Exists)),
+        ("primary", Token::Keyword(Keyword::Primary)),
+        ("key", Token::Keyword(Keyword::Key)),
+        ("unique", Token::Keyword(Keyword::Unique)),
+        ("default", Token::Keyword(Keyword::Default)),
+        ("array", Token::Keyword(Keyword::Array)),
+        ("order", Token::Keyword(Keyword::Order)),
+        ("group", Token::Keyword(Keyword::Group)),
+        ("by", Token::Keyword(Keyword::By)),
+        ("asc", Token::Keyword(Keyword::Asc)),
+        ("desc", Token::Keyword(Keyword::Desc)),
+        ("create", Token::Keyword(Keyword::Create)),
+        ("drop", Token::Keyword(Keyword::Drop)),
+        ("describe", Token::Keyword(Keyword::Describe)),
+        ("if", Token::Keyword(Keyword::If)),
+        ("table", Token::Keyword(Keyword::Table)),
+        ("database", Token::Keyword(Keyword::Database)),
+        ("index", Token::Keyword(Keyword::Index)),
+        ("include", Token::Keyword(Keyword::Include)),
+        ("partition", Token::Keyword(Keyword::Partition)),
+        ("range", Token::Keyword(Keyword::Range)),
+        ("generated", Token::Keyword(Keyword::Generated)),
+        ("always", Token::Keyword(Keyword::Always)),
+        ("stored", Token::Keyword(Keyword::Stored)),
+        ("virtual", Token::Keyword(Keyword::Virtual)),
+        ("true", Token::Keyword(Keyword::True)),
+        ("false", Token::Keyword(Keyword::False)),
+        // Logical
+        ("is", Token::Logical(Logical::Is)),
+        ("in", Token::Logical(Logical::In)),
+        ("not", Token::Logical(Logical::Not)),
+        ("like", Token::Logical(Logical::Like)),
+        ("then", Token::Logical(Logical::Then)),
+        ("else", Token::Logical(Logical::Else)),
+        // Datatypes
+        ("int", Token::Keyword(Keyword::Int)),
+        ("boolean", Token::Keyword(Keyword::Boolean)),
+        ("bool", Token::Keyword(Keyword::Boolean)),
+        ("blob", Token::Keyword(Keyword::Blob)),
+        ("varbinary", Token::Keyword(Keyword::Blob)),
+        ("json", Token::Keyword(Keyword::Json)),
+        ("uuid", Token::Keyword(Keyword::Uuid)),
+        ("uniqueidentifier", Token::Keyword(Keyword::Uuid)),
+        ("varchar", Token::Keyword(Keyword::Varchar)),
+        ("text", Token::Keyword(Keyword::Text)),
+        ("float", Token::Keyword(Keyword::Float)),
+        ("date", Token::Keyword(Keyword::Date)),
+        ("bigint", Token::Keyword(Keyword::BigInt)),
+        // Other
+        ("null", Token::Null),
+    ])
+});
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Ident {
     pub value: Slice,
@@ -49,9 +192,33 @@ impl Ident {
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Value {
     SingleQuoted(Slice),
+    DoubleQuoted(Slice),
+    Hex(Slice),
     Raw(Slice),
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Parameter {
+    Anonymous,         // ?
+    Positional(Slice), // $1, the slice covers the number
+    Named(Slice),      // :name, the slice covers the name
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Radix {
+    Binary,      // 0b1010
+    Hexadecimal, // 0x1F
+}
+
+impl Radix {
+    pub fn base(&self) -> u32 {
+        match self {
+            Radix::Binary => 2,
+            Radix::Hexadecimal => 16,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Arithmetic {
     Multiply,
@@ -122,8 +289,12 @@ pub enum Token {
     Logical(Logical),
     Comparison(Comparison),
     Bitwise(Bitwise),
+    Arrow,       // ->
+    DoubleArrow, // ->>
     Numeric(Slice),
+    RadixNumeric(Slice, Radix), // Slice excludes the 0x/0b prefix
     Identifier(Ident),
+    Parameter(Parameter),
     Comment(Slice),
     Value(Value),
     EOF,
@@ -134,11 +305,16 @@ pub enum Token {
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct LocatableToken {
     pub token: Token,
-    pub position: usize,
+    pub span: Slice, // Byte range of the whole token in the input, including any quotes or prefix
 }
 
 impl LocatableToken {
-    pub fn at_position(token: Token, position: usize) -> Self {
-        LocatableToken { token, position }
+    pub fn new(token: Token, span: Slice) -> Self {
+        LocatableToken { token, span }
+    }
+
+    /// The byte offset the token starts at.
+    pub fn position(&self) -> usize {
+        self.span.start
     }
 }
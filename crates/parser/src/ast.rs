@@ -1,32 +1,97 @@
 use std::fmt;
 
+pub use crate::to_sql::{statement_to_sql, to_sql};
+
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Program {
     Statements(Vec<Statement>),
     Empty,
 }
 
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Statement {
     User(UserStatement),
     Server(ServerStatement),
 }
 
+/// What a statement does, for deciding whether it can run.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StatementKind {
+    /// Reads data without changing it. For example, SELECT.
+    ReadOnly,
+    /// Changes the data in a table. For example, INSERT.
+    Write,
+    /// Changes the schema of a database. For example, CREATE TABLE.
+    Ddl,
+    /// Changes the server. For example, CREATE DATABASE.
+    Server,
+}
+
+impl Statement {
+    pub fn kind(&self) -> StatementKind {
+        match self {
+            Statement::User(user_statement) => user_statement.kind(),
+            // Neither changes any data
+            Statement::Server(ServerStatement::Set(_) | ServerStatement::Use(_)) => {
+                StatementKind::ReadOnly
+            }
+            Statement::Server(_) => StatementKind::Server,
+        }
+    }
+}
+
+impl UserStatement {
+    pub fn kind(&self) -> StatementKind {
+        match self {
+            UserStatement::Select(_) | UserStatement::Describe(_) => StatementKind::ReadOnly,
+            UserStatement::Update | UserStatement::Insert | UserStatement::Delete => {
+                StatementKind::Write
+            }
+            UserStatement::CreateTable(_)
+            | UserStatement::CreateIndex(_)
+            | UserStatement::DropTable(_) => StatementKind::Ddl,
+        }
+    }
+}
+
+impl fmt::Display for StatementKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatementKind::ReadOnly => write!(f, "read-only"),
+            StatementKind::Write => write!(f, "write"),
+            StatementKind::Ddl => write!(f, "DDL"),
+            StatementKind::Server => write!(f, "server"),
+        }
+    }
+}
+
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UserStatement {
     Select(SelectExpressionBody),
     Update,
     Insert,
     Delete,
     CreateTable(CreateTableBody),
+    CreateIndex(CreateIndexBody),
+    DropTable(DropTableBody),
+    Describe(DescribeBody),
 }
 
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ServerStatement {
     CreateDatabase(CreateDatabaseBody),
+    DropDatabase(DropDatabaseBody),
+    Set(SetBody),
+    Use(UseBody),
 }
 
 #[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelectExpressionBody {
     pub select_item_list: SelectItemList,
     pub from_clause: Option<FromClause>,
@@ -36,26 +101,148 @@ pub struct SelectExpressionBody {
 }
 
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CreateTableBody {
     pub table_name: Identifier,
+    /// IF NOT EXISTS, so an existing table isn't an error.
+    pub if_not_exists: bool,
     pub column_list: Vec<ColumnDefinition>,
+    pub partition_by: Option<PartitionBy>,
+}
+
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PartitionBy {
+    Range(Identifier),
 }
 
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColumnDefinition {
     pub column_name: Identifier,
     pub datatype: DataType,
     pub nullable: bool,
+    pub primary_key: bool,
+    pub unique: bool,
+    pub default: Option<Expr>,
+    pub generated: Option<GeneratedColumn>,
+}
+
+/// A column whose value is computed from other columns in the row, such as:
+///     Total INT GENERATED ALWAYS AS (Price + Tax) STORED
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeneratedColumn {
+    pub expr: Expr,
+    pub storage: GeneratedStorage,
 }
 
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GeneratedStorage {
+    /// Computed when the row is written, and saved with it.
+    Stored,
+    /// Computed whenever the row is read. The default.
+    Virtual,
+}
+
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DataType {
     Int,
+    Boolean,
+    Blob,
+    Json,
+    Uuid,
+    /// Text of at most the given number of characters.
+    Varchar(u32),
+    Text,
+    Float,
+    Date,
+    BigInt,
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataType::Int => f.write_str("INT"),
+            DataType::Boolean => f.write_str("BOOLEAN"),
+            DataType::Blob => f.write_str("BLOB"),
+            DataType::Json => f.write_str("JSON"),
+            DataType::Uuid => f.write_str("UUID"),
+            DataType::Varchar(length) => write!(f, "VARCHAR({length})"),
+            DataType::Text => f.write_str("TEXT"),
+            DataType::Float => f.write_str("FLOAT"),
+            DataType::Date => f.write_str("DATE"),
+            DataType::BigInt => f.write_str("BIGINT"),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateIndexBody {
+    pub index_name: Option<Identifier>,
+    pub table_name: Identifier,
+    pub key_list: Vec<IndexKey>,
+    pub include_list: Vec<Identifier>,
+    pub where_clause: Option<WhereClause>,
 }
 
+/// One key of an index and the order it's kept in, such as `Name DESC`.
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IndexKey {
+    pub expr: Expr,
+    pub dir: OrderDirection,
+}
+
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CreateDatabaseBody {
     pub database_name: Identifier,
+    /// IF NOT EXISTS, so an existing database isn't an error.
+    pub if_not_exists: bool,
+}
+
+/// Remove a table, such as DROP TABLE IF EXISTS Users.
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropTableBody {
+    pub table_name: Identifier,
+    /// IF EXISTS, so a missing table isn't an error.
+    pub if_exists: bool,
+}
+
+/// List a table's columns, such as DESCRIBE Users.
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DescribeBody {
+    pub table_name: Identifier,
+}
+
+/// Remove a database, such as DROP DATABASE IF EXISTS Sales.
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropDatabaseBody {
+    pub database_name: Identifier,
+    /// IF EXISTS, so a missing database isn't an error.
+    pub if_exists: bool,
+}
+
+/// Switch the session to another database, such as USE Sales.
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UseBody {
+    pub database_name: Identifier,
+}
+
+/// Change a server setting, such as SET cache_size = 1000.
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetBody {
+    pub name: Identifier,
+    pub value: Expr,
 }
 
 impl fmt::Display for SelectExpressionBody {
@@ -90,6 +277,7 @@ impl fmt::Debug for SelectExpressionBody {
 }
 
 #[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelectItemList {
     pub item_list: Vec<SelectItem>,
 }
@@ -114,6 +302,7 @@ impl fmt::Debug for SelectItemList {
 }
 
 #[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelectItem {
     pub expr: Expr,
     pub alias: Option<Identifier>,
@@ -144,8 +333,7 @@ impl SelectItem {
 
     pub fn simple_identifier(identifier: &str) -> Self {
         SelectItem {
-            // todo: change to identifier
-            expr: Expr::Value(Value::String(String::from(identifier), QuoteType::None)),
+            expr: Expr::Identifier(Identifier::from(String::from(identifier))),
             alias: None,
         }
     }
@@ -171,8 +359,7 @@ impl SelectItem {
 
     pub fn aliased_identifier(identifier: &str, alias: Identifier) -> Self {
         SelectItem {
-            // todo: change to identifier
-            expr: Expr::Value(Value::String(String::from(identifier), QuoteType::None)),
+            expr: Expr::Identifier(Identifier::from(String::from(identifier))),
             alias: Some(alias),
         }
     }
@@ -191,20 +378,127 @@ impl SelectItem {
 }
 
 #[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FromClause {
-    pub identifier: Identifier,
+    pub source: TableSource,
     pub alias: Option<Identifier>,
+    pub joins: Vec<Join>,
+}
+
+/// What a FROM clause reads from.
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TableSource {
+    Table(TableName),
+    /// A subquery, such as FROM (SELECT 1 AS x) t. It must have an alias.
+    Derived(Box<SelectExpressionBody>),
 }
 
 impl fmt::Display for FromClause {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self.alias {
-            Some(a) => write!(f, "{} AS {}", self.identifier, a),
-            None => write!(f, "{}", self.identifier),
+            Some(a) => write!(f, "{} AS {}", self.source, a)?,
+            None => write!(f, "{}", self.source)?,
+        }
+
+        for join in &self.joins {
+            write!(f, " {}", join)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Join {
+    pub join_type: JoinType,
+    pub identifier: TableName,
+    pub alias: Option<Identifier>,
+    pub on: Expr,
+}
+
+impl fmt::Display for Join {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} JOIN {}", self.join_type, self.identifier)?;
+
+        if let Some(a) = &self.alias {
+            write!(f, " AS {}", a)?;
+        }
+
+        write!(f, " ON {}", self.on)
+    }
+}
+
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JoinType {
+    Inner,
+    Left,
+    Right,
+}
+
+impl fmt::Display for JoinType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JoinType::Inner => write!(f, "INNER"),
+            JoinType::Left => write!(f, "LEFT"),
+            JoinType::Right => write!(f, "RIGHT"),
+        }
+    }
+}
+
+/// A table, optionally qualified by the database it's in, such as Users or Sales.Users.
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableName {
+    pub database: Option<Identifier>,
+    pub table: Identifier,
+}
+
+impl TableName {
+    pub fn qualified(database: Identifier, table: Identifier) -> Self {
+        TableName {
+            database: Some(database),
+            table,
+        }
+    }
+}
+
+impl From<Identifier> for TableName {
+    fn from(table: Identifier) -> Self {
+        TableName {
+            database: None,
+            table,
         }
     }
 }
 
+impl fmt::Display for TableName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.database {
+            Some(database) => write!(f, "{database}.{}", self.table),
+            None => write!(f, "{}", self.table),
+        }
+    }
+}
+
+impl fmt::Display for TableSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableSource::Table(name) => write!(f, "{name}"),
+            TableSource::Derived(body) => write!(f, "({body})"),
+        }
+    }
+}
+
+impl fmt::Debug for TableSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Passthrough to fmt::Display
+        write!(f, "{}", self)
+    }
+}
+
 impl fmt::Debug for FromClause {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // Passthrough to fmt::Display
@@ -213,6 +507,7 @@ impl fmt::Debug for FromClause {
 }
 
 #[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WhereClause {
     pub expr: Expr,
 }
@@ -231,14 +526,15 @@ impl fmt::Debug for WhereClause {
 }
 
 #[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OrderByClause {
-    pub identifier: Identifier,
+    pub expr: Box<Expr>,
     pub dir: OrderDirection,
 }
 
 impl fmt::Display for OrderByClause {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} {}", self.identifier, self.dir)
+        write!(f, "{} {}", self.expr, self.dir)
     }
 }
 
@@ -250,13 +546,14 @@ impl fmt::Debug for OrderByClause {
 }
 
 #[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GroupByClause {
-    pub identifier: Identifier,
+    pub expr: Box<Expr>,
 }
 
 impl fmt::Display for GroupByClause {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.identifier)
+        write!(f, "{}", self.expr)
     }
 }
 
@@ -268,6 +565,7 @@ impl fmt::Debug for GroupByClause {
 }
 
 #[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Expr {
     IsTrue(Box<Expr>),
     IsNotTrue(Box<Expr>),
@@ -283,6 +581,17 @@ pub enum Expr {
         expr: Box<Expr>,
         list: Vec<Expr>,
     },
+    IsInSubquery {
+        expr: Box<Expr>,
+        subquery: Box<SelectExpressionBody>,
+    },
+    IsNotInSubquery {
+        expr: Box<Expr>,
+        subquery: Box<SelectExpressionBody>,
+    },
+    Exists(Box<SelectExpressionBody>),
+    /// A SELECT used as a value, such as (SELECT MAX(Id) FROM Users).
+    Subquery(Box<SelectExpressionBody>),
     Between {
         expr: Box<Expr>,
         lower: Box<Expr>,
@@ -306,16 +615,96 @@ pub enum Expr {
         op: BinaryOperator,
         right: Box<Expr>,
     },
+    UnaryOperator {
+        op: UnaryOperator,
+        expr: Box<Expr>,
+    },
+    Cast {
+        expr: Box<Expr>,
+        data_type: DataType,
+    },
     Value(Value),
     Identifier(Identifier),
     QualifiedIdentifier(Vec<Identifier>),
+    Function {
+        name: Identifier,
+        args: Vec<Expr>,
+        distinct: bool,
+    },
+    Parameter(Parameter),
     Wildcard,
 }
 
+/// Functions which combine the values of many rows into one.
+pub const AGGREGATE_FUNCTIONS: [&str; 5] = ["COUNT", "SUM", "AVG", "MIN", "MAX"];
+
+impl Expr {
+    /// Whether this is a call to an aggregate function, such as COUNT(*).
+    pub fn is_aggregate(&self) -> bool {
+        match self {
+            Expr::Function { name, .. } => AGGREGATE_FUNCTIONS
+                .iter()
+                .any(|aggregate| aggregate.eq_ignore_ascii_case(&name.value)),
+            _ => false,
+        }
+    }
+
+    /// Find the first expression, either this one or one nested inside it, which matches the predicate.
+    /// Subqueries aren't searched, as their expressions belong to another SELECT.
+    pub fn find(&self, predicate: &dyn Fn(&Expr) -> bool) -> Option<&Expr> {
+        if predicate(self) {
+            return Some(self);
+        }
+
+        match self {
+            Expr::IsTrue(expr)
+            | Expr::IsNotTrue(expr)
+            | Expr::IsFalse(expr)
+            | Expr::IsNotFalse(expr)
+            | Expr::IsNull(expr)
+            | Expr::IsNotNull(expr)
+            | Expr::IsInSubquery { expr, .. }
+            | Expr::IsNotInSubquery { expr, .. }
+            | Expr::UnaryOperator { expr, .. }
+            | Expr::Cast { expr, .. } => expr.find(predicate),
+            Expr::IsIn { expr, list } | Expr::IsNotIn { expr, list } => expr
+                .find(predicate)
+                .or_else(|| list.iter().find_map(|x| x.find(predicate))),
+            Expr::Between {
+                expr,
+                lower,
+                higher,
+            }
+            | Expr::NotBetween {
+                expr,
+                lower,
+                higher,
+            } => expr
+                .find(predicate)
+                .or_else(|| lower.find(predicate))
+                .or_else(|| higher.find(predicate)),
+            Expr::Like { expr, pattern } | Expr::NotLike { expr, pattern } => {
+                expr.find(predicate).or_else(|| pattern.find(predicate))
+            }
+            Expr::BinaryOperator { left, right, .. } => {
+                left.find(predicate).or_else(|| right.find(predicate))
+            }
+            Expr::Function { args, .. } => args.iter().find_map(|x| x.find(predicate)),
+            Expr::Exists(_)
+            | Expr::Subquery(_)
+            | Expr::Value(_)
+            | Expr::Identifier(_)
+            | Expr::QualifiedIdentifier(_)
+            | Expr::Parameter(_)
+            | Expr::Wildcard => None,
+        }
+    }
+}
+
 impl fmt::Display for Expr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Expr::IsTrue(s) => write!(f, "{s}"),
+            Expr::IsTrue(e) => write!(f, "{e} IS TRUE"),
             Expr::IsNotTrue(e) => write!(f, "{e} IS NOT TRUE"),
             Expr::IsFalse(e) => write!(f, "{e} IS FALSE"),
             Expr::IsNotFalse(e) => write!(f, "{e} IS NOT FALSE"),
@@ -336,6 +725,15 @@ impl fmt::Display for Expr {
             Expr::Like { expr, pattern } => write!(f, "{expr} LIKE {pattern}"),
             Expr::NotLike { expr, pattern } => write!(f, "{expr} NOT LIKE {pattern}"),
             Expr::BinaryOperator { left, op, right } => write!(f, "({left} {op} {right})"),
+            Expr::IsInSubquery { expr, subquery } => write!(f, "{expr} IN ({subquery})"),
+            Expr::IsNotInSubquery { expr, subquery } => write!(f, "{expr} NOT IN ({subquery})"),
+            Expr::Exists(subquery) => write!(f, "EXISTS ({subquery})"),
+            Expr::Subquery(subquery) => write!(f, "({subquery})"),
+            Expr::Cast { expr, data_type } => write!(f, "CAST({expr} AS {data_type})"),
+            Expr::UnaryOperator { op, expr } => match op {
+                UnaryOperator::Not => write!(f, "({op} {expr})"),
+                UnaryOperator::Minus | UnaryOperator::Plus => write!(f, "({op}{expr})"),
+            },
             Expr::Value(v) => write!(f, "{v:?}"),
             Expr::Identifier(i) => write!(f, "{i:?}"),
             Expr::QualifiedIdentifier(i) => {
@@ -347,6 +745,23 @@ impl fmt::Display for Expr {
 
                 write!(f, "{joined:?}")
             }
+            Expr::Function {
+                name,
+                args,
+                distinct,
+            } => {
+                let joined = args
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                match distinct {
+                    true => write!(f, "{}(DISTINCT {joined})", name.value),
+                    false => write!(f, "{}({joined})", name.value),
+                }
+            }
+            Expr::Parameter(p) => write!(f, "{p}"),
             Expr::Wildcard => write!(f, "*"),
         }
     }
@@ -360,6 +775,7 @@ impl fmt::Debug for Expr {
 }
 
 #[derive(PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BinaryOperator {
     Plus,
     Minus,
@@ -378,6 +794,8 @@ pub enum BinaryOperator {
     BitwiseOr,
     BitwiseAnd,
     BitwiseXor,
+    JsonExtract,
+    JsonExtractText,
 }
 
 impl fmt::Display for BinaryOperator {
@@ -400,6 +818,8 @@ impl fmt::Display for BinaryOperator {
             BinaryOperator::BitwiseOr => f.write_str("|"),
             BinaryOperator::BitwiseAnd => f.write_str("&"),
             BinaryOperator::BitwiseXor => f.write_str("^"),
+            BinaryOperator::JsonExtract => f.write_str("->"),
+            BinaryOperator::JsonExtractText => f.write_str("->>"),
         }
     }
 }
@@ -411,7 +831,33 @@ impl fmt::Debug for BinaryOperator {
     }
 }
 
+#[derive(PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnaryOperator {
+    Not,
+    Minus,
+    Plus,
+}
+
+impl fmt::Display for UnaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnaryOperator::Not => f.write_str("NOT"),
+            UnaryOperator::Minus => f.write_str("-"),
+            UnaryOperator::Plus => f.write_str("+"),
+        }
+    }
+}
+
+impl fmt::Debug for UnaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Passthrough to fmt::Display
+        write!(f, "{}", self)
+    }
+}
+
 #[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum QuoteType {
     None,
     Single,
@@ -419,10 +865,12 @@ pub enum QuoteType {
 }
 
 #[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Value {
     Number(String),
     String(String, QuoteType),
     Boolean(bool),
+    Blob(Vec<u8>),
     Null,
 }
 
@@ -447,6 +895,13 @@ impl fmt::Display for Value {
                 true => "TRUE",
                 false => "FALSE",
             }),
+            Value::Blob(bytes) => {
+                f.write_str("X'")?;
+                for byte in bytes {
+                    write!(f, "{byte:02X}")?;
+                }
+                f.write_str("'")
+            }
             Value::Null => f.write_str("NULL"),
         }
     }
@@ -459,7 +914,37 @@ impl fmt::Debug for Value {
     }
 }
 
+/// A placeholder for a value bound when a prepared statement is executed.
 #[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Parameter {
+    /// A ? placeholder. Numbered by its position among the other ? placeholders, from 1.
+    Anonymous(usize),
+    /// A numbered placeholder, such as $1.
+    Positional(usize),
+    /// A named placeholder, such as :name.
+    Named(String),
+}
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Parameter::Anonymous(_) => write!(f, "?"),
+            Parameter::Positional(n) => write!(f, "${n}"),
+            Parameter::Named(name) => write!(f, ":{name}"),
+        }
+    }
+}
+
+impl fmt::Debug for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Passthrough to fmt::Display
+        write!(f, "{}", self)
+    }
+}
+
+#[derive(PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OrderDirection {
     Asc,
     Desc,
@@ -482,6 +967,7 @@ impl fmt::Debug for OrderDirection {
 }
 
 #[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Identifier {
     pub value: String,
 }
@@ -504,3 +990,45 @@ impl Identifier {
         Identifier { value }
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod ast_serde_tests {
+    use crate::ast::{Expr, Program, Statement, UserStatement};
+    use crate::Parser;
+
+    fn parse(query: &str) -> Program {
+        let query = String::from(query);
+        let program = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        program.unwrap()
+    }
+
+    #[test]
+    fn test_program_round_trips_through_json() {
+        let program = parse(
+            "SELECT 1 + 2, COUNT(DISTINCT Id) FROM Users u JOIN Orders ON u.Id = Orders.UserId WHERE Name LIKE 'a%' ORDER BY Id DESC; CREATE TABLE t (Name VARCHAR(10), Total INT GENERATED ALWAYS AS (1 + 2) STORED)",
+        );
+
+        let json = serde_json::to_string(&program).unwrap();
+        let decoded: Program = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(decoded, program);
+    }
+
+    #[test]
+    fn test_transformed_json_deserializes() {
+        let Program::Statements(statements) = parse("SELECT 1") else {
+            panic!("Expected statements");
+        };
+        let Statement::User(UserStatement::Select(body)) = &statements[0] else {
+            panic!("Expected a SELECT");
+        };
+
+        let mut json = serde_json::to_value(&body.select_item_list.item_list[0].expr).unwrap();
+        json["Value"]["Number"] = serde_json::Value::from("2");
+
+        let expr: Expr = serde_json::from_value(json).unwrap();
+
+        assert_eq!(expr.to_string(), "2");
+    }
+}
@@ -1,21 +1,28 @@
+use std::collections::VecDeque;
+
 use cli_common::{ParseError, ParseErrorKind};
-use core::panic;
 
 use ast::*;
 use lexer::token::{
-    Arithmetic, Bitwise, Comparison, Ident as LexerIdent, Keyword, LocatableToken, Logical, Slice,
-    Token, Value as LexerValue,
+    Arithmetic, Bitwise, Comparison, Ident as LexerIdent, Keyword, LocatableToken, Logical,
+    Parameter as LexerParameter, Slice, Token, Value as LexerValue,
 };
 use recursion::*;
 
 pub mod ast;
+pub mod lint;
 mod recursion;
+mod to_sql;
 
 pub struct Parser<'a> {
-    tokens: Vec<LocatableToken>,
+    tokens: Box<dyn Iterator<Item = LocatableToken> + 'a>,
+    lookahead_tokens: VecDeque<LocatableToken>, // The next LOOKAHEAD_TOKENS tokens, if there are that many
+    prev_token: Option<LocatableToken>,
     buf: &'a str,
     recursion_guard: RecursionGuard,
     errors: Vec<ParseError>,
+    anonymous_parameter_count: usize,
+    options: ParserOptions,
     pub curr_pos: usize,
 }
 
@@ -23,44 +30,86 @@ pub struct Parser<'a> {
 /// If it does, that's one crazy query.
 const MAX_DEPTH: usize = 50;
 
+/// The deepest `max_depth` can be set to. Expressions nested much deeper than this
+/// overflow the stack while being parsed.
+pub const MAX_DEPTH_LIMIT: usize = 500;
+
+/// Limits on the size of what the parser accepts. Exceeding one is a parse error.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParserOptions {
+    /// How deeply expressions can nest.
+    pub max_depth: usize,
+    /// How many statements a single program can hold.
+    pub max_statements: usize,
+    /// How many items a single SELECT list can hold.
+    pub max_select_items: usize,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        ParserOptions {
+            max_depth: MAX_DEPTH,
+            max_statements: usize::MAX,
+            max_select_items: usize::MAX,
+        }
+    }
+}
+
+/// How many tokens the parser can see ahead of the current position.
+const LOOKAHEAD_TOKENS: usize = 2;
+
 impl<'a> Parser<'a> {
-    pub fn new(tokens: Vec<LocatableToken>, buf: &'a str) -> Parser {
+    /// Create a new parser over any stream of tokens, such as a `Vec` or `Lexer::iter`.
+    /// Tokens are pulled from the stream only as the parser needs them.
+    pub fn new<I>(tokens: I, buf: &'a str) -> Parser<'a>
+    where
+        I: IntoIterator<Item = LocatableToken>,
+        I::IntoIter: 'a,
+    {
+        let mut tokens: Box<dyn Iterator<Item = LocatableToken> + 'a> =
+            Box::new(tokens.into_iter());
+        let lookahead_tokens = tokens.by_ref().take(LOOKAHEAD_TOKENS).collect();
+        let options = ParserOptions::default();
+
         Parser {
             tokens,
+            lookahead_tokens,
+            prev_token: None,
             buf,
-            recursion_guard: RecursionGuard::new(MAX_DEPTH),
+            recursion_guard: RecursionGuard::new(options.max_depth),
             errors: vec![],
+            anonymous_parameter_count: 0,
+            options,
             curr_pos: 0,
         }
     }
 
+    /// Replace the default limits.
+    pub fn with_options(mut self, options: ParserOptions) -> Self {
+        self.recursion_guard = RecursionGuard::new(options.max_depth);
+        self.options = options;
+        self
+    }
+
     /// Create a new parser, but without token positions.
     /// Largely used just for testing.
     pub fn new_positionless(tokens: Vec<Token>, buf: &'a str) -> Parser<'a> {
-        Parser {
-            tokens: tokens
-                .iter()
-                .map(|t| LocatableToken {
-                    token: *t,
-                    position: 0,
-                })
-                .collect(),
-            buf,
-            recursion_guard: RecursionGuard::new(MAX_DEPTH),
-            errors: vec![],
-            curr_pos: 0,
-        }
+        let tokens = tokens
+            .into_iter()
+            .map(|t| LocatableToken::new(t, Slice::new(0, 0)));
+
+        Parser::new(tokens, buf)
     }
 
     pub fn parse(&mut self) -> Result<Program, Vec<ParseError>> {
-        if self.tokens.is_empty() {
+        if self.lookahead_tokens.is_empty() {
             return Ok(Program::Statements(vec![]));
         }
 
         let parse_result = self.parse_program();
 
         match self.errors.is_empty() {
-            true => Ok(parse_result.unwrap()),
+            true => Ok(parse_result.unwrap_or(Program::Empty)),
             false => Err(self.errors.clone()),
         }
     }
@@ -76,19 +125,35 @@ impl<'a> Parser<'a> {
                 break;
             }
 
+            self.next_significant_token();
+
             if let Some(Token::EOF) = self.peek() {
                 parsed_full = true;
                 self.eat();
                 break;
             }
 
-            self.next_significant_token();
+            if statements.len() >= self.options.max_statements {
+                self.push_error(ParseErrorKind::TooManyStatements(
+                    self.options.max_statements,
+                ));
+                break;
+            }
 
-            let query = self.parse_query();
+            let error_count = self.errors.len();
 
-            match query {
+            // Keep going after a bad statement, so later statements still get their errors reported
+            match self.parse_query() {
                 Some(q) => statements.push(q),
-                None => break,
+                None => {
+                    // Not every path which gives up reports why, so make sure the statement
+                    // isn't dropped silently
+                    if self.errors.len() == error_count {
+                        self.push_error(ParseErrorKind::UnsupportedSyntax);
+                    }
+
+                    self.skip_statement();
+                }
             }
         }
 
@@ -111,18 +176,42 @@ impl<'a> Parser<'a> {
             Some(Token::Keyword(Keyword::Update)) => self.parse_update_statement(),
             Some(Token::Keyword(Keyword::Delete)) => self.parse_delete_statement(),
             Some(Token::Keyword(Keyword::Create)) => self.parse_create_statement(),
+            Some(Token::Keyword(Keyword::Drop)) => self.parse_drop_statement(),
+            Some(Token::Keyword(Keyword::Describe | Keyword::Desc)) => {
+                self.parse_describe_statement()
+            }
+            // PRAGMA is another way to write SET, for those used to SQLite
+            Some(Token::Keyword(Keyword::Set | Keyword::Pragma)) => self.parse_set_statement(),
+            Some(Token::Keyword(Keyword::Use)) => self.parse_use_statement(),
             _ => {
                 self.push_error(ParseErrorKind::ExpectedStatemnt);
                 None
             }
-        };
+        }?;
 
         self.next_significant_token();
         if self.lookahead(Token::Semicolon) {
             self.match_(Token::Semicolon);
         }
 
-        query
+        Some(query)
+    }
+
+    /// Skip the rest of a statement that failed to parse, up to and including its semicolon.
+    /// Stops before EOF, so the program can still end cleanly.
+    fn skip_statement(&mut self) {
+        loop {
+            match self.peek() {
+                Some(Token::EOF) | None => break,
+                Some(Token::Semicolon) => {
+                    self.eat();
+                    break;
+                }
+                Some(_) => {
+                    self.eat();
+                }
+            }
+        }
     }
 
     fn parse_select_statement(&mut self) -> Option<Statement> {
@@ -142,7 +231,7 @@ impl<'a> Parser<'a> {
 
         let select_item_list = self.parse_select_item_list()?;
         let from_clause = self.parse_from_clause_optional();
-        let where_clause = self.parse_where_clause_optional();
+        let where_clause = self.parse_where_clause_optional()?;
         let group_by_clause = self.parse_group_by_clause_optional();
         let order_by_clause = self.parse_order_by_clause_optional();
 
@@ -163,6 +252,13 @@ impl<'a> Parser<'a> {
         self.next_significant_token();
 
         while self.lookahead(Token::Comma) {
+            if item_list.len() >= self.options.max_select_items {
+                self.push_error(ParseErrorKind::TooManySelectItems(
+                    self.options.max_select_items,
+                ));
+                return None;
+            }
+
             self.match_(Token::Comma);
             self.next_significant_token();
             item_list.push(self.parse_select_item()?);
@@ -173,123 +269,129 @@ impl<'a> Parser<'a> {
 
     /// Parse a select item, such as:
     ///     Name
-    ///     1 + 2
+    ///     1 + 2 AS Three
     ///     *
     ///     'Hello'
     ///     users.Name
-    ///     users.Email AS UserEmail
+    ///     users.Email UserEmail
     fn parse_select_item(&mut self) -> Option<SelectItem> {
-        match self.peek() {
+        let expr = match self.peek() {
             Some(Token::Arithmetic(Arithmetic::Multiply)) => {
                 self.eat();
-                Some(SelectItem::new(Expr::Wildcard))
+                return Some(SelectItem::new(Expr::Wildcard));
             }
-            Some(Token::Identifier(_)) => self.parse_object_name(),
-            _ => {
-                let expr = self.parse_expr();
-
-                match expr {
-                    Some(e) => Some(SelectItem::new(e)),
-                    None => {
-                        self.push_error(ParseErrorKind::ExpectedIdentifier);
-                        None
-                    }
+            Some(_) if self.is_function_call() => self.parse_expr()?,
+            Some(_) if self.peek_identifier().is_some() => self.parse_object_name()?,
+            _ => match self.parse_expr() {
+                Some(e) => e,
+                None => {
+                    self.push_error(ParseErrorKind::ExpectedIdentifier);
+                    return None;
                 }
-            }
+            },
+        };
+
+        match self.parse_select_item_alias() {
+            Some(alias) => Some(SelectItem::aliased(expr, alias)),
+            None => Some(SelectItem::new(expr)),
         }
     }
 
-    /// Parse a potentially qualified and aliased object name,
+    /// Parse a select item starting with a potentially qualified object name,
     /// such as:
     ///     name
-    ///     id AS UserId
     ///     users.name
-    ///     users.email AS UserEmail
-    fn parse_object_name(&mut self) -> Option<SelectItem> {
-        let slice = match self.peek() {
-            Some(Token::Identifier(LexerIdent { value })) => Some(value),
-            _ => None,
-        }
-        .unwrap();
+    ///     sales.users.email
+    ///     users.price * 2
+    fn parse_object_name(&mut self) -> Option<Expr> {
+        let slice = self.peek_identifier()?;
 
-        let identifier_str = String::from(self.resolve_slice(slice));
+        let identifier_str = String::from(self.resolve_slice(&slice));
         self.eat();
 
-        let qualified_identifier = self.parse_qualified_identifier();
-        let alias = self.pase_identifier_alias();
-
-        match qualified_identifier {
-            Some(qualified) => {
-                let qualified_select_item = match alias {
-                    Some(alias) => SelectItem::aliased_qualified_identifier(
-                        vec![&identifier_str, &qualified],
-                        alias,
-                    ),
-                    None => SelectItem::qualified_identifier(vec![&identifier_str, &qualified]),
-                };
-
-                Some(qualified_select_item)
-            }
-            None => {
-                let select_item = match alias {
-                    Some(alias) => SelectItem::aliased_identifier(&identifier_str, alias),
-                    None => SelectItem::simple_identifier(&identifier_str),
-                };
+        let qualifiers = self.parse_qualifiers()?;
+        let is_expression = self.next_expr_precedence() > 0;
+
+        let expr = match qualifiers.is_empty() {
+            true => Expr::Identifier(Identifier::from(identifier_str)),
+            false => Expr::QualifiedIdentifier(
+                std::iter::once(identifier_str)
+                    .chain(qualifiers)
+                    .map(Identifier::from)
+                    .collect(),
+            ),
+        };
 
-                Some(select_item)
-            }
+        match is_expression {
+            true => self.parse_infixes(expr, 0),
+            false => Some(expr),
         }
     }
 
-    /// Parse an optional identifier qualifier, i.e. the identifier
-    /// following the dot in:
+    /// Parse the identifiers following each dot in a qualified name, i.e. the name and email in:
     ///     users.name
     /// or
-    ///     u.email
-    fn parse_qualified_identifier(&mut self) -> Option<String> {
+    ///     sales.u.email
+    /// Returns an empty list if the name isn't qualified. There's no limit on the number of parts,
+    /// the binder checks how many each position allows.
+    fn parse_qualifiers(&mut self) -> Option<Vec<String>> {
+        let mut qualifiers = vec![];
+
         self.next_significant_token();
-        match self.peek() {
-            Some(Token::Dot) => {
-                self.eat();
+        while self.lookahead(Token::Dot) {
+            self.eat();
 
-                match self.peek() {
-                    Some(Token::Identifier(LexerIdent { value })) => {
-                        let identifier = Some(String::from(self.resolve_slice(value)));
-                        self.eat();
-                        identifier
-                    }
-                    _ => {
-                        self.push_error(ParseErrorKind::ExpectedIdentifier);
-                        None
-                    }
-                }
-            }
-            _ => None,
+            let Some(value) = self.peek_identifier() else {
+                self.push_error(ParseErrorKind::ExpectedIdentifier);
+                return None;
+            };
+
+            qualifiers.push(String::from(self.resolve_slice(&value)));
+            self.eat();
+            self.next_significant_token();
         }
+
+        Some(qualifiers)
     }
 
-    /// Parse an optional alias, such as:
+    /// Parse an optional select item alias, such as any of:
     ///     name AS UserName
-    fn pase_identifier_alias(&mut self) -> Option<Identifier> {
+    ///     name UserName
+    ///     name AS "User Name"
+    ///     name AS 'User Name'
+    /// Without AS, keywords and single-quoted strings aren't taken as the alias.
+    fn parse_select_item_alias(&mut self) -> Option<Identifier> {
         self.next_significant_token();
-        match self.peek() {
-            Some(Token::Keyword(Keyword::As)) => {
-                self.eat();
-                self.next_significant_token();
 
-                match self.peek() {
-                    Some(Token::Identifier(ident)) => {
-                        let value = Identifier {
-                            value: String::from(self.resolve_slice(&ident.value)),
-                        };
-                        self.eat();
-                        Some(value)
-                    }
-                    _ => None,
-                }
-            }
-            _ => None,
+        if let Some(
+            Token::Identifier(LexerIdent { value }) | Token::Value(LexerValue::DoubleQuoted(value)),
+        ) = self.peek()
+        {
+            let alias = Identifier::from(String::from(self.resolve_slice(value)));
+            self.eat();
+            return Some(alias);
         }
+
+        if !self.match_(Token::Keyword(Keyword::As)) {
+            return None;
+        }
+
+        self.next_significant_token();
+
+        let value = match self.peek() {
+            Some(Token::Value(LexerValue::SingleQuoted(value))) => *value,
+            _ => match self.peek_identifier() {
+                Some(value) => value,
+                None => {
+                    self.push_error(ParseErrorKind::ExpectedIdentifier);
+                    return None;
+                }
+            },
+        };
+
+        let alias = Identifier::from(String::from(self.resolve_slice(&value)));
+        self.eat();
+        Some(alias)
     }
 
     fn parse_from_clause_optional(&mut self) -> Option<FromClause> {
@@ -297,28 +399,130 @@ impl<'a> Parser<'a> {
 
         if self.match_(Token::Keyword(Keyword::From)) {
             self.next_significant_token();
-            match self.peek() {
-                Some(Token::Identifier(LexerIdent { value })) => {
-                    let identifier_str = String::from(self.resolve_slice(value));
-                    self.eat();
 
-                    let alias = self.parse_table_alias();
+            if self.lookahead(Token::ParenOpen) {
+                return self.parse_derived_table();
+            }
 
-                    Some(FromClause {
-                        identifier: Identifier {
-                            value: identifier_str,
-                        },
-                        alias,
-                    })
+            let table_name = self.parse_table_name()?;
+            let alias = self.parse_table_alias();
+            let joins = self.parse_joins()?;
+
+            Some(FromClause {
+                source: TableSource::Table(table_name),
+                alias,
+                joins,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Parse a subquery in a FROM clause, which must be aliased, for example:
+    ///     (SELECT 1 AS x) t
+    fn parse_derived_table(&mut self) -> Option<FromClause> {
+        self.match_(Token::ParenOpen);
+        self.next_significant_token();
+
+        if !self.lookahead(Token::Keyword(Keyword::Select)) {
+            self.push_error(ParseErrorKind::ExpectedKeyword(String::from("SELECT")));
+            return None;
+        }
+
+        let body = self.parse_select_expression_body()?;
+
+        self.next_significant_token();
+
+        if !self.match_(Token::ParenClose) {
+            self.push_error(ParseErrorKind::ExpectedParentheses(")".to_string()));
+            return None;
+        }
+
+        let Some(alias) = self.parse_table_alias() else {
+            self.push_error(ParseErrorKind::ExpectedAlias);
+            return None;
+        };
+
+        let joins = self.parse_joins()?;
+
+        Some(FromClause {
+            source: TableSource::Derived(Box::new(body)),
+            alias: Some(alias),
+            joins,
+        })
+    }
+
+    /// Parse any joins following the first table in a FROM clause, such as:
+    ///     INNER JOIN Orders o ON u.Id = o.UserId
+    ///     LEFT JOIN Orders ON Users.Id = Orders.UserId
+    ///     JOIN Orders o ON u.Id = o.UserId
+    fn parse_joins(&mut self) -> Option<Vec<Join>> {
+        let mut joins = vec![];
+
+        loop {
+            self.next_significant_token();
+
+            let join_type = match self.peek() {
+                Some(Token::Keyword(Keyword::Join)) => JoinType::Inner,
+                Some(Token::Keyword(Keyword::Inner)) => {
+                    self.eat();
+                    JoinType::Inner
                 }
-                _ => {
-                    self.push_error(ParseErrorKind::ExpectedIdentifier);
-                    None
+                Some(Token::Keyword(Keyword::Left)) => {
+                    self.eat();
+                    JoinType::Left
+                }
+                Some(Token::Keyword(Keyword::Right)) => {
+                    self.eat();
+                    JoinType::Right
                 }
+                _ => break,
+            };
+
+            self.next_significant_token();
+
+            if !self.match_(Token::Keyword(Keyword::Join)) {
+                self.push_error(ParseErrorKind::ExpectedKeyword(String::from("JOIN")));
+                return None;
             }
-        } else {
-            None
+
+            let identifier = self.parse_table_name()?;
+            let alias = self.parse_table_alias();
+
+            self.next_significant_token();
+
+            if !self.match_(Token::Keyword(Keyword::On)) {
+                self.push_error(ParseErrorKind::ExpectedKeyword(String::from("ON")));
+                return None;
+            }
+
+            let on = self.parse_expr()?;
+
+            joins.push(Join {
+                join_type,
+                identifier,
+                alias,
+                on,
+            });
+        }
+
+        Some(joins)
+    }
+
+    /// Parse a table name, optionally qualified by its database, such as:
+    ///     Users
+    ///     Sales.Users
+    fn parse_table_name(&mut self) -> Option<TableName> {
+        let name = self.parse_unqualified_object_name()?;
+
+        self.next_significant_token();
+        if !self.match_(Token::Dot) {
+            return Some(TableName::from(name));
         }
+
+        let table = self.parse_unqualified_object_name()?;
+
+        Some(TableName::qualified(name, table))
     }
 
     /// Parse an optional identifier, assumed to be following a table name, for example:
@@ -337,16 +541,26 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn parse_where_clause_optional(&mut self) -> Option<WhereClause> {
+    /// Parse an optional WHERE clause.
+    /// Returns Some(None) if there is no clause, and None if it has no valid predicate.
+    fn parse_where_clause_optional(&mut self) -> Option<Option<WhereClause>> {
         self.next_significant_token();
 
-        if self.match_(Token::Keyword(Keyword::Where)) {
-            let expr = self.parse_expr()?;
-
-            Some(WhereClause { expr })
-        } else {
-            None
+        if !self.match_(Token::Keyword(Keyword::Where)) {
+            return Some(None);
         }
+
+        let error_count = self.errors.len();
+
+        let Some(expr) = self.parse_expr() else {
+            if self.errors.len() == error_count {
+                self.push_error(ParseErrorKind::ExpectedExpression);
+            }
+
+            return None;
+        };
+
+        Some(Some(WhereClause { expr }))
     }
 
     fn parse_order_by_clause_optional(&mut self) -> Option<OrderByClause> {
@@ -358,25 +572,13 @@ impl<'a> Parser<'a> {
             if self.match_(Token::Keyword(Keyword::By)) {
                 self.next_significant_token();
 
-                match self.peek() {
-                    Some(Token::Identifier(LexerIdent { value })) => {
-                        let identifier_str = String::from(self.resolve_slice(value));
-                        self.eat();
-
-                        let dir = self.parse_order_direction();
+                let expr = self.parse_expr()?;
+                let dir = self.parse_order_direction();
 
-                        Some(OrderByClause {
-                            identifier: Identifier {
-                                value: identifier_str,
-                            },
-                            dir,
-                        })
-                    }
-                    _ => {
-                        self.push_error(ParseErrorKind::ExpectedIdentifier);
-                        None
-                    }
-                }
+                Some(OrderByClause {
+                    expr: Box::new(expr),
+                    dir,
+                })
             } else {
                 self.push_error(ParseErrorKind::ExpectedKeyword(String::from("BY")));
                 None
@@ -410,22 +612,11 @@ impl<'a> Parser<'a> {
             if self.match_(Token::Keyword(Keyword::By)) {
                 self.next_significant_token();
 
-                match self.peek() {
-                    Some(Token::Identifier(LexerIdent { value })) => {
-                        let identifier_str = String::from(self.resolve_slice(value));
-                        self.eat();
+                let expr = self.parse_expr()?;
 
-                        Some(GroupByClause {
-                            identifier: Identifier {
-                                value: identifier_str,
-                            },
-                        })
-                    }
-                    _ => {
-                        self.push_error(ParseErrorKind::ExpectedIdentifier);
-                        None
-                    }
-                }
+                Some(GroupByClause {
+                    expr: Box::new(expr),
+                })
             } else {
                 self.push_error(ParseErrorKind::ExpectedKeyword(String::from("BY")));
                 None
@@ -446,8 +637,32 @@ impl<'a> Parser<'a> {
             return None;
         }
 
-        let mut expr = self.parse_prefix()?;
+        let expr = self.parse_subexpr_within_depth(precedence);
+        self.recursion_guard.inc();
+
+        expr
+    }
+
+    fn parse_subexpr_within_depth(&mut self, precedence: u8) -> Option<Expr> {
+        let expr = self.parse_prefix()?;
+
+        self.parse_infixes_within_depth(expr, precedence)
+    }
+
+    /// Carry on parsing an expression whose first operand has already been parsed.
+    fn parse_infixes(&mut self, expr: Expr, precedence: u8) -> Option<Expr> {
+        if let Err(err) = self.recursion_guard.dec() {
+            self.push_error(err);
+            return None;
+        }
+
+        let expr = self.parse_infixes_within_depth(expr, precedence);
+        self.recursion_guard.inc();
 
+        expr
+    }
+
+    fn parse_infixes_within_depth(&mut self, mut expr: Expr, precedence: u8) -> Option<Expr> {
         loop {
             let next_precedence = self.next_expr_precedence();
 
@@ -464,24 +679,82 @@ impl<'a> Parser<'a> {
     fn parse_prefix(&mut self) -> Option<Expr> {
         self.next_significant_token();
 
+        let identifier = self.peek_identifier();
+
         let expr = match self.peek() {
             Some(token) => match token {
                 Token::Keyword(Keyword::True) | Token::Keyword(Keyword::False) | Token::Null => {
                     let val = self.parse_value();
                     Some(Expr::Value(val?))
                 }
-                Token::Identifier(i) => {
-                    let val = self.buf[i.value.start..i.value.end].to_string();
+                _ if identifier.is_some() => {
+                    let val = identifier.map(|i| self.resolve_slice(&i).to_string())?;
                     self.eat();
 
+                    if self.lookahead(Token::Dot) {
+                        let qualifiers = self.parse_qualifiers()?;
+
+                        return Some(Expr::QualifiedIdentifier(
+                            std::iter::once(val)
+                                .chain(qualifiers)
+                                .map(Identifier::from)
+                                .collect(),
+                        ));
+                    }
+
+                    if self.lookahead(Token::ParenOpen) {
+                        let (args, distinct) = self.parse_function_args()?;
+
+                        return Some(Expr::Function {
+                            name: Identifier::from(val),
+                            args,
+                            distinct,
+                        });
+                    }
+
                     Some(Expr::Identifier(Identifier::from(val)))
                 }
-                Token::Numeric(_) | Token::Value(LexerValue::SingleQuoted(_)) => {
+                Token::Numeric(_)
+                | Token::RadixNumeric(..)
+                | Token::Value(LexerValue::SingleQuoted(_))
+                | Token::Value(LexerValue::Hex(_)) => {
                     let val = self.parse_value();
                     Some(Expr::Value(val?))
                 }
+                Token::Parameter(_) => self.parse_parameter().map(Expr::Parameter),
+                Token::Keyword(Keyword::Cast) => self.parse_cast(),
+                Token::Logical(Logical::Not) => self.parse_unary(UnaryOperator::Not),
+                Token::Arithmetic(Arithmetic::Minus) => self.parse_unary(UnaryOperator::Minus),
+                Token::Arithmetic(Arithmetic::Plus) => self.parse_unary(UnaryOperator::Plus),
+                Token::Keyword(Keyword::Exists) => {
+                    self.eat();
+                    self.next_significant_token();
+
+                    if !self.match_(Token::ParenOpen) {
+                        self.push_error(ParseErrorKind::ExpectedParentheses("(".to_string()));
+                        return None;
+                    }
+
+                    self.next_significant_token();
+
+                    if !self.lookahead(Token::Keyword(Keyword::Select)) {
+                        self.push_error(ParseErrorKind::ExpectedKeyword(String::from("SELECT")));
+                        return None;
+                    }
+
+                    self.parse_subquery()
+                        .map(|subquery| Expr::Exists(Box::new(subquery)))
+                }
                 Token::ParenOpen => {
                     self.match_(Token::ParenOpen);
+                    self.next_significant_token();
+
+                    if self.lookahead(Token::Keyword(Keyword::Select)) {
+                        return self
+                            .parse_subquery()
+                            .map(|subquery| Expr::Subquery(Box::new(subquery)));
+                    }
+
                     let sub_expr = self.parse_subexpr(0);
 
                     if self.match_(Token::ParenClose) {
@@ -499,34 +772,187 @@ impl<'a> Parser<'a> {
         expr
     }
 
-    fn parse_infix(&mut self, expr: Expr, precedence: u8) -> Option<Expr> {
+    /// Parse an explicit type conversion, such as:
+    ///     CAST(a + 1 AS BOOLEAN)
+    fn parse_cast(&mut self) -> Option<Expr> {
+        self.eat();
         self.next_significant_token();
 
-        let binary_op = match self.peek()? {
-            Token::Arithmetic(Arithmetic::Plus) => Some(BinaryOperator::Plus),
-            Token::Arithmetic(Arithmetic::Minus) => Some(BinaryOperator::Minus),
-            Token::Arithmetic(Arithmetic::Multiply) => Some(BinaryOperator::Multiply),
-            Token::Arithmetic(Arithmetic::Divide) => Some(BinaryOperator::Divide),
-            Token::Arithmetic(Arithmetic::Modulo) => Some(BinaryOperator::Modulo),
-            Token::Comparison(Comparison::GreaterThan) => Some(BinaryOperator::GreaterThan),
-            Token::Comparison(Comparison::GreaterThanOrEqual) => {
-                Some(BinaryOperator::GreaterThanOrEqual)
-            }
-            Token::Comparison(Comparison::LessThan) => Some(BinaryOperator::LessThan),
-            Token::Comparison(Comparison::LessThanOrEqual) => Some(BinaryOperator::LessThanOrEqual),
-            Token::Comparison(Comparison::Equal) => Some(BinaryOperator::Equal),
-            Token::Comparison(Comparison::NotEqual) => Some(BinaryOperator::NotEqual),
-            Token::Keyword(Keyword::And) => Some(BinaryOperator::And),
-            Token::Keyword(Keyword::Or) => Some(BinaryOperator::Or),
-            Token::Keyword(Keyword::Xor) => Some(BinaryOperator::Xor),
-            Token::Bitwise(Bitwise::Or) => Some(BinaryOperator::BitwiseOr),
-            Token::Bitwise(Bitwise::And) => Some(BinaryOperator::BitwiseAnd),
-            Token::Bitwise(Bitwise::Xor) => Some(BinaryOperator::BitwiseXor),
-            _ => None,
-        };
-
-        if let Some(op) = binary_op {
-            self.eat();
+        if !self.match_(Token::ParenOpen) {
+            self.push_error(ParseErrorKind::ExpectedParentheses("(".to_string()));
+            return None;
+        }
+
+        let expr = self.parse_expr()?;
+
+        self.next_significant_token();
+
+        if !self.match_(Token::Keyword(Keyword::As)) {
+            self.push_error(ParseErrorKind::ExpectedKeyword(String::from("AS")));
+            return None;
+        }
+
+        self.next_significant_token();
+        let data_type = self.parse_data_type()?;
+        self.next_significant_token();
+
+        if !self.match_(Token::ParenClose) {
+            self.push_error(ParseErrorKind::ExpectedParentheses(")".to_string()));
+            return None;
+        }
+
+        Some(Expr::Cast {
+            expr: Box::new(expr),
+            data_type,
+        })
+    }
+
+    /// Parse a SELECT nested in an expression, up to and including its closing parenthesis.
+    /// The opening parenthesis should already have been matched.
+    fn parse_subquery(&mut self) -> Option<SelectExpressionBody> {
+        let body = self.parse_select_expression_body()?;
+
+        self.next_significant_token();
+
+        if !self.match_(Token::ParenClose) {
+            self.push_error(ParseErrorKind::ExpectedParentheses(")".to_string()));
+            return None;
+        }
+
+        Some(body)
+    }
+
+    /// Parse a prefix operator and its operand, such as:
+    ///     NOT a = b
+    ///     -(1 + 2)
+    /// NOT binds more loosely than comparisons, so the first is NOT (a = b).
+    /// Minus and plus bind more tightly than any binary operator.
+    fn parse_unary(&mut self, op: UnaryOperator) -> Option<Expr> {
+        self.eat();
+
+        let precedence = match op {
+            UnaryOperator::Not => 15,
+            UnaryOperator::Minus | UnaryOperator::Plus => 50,
+        };
+
+        match self.parse_subexpr(precedence) {
+            Some(expr) => Some(Expr::UnaryOperator {
+                op,
+                expr: Box::new(expr),
+            }),
+            None => {
+                self.push_error(ParseErrorKind::ExpectedValue);
+                None
+            }
+        }
+    }
+
+    /// Parse a prepared statement parameter placeholder, such as:
+    ///     ?
+    ///     $1
+    ///     :name
+    fn parse_parameter(&mut self) -> Option<Parameter> {
+        let parameter = match self.peek()? {
+            Token::Parameter(LexerParameter::Anonymous) => {
+                self.anonymous_parameter_count += 1;
+                Parameter::Anonymous(self.anonymous_parameter_count)
+            }
+            Token::Parameter(LexerParameter::Positional(s)) => {
+                match self.resolve_slice(s).parse::<usize>() {
+                    Ok(n) if n > 0 => Parameter::Positional(n),
+                    _ => {
+                        self.push_error(ParseErrorKind::InvalidParameter);
+                        return None;
+                    }
+                }
+            }
+            Token::Parameter(LexerParameter::Named(s)) => {
+                Parameter::Named(self.resolve_slice(s).to_string())
+            }
+            _ => return None,
+        };
+
+        self.eat();
+        Some(parameter)
+    }
+
+    /// Parse the parenthesised argument list of a function call, such as:
+    ///     (a, 1 + 2)
+    ///     (*)
+    ///     (DISTINCT a)
+    ///     ()
+    /// Returns the arguments, and whether they were preceded by DISTINCT.
+    fn parse_function_args(&mut self) -> Option<(Vec<Expr>, bool)> {
+        self.match_(Token::ParenOpen);
+        self.next_significant_token();
+
+        let mut args = vec![];
+        let distinct = self.match_(Token::Keyword(Keyword::Distinct));
+
+        if !distinct && self.match_(Token::ParenClose) {
+            return Some((args, distinct));
+        }
+
+        loop {
+            self.next_significant_token();
+
+            if !distinct && self.match_(Token::Arithmetic(Arithmetic::Multiply)) {
+                args.push(Expr::Wildcard);
+            } else {
+                match self.parse_expr() {
+                    Some(expr) => args.push(expr),
+                    None => {
+                        self.push_error(ParseErrorKind::ExpectedValue);
+                        return None;
+                    }
+                }
+            }
+
+            self.next_significant_token();
+
+            if self.match_(Token::Comma) {
+                continue;
+            }
+
+            if self.match_(Token::ParenClose) {
+                return Some((args, distinct));
+            }
+
+            self.push_error(ParseErrorKind::ExpectedParentheses(")".to_string()));
+            return None;
+        }
+    }
+
+    fn parse_infix(&mut self, expr: Expr, precedence: u8) -> Option<Expr> {
+        self.next_significant_token();
+
+        let binary_op = match self.peek()? {
+            Token::Arithmetic(Arithmetic::Plus) => Some(BinaryOperator::Plus),
+            Token::Arithmetic(Arithmetic::Minus) => Some(BinaryOperator::Minus),
+            Token::Arithmetic(Arithmetic::Multiply) => Some(BinaryOperator::Multiply),
+            Token::Arithmetic(Arithmetic::Divide) => Some(BinaryOperator::Divide),
+            Token::Arithmetic(Arithmetic::Modulo) => Some(BinaryOperator::Modulo),
+            Token::Comparison(Comparison::GreaterThan) => Some(BinaryOperator::GreaterThan),
+            Token::Comparison(Comparison::GreaterThanOrEqual) => {
+                Some(BinaryOperator::GreaterThanOrEqual)
+            }
+            Token::Comparison(Comparison::LessThan) => Some(BinaryOperator::LessThan),
+            Token::Comparison(Comparison::LessThanOrEqual) => Some(BinaryOperator::LessThanOrEqual),
+            Token::Comparison(Comparison::Equal) => Some(BinaryOperator::Equal),
+            Token::Comparison(Comparison::NotEqual) => Some(BinaryOperator::NotEqual),
+            Token::Keyword(Keyword::And) => Some(BinaryOperator::And),
+            Token::Keyword(Keyword::Or) => Some(BinaryOperator::Or),
+            Token::Keyword(Keyword::Xor) => Some(BinaryOperator::Xor),
+            Token::Bitwise(Bitwise::Or) => Some(BinaryOperator::BitwiseOr),
+            Token::Bitwise(Bitwise::And) => Some(BinaryOperator::BitwiseAnd),
+            Token::Bitwise(Bitwise::Xor) => Some(BinaryOperator::BitwiseXor),
+            Token::Arrow => Some(BinaryOperator::JsonExtract),
+            Token::DoubleArrow => Some(BinaryOperator::JsonExtractText),
+            _ => None,
+        };
+
+        if let Some(op) = binary_op {
+            self.eat();
 
             let right = self.parse_subexpr(precedence)?;
 
@@ -537,6 +963,45 @@ impl<'a> Parser<'a> {
             });
         }
 
+        // Handle IN (...)
+        if self.lookahead(Token::Logical(Logical::In)) {
+            self.eat();
+            return self.parse_in(expr, false);
+        }
+
+        // Handle LIKE
+        if self.lookahead(Token::Logical(Logical::Like)) {
+            self.eat();
+            let pattern = self.parse_subexpr(precedence)?;
+
+            return Some(Expr::Like {
+                expr: Box::new(expr),
+                pattern: Box::new(pattern),
+            });
+        }
+
+        // Handle NOT IN and NOT LIKE
+        if self.lookahead(Token::Logical(Logical::Not)) {
+            self.eat();
+            self.next_significant_token();
+
+            if self.match_(Token::Logical(Logical::In)) {
+                return self.parse_in(expr, true);
+            }
+
+            if self.match_(Token::Logical(Logical::Like)) {
+                let pattern = self.parse_subexpr(precedence)?;
+
+                return Some(Expr::NotLike {
+                    expr: Box::new(expr),
+                    pattern: Box::new(pattern),
+                });
+            }
+
+            self.push_error(ParseErrorKind::ExpectedKeyword(String::from("IN or LIKE")));
+            return None;
+        }
+
         // Handle IS [NOT] TRUE/FALSE/NULL
         if self.lookahead(Token::Logical(Logical::Is)) {
             self.eat();
@@ -607,6 +1072,65 @@ impl<'a> Parser<'a> {
         None
     }
 
+    /// Parse what follows [NOT] IN, either a list or a subquery, such as:
+    ///     (1, 2, a + b)
+    ///     (SELECT Id FROM Users)
+    fn parse_in(&mut self, expr: Expr, negated: bool) -> Option<Expr> {
+        self.next_significant_token();
+
+        if !self.match_(Token::ParenOpen) {
+            self.push_error(ParseErrorKind::ExpectedParentheses("(".to_string()));
+            return None;
+        }
+
+        self.next_significant_token();
+        let expr = Box::new(expr);
+
+        if self.lookahead(Token::Keyword(Keyword::Select)) {
+            let subquery = Box::new(self.parse_subquery()?);
+
+            return Some(match negated {
+                false => Expr::IsInSubquery { expr, subquery },
+                true => Expr::IsNotInSubquery { expr, subquery },
+            });
+        }
+
+        let list = self.parse_in_list()?;
+
+        Some(match negated {
+            false => Expr::IsIn { expr, list },
+            true => Expr::IsNotIn { expr, list },
+        })
+    }
+
+    /// Parse a list of expressions up to and including the closing parenthesis.
+    fn parse_in_list(&mut self) -> Option<Vec<Expr>> {
+        let mut list = vec![];
+
+        loop {
+            match self.parse_expr() {
+                Some(expr) => list.push(expr),
+                None => {
+                    self.push_error(ParseErrorKind::ExpectedValue);
+                    return None;
+                }
+            }
+
+            self.next_significant_token();
+
+            if self.match_(Token::Comma) {
+                continue;
+            }
+
+            if self.match_(Token::ParenClose) {
+                return Some(list);
+            }
+
+            self.push_error(ParseErrorKind::ExpectedParentheses(")".to_string()));
+            return None;
+        }
+    }
+
     fn next_expr_precedence(&mut self) -> u8 {
         self.next_significant_token();
         match self.peek() {
@@ -618,13 +1142,17 @@ impl<'a> Parser<'a> {
                 | Token::Comparison(Comparison::GreaterThan)
                 | Token::Comparison(Comparison::GreaterThanOrEqual)
                 | Token::Comparison(Comparison::LessThan)
-                | Token::Comparison(Comparison::LessThanOrEqual) => 20,
+                | Token::Comparison(Comparison::LessThanOrEqual)
+                | Token::Logical(Logical::In)
+                | Token::Logical(Logical::Like) => 20,
                 Token::Bitwise(Bitwise::Or) => 21,
                 Token::Arithmetic(Arithmetic::Plus) | Token::Arithmetic(Arithmetic::Minus) => 30,
                 Token::Arithmetic(Arithmetic::Multiply)
                 | Token::Arithmetic(Arithmetic::Divide)
                 | Token::Arithmetic(Arithmetic::Modulo) => 40,
-                Token::Logical(Logical::Not) => 50,
+                Token::Arrow | Token::DoubleArrow => 45,
+                // NOT following an expression, as in NOT IN or NOT LIKE
+                Token::Logical(Logical::Not) => 20,
                 Token::ParenOpen => 50,
                 _ => 0,
             },
@@ -639,11 +1167,27 @@ impl<'a> Parser<'a> {
                 Token::Keyword(Keyword::True) => Some(Value::Boolean(true)),
                 Token::Keyword(Keyword::False) => Some(Value::Boolean(false)),
                 Token::Numeric(s) => Some(Value::Number(self.buf[s.start..s.end].to_string())),
+                Token::RadixNumeric(s, radix) => {
+                    match u128::from_str_radix(&self.buf[s.start..s.end], radix.base()) {
+                        Ok(n) => Some(Value::Number(n.to_string())),
+                        Err(_) => {
+                            self.push_error(ParseErrorKind::InvalidNumericLiteral);
+                            None
+                        }
+                    }
+                }
                 Token::Value(LexerValue::SingleQuoted(s)) => Some(Value::String(
                     // todo: string interning? we indexing into buf here and maybe not great
                     self.buf[s.start..s.end].to_string(),
                     QuoteType::Single,
                 )),
+                Token::Value(LexerValue::Hex(s)) => match decode_hex(&self.buf[s.start..s.end]) {
+                    Some(bytes) => Some(Value::Blob(bytes)),
+                    None => {
+                        self.push_error(ParseErrorKind::InvalidHexLiteral);
+                        None
+                    }
+                },
                 _ => {
                     self.push_error(ParseErrorKind::ExpectedValue);
                     None
@@ -689,6 +1233,42 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Parse a USE statement, such as:
+    ///     USE Sales
+    fn parse_use_statement(&mut self) -> Option<Statement> {
+        // Eat the 'USE' keyword
+        self.eat();
+
+        let database_name = self.parse_unqualified_object_name()?;
+
+        Some(Statement::Server(ServerStatement::Use(UseBody {
+            database_name,
+        })))
+    }
+
+    /// Parse a SET statement, such as:
+    ///     SET cache_size = 1000
+    fn parse_set_statement(&mut self) -> Option<Statement> {
+        // Eat the 'SET' or 'PRAGMA' keyword
+        self.eat();
+
+        let name = self.parse_unqualified_object_name()?;
+
+        self.next_significant_token();
+
+        if !self.match_(Token::Comparison(Comparison::Equal)) {
+            self.push_error(ParseErrorKind::ExpectedKeyword("=".to_string()));
+            return None;
+        }
+
+        let value = self.parse_expr()?;
+
+        Some(Statement::Server(ServerStatement::Set(SetBody {
+            name,
+            value,
+        })))
+    }
+
     fn parse_create_statement(&mut self) -> Option<Statement> {
         if self.match_(Token::Keyword(Keyword::Create)) {
             self.next_significant_token();
@@ -702,6 +1282,10 @@ impl<'a> Parser<'a> {
                     let body = self.parse_create_database_statement();
                     body.map(|x| Statement::Server(ServerStatement::CreateDatabase(x)))
                 }
+                Some(Token::Keyword(Keyword::Index)) => {
+                    let body = self.parse_create_index_statement();
+                    body.map(|x| Statement::User(UserStatement::CreateIndex(x)))
+                }
                 _ => {
                     self.push_error(ParseErrorKind::UnsupportedSyntax);
                     None
@@ -717,48 +1301,43 @@ impl<'a> Parser<'a> {
         // Eat the 'TABLE' keyword
         self.eat();
 
+        let if_not_exists = self.parse_if_not_exists()?;
         let table_name = self.parse_unqualified_object_name()?;
         let column_list = self.parse_table_create_column_list()?;
+        let partition_by = self.parse_partition_by_clause_optional()?;
 
         Some(CreateTableBody {
             table_name,
+            if_not_exists,
             column_list,
+            partition_by,
         })
     }
 
-    fn parse_create_database_statement(&mut self) -> Option<CreateDatabaseBody> {
-        // Eat the 'DATABASE' keyword
-        self.eat();
-
-        let database_name = self.parse_unqualified_object_name()?;
+    /// Parse an optional partitioning clause following a table's column list, such as:
+    ///     PARTITION BY RANGE (CreatedDate)
+    /// Returns Some(None) if there is no clause, and None if the clause is malformed.
+    fn parse_partition_by_clause_optional(&mut self) -> Option<Option<PartitionBy>> {
+        self.next_significant_token();
 
-        Some(CreateDatabaseBody { database_name })
-    }
+        if !self.match_(Token::Keyword(Keyword::Partition)) {
+            return Some(None);
+        }
 
-    fn parse_unqualified_object_name(&mut self) -> Option<Identifier> {
         self.next_significant_token();
-        let identifier = match self.peek() {
-            Some(Token::Identifier(LexerIdent { value })) => Some(value),
-            _ => None,
-        };
 
-        match identifier {
-            Some(id) => {
-                let identifier_str = String::from(self.resolve_slice(id));
-                self.eat();
+        if !self.match_(Token::Keyword(Keyword::By)) {
+            self.push_error(ParseErrorKind::ExpectedKeyword(String::from("BY")));
+            return None;
+        }
 
-                Some(Identifier {
-                    value: identifier_str,
-                })
-            }
-            None => {
-                self.push_error(ParseErrorKind::ExpectedIdentifier);
-                None
-            }
+        self.next_significant_token();
+
+        if !self.match_(Token::Keyword(Keyword::Range)) {
+            self.push_error(ParseErrorKind::ExpectedKeyword(String::from("RANGE")));
+            return None;
         }
-    }
 
-    fn parse_table_create_column_list(&mut self) -> Option<Vec<ColumnDefinition>> {
         self.next_significant_token();
 
         if !self.match_(Token::ParenOpen) {
@@ -766,80 +1345,495 @@ impl<'a> Parser<'a> {
             return None;
         }
 
-        let mut columns = vec![];
-
-        while !self.lookahead(Token::ParenClose) {
-            self.match_(Token::Comma);
-            self.next_significant_token();
-
-            let column_definition = self.parse_column_definition()?;
-            columns.push(column_definition);
-        }
+        let column = self.parse_unqualified_object_name()?;
+        self.next_significant_token();
 
         if !self.match_(Token::ParenClose) {
             self.push_error(ParseErrorKind::ExpectedParentheses(")".to_string()));
             return None;
         }
 
-        Some(columns)
+        Some(Some(PartitionBy::Range(column)))
     }
 
-    fn parse_column_definition(&mut self) -> Option<ColumnDefinition> {
+    fn parse_create_database_statement(&mut self) -> Option<CreateDatabaseBody> {
+        // Eat the 'DATABASE' keyword
+        self.eat();
+
+        let if_not_exists = self.parse_if_not_exists()?;
+        let database_name = self.parse_unqualified_object_name()?;
+
+        Some(CreateDatabaseBody {
+            database_name,
+            if_not_exists,
+        })
+    }
+
+    /// Parse a DROP statement, such as:
+    ///     DROP TABLE Users
+    ///     DROP DATABASE IF EXISTS Sales
+    fn parse_drop_statement(&mut self) -> Option<Statement> {
+        // Eat the 'DROP' keyword
+        self.eat();
         self.next_significant_token();
 
         match self.peek() {
-            Some(Token::Identifier(LexerIdent { value })) => {
-                let identifier_str = String::from(self.resolve_slice(value));
-
+            Some(Token::Keyword(Keyword::Table)) => {
                 self.eat();
-                self.next_significant_token();
 
-                let datatype = match self.peek() {
-                    Some(Token::Keyword(Keyword::Int)) => Some(DataType::Int),
-                    _ => {
-                        self.push_error(ParseErrorKind::ExpectedDataType);
-                        None
-                    }
-                }?;
+                let if_exists = self.parse_if_exists()?;
+                let table_name = self.parse_unqualified_object_name()?;
 
+                Some(Statement::User(UserStatement::DropTable(DropTableBody {
+                    table_name,
+                    if_exists,
+                })))
+            }
+            Some(Token::Keyword(Keyword::Database)) => {
                 self.eat();
 
-                Some(ColumnDefinition {
-                    column_name: Identifier::from(identifier_str),
-                    datatype,
-                    nullable: false, // TODO
-                })
+                let if_exists = self.parse_if_exists()?;
+                let database_name = self.parse_unqualified_object_name()?;
+
+                Some(Statement::Server(ServerStatement::DropDatabase(
+                    DropDatabaseBody {
+                        database_name,
+                        if_exists,
+                    },
+                )))
             }
             _ => {
-                self.push_error(ParseErrorKind::ExpectedIdentifier);
+                self.push_error(ParseErrorKind::UnsupportedSyntax);
                 None
             }
         }
     }
 
-    /// Check if the next token is of a certain type
-    fn lookahead(&self, token: Token) -> bool {
-        match self.curr_pos < self.tokens.len() {
-            true => self.tokens[self.curr_pos].token == token,
-            false => false,
-        }
-    }
+    fn parse_describe_statement(&mut self) -> Option<Statement> {
+        // Eat the 'DESCRIBE' or 'DESC' keyword
+        self.eat();
+
+        let table_name = self.parse_unqualified_object_name()?;
+
+        Some(Statement::User(UserStatement::Describe(DescribeBody {
+            table_name,
+        })))
+    }
+
+    /// Parse an optional IF NOT EXISTS, returning whether it was there.
+    /// Returns None if it's incomplete, such as IF NOT on its own.
+    fn parse_if_not_exists(&mut self) -> Option<bool> {
+        self.next_significant_token();
+
+        if !self.match_(Token::Keyword(Keyword::If)) {
+            return Some(false);
+        }
+
+        self.next_significant_token();
+
+        if !self.match_(Token::Logical(Logical::Not)) {
+            self.push_error(ParseErrorKind::ExpectedKeyword(String::from("NOT")));
+            return None;
+        }
+
+        self.expect_exists()
+    }
+
+    /// Parse an optional IF EXISTS, returning whether it was there.
+    fn parse_if_exists(&mut self) -> Option<bool> {
+        self.next_significant_token();
+
+        if !self.match_(Token::Keyword(Keyword::If)) {
+            return Some(false);
+        }
+
+        self.expect_exists()
+    }
+
+    fn expect_exists(&mut self) -> Option<bool> {
+        self.next_significant_token();
+
+        if !self.match_(Token::Keyword(Keyword::Exists)) {
+            self.push_error(ParseErrorKind::ExpectedKeyword(String::from("EXISTS")));
+            return None;
+        }
+
+        Some(true)
+    }
+
+    /// Parse a CREATE INDEX statement, such as:
+    ///     CREATE INDEX ix_name ON Users (Name)
+    ///     CREATE INDEX ON Users ((Age + 1), Name)
+    ///     CREATE INDEX ON Users (Name) INCLUDE (Age)
+    ///     CREATE INDEX ON Users (Name) WHERE Active = TRUE
+    /// The index name is optional. INCLUDE lists non-key columns stored in the index,
+    /// and a WHERE clause makes the index partial.
+    fn parse_create_index_statement(&mut self) -> Option<CreateIndexBody> {
+        // Eat the 'INDEX' keyword
+        self.eat();
+        self.next_significant_token();
+
+        let index_name = match self.peek() {
+            Some(Token::Keyword(Keyword::On)) => None,
+            _ => Some(self.parse_unqualified_object_name()?),
+        };
+
+        self.next_significant_token();
+
+        if !self.match_(Token::Keyword(Keyword::On)) {
+            self.push_error(ParseErrorKind::ExpectedKeyword(String::from("ON")));
+            return None;
+        }
+
+        let table_name = self.parse_unqualified_object_name()?;
+        let key_list = self.parse_index_key_list()?;
+        let include_list = self.parse_index_include_list_optional()?;
+        let where_clause = self.parse_where_clause_optional()?;
+
+        Some(CreateIndexBody {
+            index_name,
+            table_name,
+            key_list,
+            include_list,
+            where_clause,
+        })
+    }
+
+    /// Parse the parenthesised list of index keys. A key is either a column name
+    /// or a parenthesised expression, optionally followed by its direction,
+    /// such as `(Name, (Age + 1) DESC)`.
+    fn parse_index_key_list(&mut self) -> Option<Vec<IndexKey>> {
+        self.next_significant_token();
+
+        if !self.match_(Token::ParenOpen) {
+            self.push_error(ParseErrorKind::ExpectedParentheses("(".to_string()));
+            return None;
+        }
+
+        let mut keys = vec![self.parse_index_key()?];
+        self.next_significant_token();
+
+        while self.match_(Token::Comma) {
+            keys.push(self.parse_index_key()?);
+            self.next_significant_token();
+        }
+
+        if !self.match_(Token::ParenClose) {
+            self.push_error(ParseErrorKind::ExpectedParentheses(")".to_string()));
+            return None;
+        }
+
+        Some(keys)
+    }
+
+    fn parse_index_key(&mut self) -> Option<IndexKey> {
+        let expr = self.parse_expr()?;
+        let dir = self.parse_order_direction();
+
+        Some(IndexKey { expr, dir })
+    }
+
+    /// Parse an optional INCLUDE column list, such as `INCLUDE (Age, Email)`.
+    /// Returns an empty list if there is no INCLUDE clause.
+    fn parse_index_include_list_optional(&mut self) -> Option<Vec<Identifier>> {
+        self.next_significant_token();
+
+        if !self.match_(Token::Keyword(Keyword::Include)) {
+            return Some(vec![]);
+        }
+
+        self.next_significant_token();
+
+        if !self.match_(Token::ParenOpen) {
+            self.push_error(ParseErrorKind::ExpectedParentheses("(".to_string()));
+            return None;
+        }
+
+        let mut columns = vec![self.parse_unqualified_object_name()?];
+        self.next_significant_token();
+
+        while self.match_(Token::Comma) {
+            columns.push(self.parse_unqualified_object_name()?);
+            self.next_significant_token();
+        }
+
+        if !self.match_(Token::ParenClose) {
+            self.push_error(ParseErrorKind::ExpectedParentheses(")".to_string()));
+            return None;
+        }
+
+        Some(columns)
+    }
+
+    fn parse_unqualified_object_name(&mut self) -> Option<Identifier> {
+        self.next_significant_token();
+        match self.peek_identifier() {
+            Some(id) => {
+                let identifier_str = String::from(self.resolve_slice(&id));
+                self.eat();
+
+                Some(Identifier {
+                    value: identifier_str,
+                })
+            }
+            None => {
+                self.push_error(ParseErrorKind::ExpectedIdentifier);
+                None
+            }
+        }
+    }
+
+    fn parse_table_create_column_list(&mut self) -> Option<Vec<ColumnDefinition>> {
+        self.next_significant_token();
+
+        if !self.match_(Token::ParenOpen) {
+            self.push_error(ParseErrorKind::ExpectedParentheses("(".to_string()));
+            return None;
+        }
+
+        let mut columns = vec![];
+
+        while !self.lookahead(Token::ParenClose) {
+            self.match_(Token::Comma);
+            self.next_significant_token();
+
+            let column_definition = self.parse_column_definition()?;
+            columns.push(column_definition);
+        }
+
+        if !self.match_(Token::ParenClose) {
+            self.push_error(ParseErrorKind::ExpectedParentheses(")".to_string()));
+            return None;
+        }
+
+        Some(columns)
+    }
+
+    /// Parse a column's name and type, followed by any constraints in any order, for example:
+    ///     Id INT PRIMARY KEY NOT NULL
+    ///     Age INT DEFAULT 0
+    fn parse_column_definition(&mut self) -> Option<ColumnDefinition> {
+        self.next_significant_token();
+
+        let Some(value) = self.peek_identifier() else {
+            self.push_error(ParseErrorKind::ExpectedIdentifier);
+            return None;
+        };
+
+        let identifier_str = String::from(self.resolve_slice(&value));
+
+        self.eat();
+        self.next_significant_token();
+
+        let mut column = ColumnDefinition {
+            column_name: Identifier::from(identifier_str),
+            datatype: self.parse_data_type()?,
+            nullable: true,
+            primary_key: false,
+            unique: false,
+            default: None,
+            generated: None,
+        };
+
+        loop {
+            self.next_significant_token();
+
+            match self.peek() {
+                Some(Token::Keyword(Keyword::Generated)) => {
+                    column.generated = Some(self.parse_generated_column()?);
+                }
+                Some(Token::Logical(Logical::Not)) => {
+                    self.eat();
+                    self.next_significant_token();
+
+                    if !self.match_(Token::Null) {
+                        self.push_error(ParseErrorKind::ExpectedKeyword("NULL".to_string()));
+                        return None;
+                    }
+
+                    column.nullable = false;
+                }
+                Some(Token::Null) => {
+                    self.eat();
+                    column.nullable = true;
+                }
+                Some(Token::Keyword(Keyword::Primary)) => {
+                    self.eat();
+                    self.next_significant_token();
+
+                    if !self.match_(Token::Keyword(Keyword::Key)) {
+                        self.push_error(ParseErrorKind::ExpectedKeyword("KEY".to_string()));
+                        return None;
+                    }
+
+                    column.primary_key = true;
+                }
+                Some(Token::Keyword(Keyword::Unique)) => {
+                    self.eat();
+                    column.unique = true;
+                }
+                Some(Token::Keyword(Keyword::Default)) => {
+                    self.eat();
+
+                    // Stop before NOT, so that DEFAULT 0 NOT NULL isn't read as NOT IN or NOT LIKE
+                    column.default = Some(self.parse_subexpr(20)?);
+                }
+                _ => break,
+            }
+        }
+
+        // A primary key can never be NULL
+        column.nullable &= !column.primary_key;
+
+        Some(column)
+    }
+
+    fn parse_data_type(&mut self) -> Option<DataType> {
+        let data_type = match self.peek() {
+            Some(Token::Keyword(Keyword::Int)) => DataType::Int,
+            Some(Token::Keyword(Keyword::Boolean)) => DataType::Boolean,
+            Some(Token::Keyword(Keyword::Blob)) => DataType::Blob,
+            Some(Token::Keyword(Keyword::Json)) => DataType::Json,
+            Some(Token::Keyword(Keyword::Uuid)) => DataType::Uuid,
+            Some(Token::Keyword(Keyword::Text)) => DataType::Text,
+            Some(Token::Keyword(Keyword::Float)) => DataType::Float,
+            Some(Token::Keyword(Keyword::Date)) => DataType::Date,
+            Some(Token::Keyword(Keyword::BigInt)) => DataType::BigInt,
+            Some(Token::Keyword(Keyword::Varchar)) => {
+                self.eat();
+                return Some(DataType::Varchar(self.parse_type_length()?));
+            }
+            _ => {
+                self.push_error(ParseErrorKind::ExpectedDataType);
+                return None;
+            }
+        };
+
+        self.eat();
+        Some(data_type)
+    }
+
+    /// Parse the length of a type such as VARCHAR(255), including the parentheses.
+    fn parse_type_length(&mut self) -> Option<u32> {
+        self.next_significant_token();
+
+        if !self.match_(Token::ParenOpen) {
+            self.push_error(ParseErrorKind::ExpectedParentheses("(".to_string()));
+            return None;
+        }
+
+        self.next_significant_token();
+
+        let length = match self.peek() {
+            Some(Token::Numeric(s)) => {
+                let Ok(length) = self.buf[s.start..s.end].parse() else {
+                    self.push_error(ParseErrorKind::InvalidNumericLiteral);
+                    return None;
+                };
+                length
+            }
+            _ => {
+                self.push_error(ParseErrorKind::ExpectedValue);
+                return None;
+            }
+        };
+
+        self.eat();
+        self.next_significant_token();
+
+        if !self.match_(Token::ParenClose) {
+            self.push_error(ParseErrorKind::ExpectedParentheses(")".to_string()));
+            return None;
+        }
+
+        Some(length)
+    }
+
+    /// Parse the definition of a generated column, following its data type, for example:
+    ///     GENERATED ALWAYS AS (a + b) STORED
+    fn parse_generated_column(&mut self) -> Option<GeneratedColumn> {
+        for keyword in [Keyword::Generated, Keyword::Always, Keyword::As] {
+            self.next_significant_token();
+
+            if !self.match_(Token::Keyword(keyword)) {
+                self.push_error(ParseErrorKind::ExpectedKeyword(
+                    format!("{keyword:?}").to_uppercase(),
+                ));
+                return None;
+            }
+        }
+
+        self.next_significant_token();
+
+        if !self.match_(Token::ParenOpen) {
+            self.push_error(ParseErrorKind::ExpectedParentheses("(".to_string()));
+            return None;
+        }
+
+        let expr = self.parse_expr()?;
+
+        self.next_significant_token();
+
+        if !self.match_(Token::ParenClose) {
+            self.push_error(ParseErrorKind::ExpectedParentheses(")".to_string()));
+            return None;
+        }
+
+        self.next_significant_token();
+
+        let storage = match self.peek() {
+            Some(Token::Keyword(Keyword::Stored)) => {
+                self.eat();
+                GeneratedStorage::Stored
+            }
+            Some(Token::Keyword(Keyword::Virtual)) => {
+                self.eat();
+                GeneratedStorage::Virtual
+            }
+            _ => GeneratedStorage::Virtual,
+        };
+
+        Some(GeneratedColumn { expr, storage })
+    }
+
+    /// Check if the next token is of a certain type
+    fn lookahead(&self, token: Token) -> bool {
+        self.peek() == Some(&token)
+    }
+
+    /// If the next token can be used as an identifier, get its slice.
+    /// As well as identifiers, this includes non-reserved keywords such as INDEX
+    /// and double-quoted identifiers such as "My Table".
+    fn peek_identifier(&self) -> Option<Slice> {
+        let next = self.peek_with_location()?;
+
+        match next.token {
+            Token::Identifier(LexerIdent { value }) => Some(value),
+            Token::Value(LexerValue::DoubleQuoted(value)) => Some(value),
+            Token::Keyword(keyword) if !keyword.is_reserved() => Some(next.span),
+            _ => None,
+        }
+    }
+
+    /// Check if the next tokens are an identifier immediately followed
+    /// by an opening parenthesis, i.e. the start of a function call.
+    fn is_function_call(&self) -> bool {
+        self.peek_identifier().is_some()
+            && matches!(
+                self.lookahead_tokens.get(1).map(|t| t.token),
+                Some(Token::ParenOpen)
+            )
+    }
 
     /// Get the next token without consuming it
     fn peek(&self) -> Option<&Token> {
-        match self.curr_pos < self.tokens.len() {
-            true => Some(&self.tokens[self.curr_pos].token),
-            false => None,
-        }
+        self.peek_with_location().map(|t| &t.token)
     }
 
     /// Get the next token without consuming it.
     /// Includes location data.
     fn peek_with_location(&self) -> Option<&LocatableToken> {
-        match self.curr_pos < self.tokens.len() {
-            true => Some(&self.tokens[self.curr_pos]),
-            false => None,
-        }
+        self.lookahead_tokens.front()
     }
 
     /// For a slice, resolve the string value from the input buffer.
@@ -847,14 +1841,21 @@ impl<'a> Parser<'a> {
         &self.buf[slice.start..slice.end]
     }
 
-    /// Consume and return the next token
-    fn eat(&mut self) -> &LocatableToken {
-        if self.curr_pos >= self.tokens.len() {
-            panic!("Unexpected end of token stream. This should never happen.")
+    /// Consume and return the next token.
+    /// Consuming past the end of the token stream is a parse error.
+    fn eat(&mut self) -> Option<&LocatableToken> {
+        let Some(token) = self.lookahead_tokens.pop_front() else {
+            self.push_error(ParseErrorKind::UnexpectedEOF);
+            return None;
+        };
+
+        if let Some(next) = self.tokens.next() {
+            self.lookahead_tokens.push_back(next);
         }
 
         self.curr_pos += 1;
-        &self.tokens[self.curr_pos - 1]
+        self.prev_token = Some(token);
+        self.prev_token.as_ref()
     }
 
     /// If the next token is as expected, consume it and return true
@@ -875,38 +1876,56 @@ impl<'a> Parser<'a> {
         }
     }
 
-    // Check if the current token is non-whitespace
+    // Check if the current token is neither whitespace nor a comment.
+    // The end of the token stream counts as significant, as there is nothing left to skip.
     fn is_significant_token(&self) -> bool {
         let next = self.peek();
 
         match next {
-            Some(Token::Space) => false,
+            Some(Token::Space | Token::NewLine | Token::Comment(_)) => false,
             Some(_) => true,
-            None => false,
+            None => true,
         }
     }
 
     // True if all tokens parsed
     fn is_end(&self) -> bool {
-        self.curr_pos >= self.tokens.len()
+        self.lookahead_tokens.is_empty()
     }
 
     /// Add a new error to the errors list.
     fn push_error(&mut self, kind: ParseErrorKind) {
         let current_token = self.peek_with_location();
-        let position = match current_token {
-            Some(t) => t.position,
-            _ => 0,
+        let (position, end) = match current_token {
+            Some(t) => (t.span.start, t.span.end),
+            _ => (0, 0),
         };
 
-        self.errors.push(ParseError { kind, position })
+        self.errors.push(ParseError {
+            kind,
+            position,
+            end,
+        })
     }
 }
 
+/// Decode a string of hex digit pairs, such as `DEADBEEF`, into bytes.
+/// Returns None if the string has an odd length or contains a non-hex digit.
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
 #[cfg(test)]
 mod parser_tests {
     use crate::*;
-    use lexer::token::{Comparison, Slice, Value as LexerValue};
+    use lexer::token::{Comparison, Radix, Slice, Value as LexerValue};
     use pretty_assertions::assert_eq;
 
     const EMPTY_QUERY: &str = "";
@@ -1057,10 +2076,11 @@ mod parser_tests {
             UserStatement::Select(SelectExpressionBody {
                 select_item_list: SelectItemList::from(vec![SelectItem::new(Expr::Wildcard)]),
                 from_clause: Some(FromClause {
-                    identifier: Identifier {
+                    source: TableSource::Table(TableName::from(Identifier {
                         value: String::from("a"),
-                    },
+                    })),
                     alias: None,
+                    joins: vec![],
                 }),
                 where_clause: None,
                 order_by_clause: None,
@@ -1097,10 +2117,11 @@ mod parser_tests {
                     vec!["u", "Name"],
                 )]),
                 from_clause: Some(FromClause {
-                    identifier: Identifier {
+                    source: TableSource::Table(TableName::from(Identifier {
                         value: String::from("Users"),
-                    },
+                    })),
                     alias: Some(Identifier::from("u".to_string())),
+                    joins: vec![],
                 }),
                 where_clause: None,
                 order_by_clause: None,
@@ -1484,11 +2505,274 @@ mod parser_tests {
             errors[0],
             ParseError {
                 position: 0,
+                end: 0,
                 kind: ParseErrorKind::ExpressionNotClosed,
             }
         );
     }
 
+    #[test]
+    fn test_expression_constant_hex_string() {
+        let query = String::from("select X'DEADBEEF';");
+        let tokens = vec![
+            Token::Keyword(Keyword::Select),
+            Token::Space,
+            Token::Value(LexerValue::Hex(Slice::new(9, 17))),
+            Token::EOF,
+        ];
+
+        let lexer = Parser::new_positionless(tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Select(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![SelectItem {
+                    expr: Expr::Value(Value::Blob(vec![0xDE, 0xAD, 0xBE, 0xEF])),
+                    alias: None,
+                }]),
+                from_clause: None,
+                where_clause: None,
+                order_by_clause: None,
+                group_by_clause: None,
+            }),
+        )]));
+
+        assert_eq!(lexer, expected);
+    }
+
+    #[test]
+    fn test_expression_hex_and_binary_numbers() {
+        let query = String::from("select 0xFF + 0b10");
+        let tokens = vec![
+            Token::Keyword(Keyword::Select),
+            Token::Space,
+            Token::RadixNumeric(Slice::new(9, 11), Radix::Hexadecimal),
+            Token::Space,
+            Token::Arithmetic(Arithmetic::Plus),
+            Token::Space,
+            Token::RadixNumeric(Slice::new(16, 18), Radix::Binary),
+            Token::EOF,
+        ];
+
+        let actual = Parser::new_positionless(tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Select(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![SelectItem::new(
+                    Expr::BinaryOperator {
+                        left: Box::new(Expr::Value(Value::Number("255".to_string()))),
+                        op: BinaryOperator::Plus,
+                        right: Box::new(Expr::Value(Value::Number("2".to_string()))),
+                    },
+                )]),
+                from_clause: None,
+                where_clause: None,
+                order_by_clause: None,
+                group_by_clause: None,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_expression_invalid_binary_number() {
+        let query = String::from("select 0b102");
+        let tokens = vec![
+            Token::Keyword(Keyword::Select),
+            Token::Space,
+            Token::RadixNumeric(Slice::new(9, 12), Radix::Binary),
+            Token::EOF,
+        ];
+
+        let actual = Parser::new_positionless(tokens, &query).parse();
+
+        let errors = match actual {
+            Ok(_) => vec![],
+            Err(e) => e,
+        };
+
+        assert_eq!(
+            errors[0],
+            ParseError {
+                position: 0,
+                end: 0,
+                kind: ParseErrorKind::InvalidNumericLiteral,
+            }
+        );
+    }
+
+    #[test]
+    fn test_expression_parameters() {
+        let query = String::from("select ? + $2 + :name + ?");
+        let tokens = vec![
+            Token::Keyword(Keyword::Select),
+            Token::Space,
+            Token::Parameter(LexerParameter::Anonymous),
+            Token::Space,
+            Token::Arithmetic(Arithmetic::Plus),
+            Token::Space,
+            Token::Parameter(LexerParameter::Positional(Slice::new(12, 13))),
+            Token::Space,
+            Token::Arithmetic(Arithmetic::Plus),
+            Token::Space,
+            Token::Parameter(LexerParameter::Named(Slice::new(17, 21))),
+            Token::Space,
+            Token::Arithmetic(Arithmetic::Plus),
+            Token::Space,
+            Token::Parameter(LexerParameter::Anonymous),
+            Token::EOF,
+        ];
+
+        let actual = Parser::new_positionless(tokens, &query).parse();
+
+        let parameter = |p| Box::new(Expr::Parameter(p));
+        let plus = |left, right| {
+            Box::new(Expr::BinaryOperator {
+                left,
+                op: BinaryOperator::Plus,
+                right,
+            })
+        };
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Select(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![SelectItem::new(*plus(
+                    plus(
+                        plus(
+                            parameter(Parameter::Anonymous(1)),
+                            parameter(Parameter::Positional(2)),
+                        ),
+                        parameter(Parameter::Named("name".to_string())),
+                    ),
+                    parameter(Parameter::Anonymous(2)),
+                ))]),
+                from_clause: None,
+                where_clause: None,
+                order_by_clause: None,
+                group_by_clause: None,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_expression_invalid_positional_parameter() {
+        let query = String::from("select $0");
+        let tokens = vec![
+            Token::Keyword(Keyword::Select),
+            Token::Space,
+            Token::Parameter(LexerParameter::Positional(Slice::new(8, 9))),
+            Token::EOF,
+        ];
+
+        let actual = Parser::new_positionless(tokens, &query).parse();
+
+        let errors = match actual {
+            Ok(_) => vec![],
+            Err(e) => e,
+        };
+
+        assert_eq!(
+            errors[0],
+            ParseError {
+                position: 0,
+                end: 0,
+                kind: ParseErrorKind::InvalidParameter,
+            }
+        );
+    }
+
+    #[test]
+    fn test_expression_invalid_hex_string() {
+        let query = String::from("select X'ABC';");
+        let tokens = vec![
+            Token::Keyword(Keyword::Select),
+            Token::Space,
+            Token::Value(LexerValue::Hex(Slice::new(9, 12))),
+            Token::EOF,
+        ];
+
+        let actual = Parser::new_positionless(tokens, &query).parse();
+
+        let errors = match actual {
+            Ok(_) => vec![],
+            Err(e) => e,
+        };
+
+        assert_eq!(
+            errors[0],
+            ParseError {
+                position: 0,
+                end: 0,
+                kind: ParseErrorKind::InvalidHexLiteral,
+            }
+        );
+    }
+
+    #[test]
+    fn test_expression_json_extract_binds_tighter_than_comparison() {
+        let query = String::from("select a from b where c->>'d' = 'e'");
+        let tokens = vec![
+            Token::Keyword(Keyword::Select),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(7, 8))),
+            Token::Space,
+            Token::Keyword(Keyword::From),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(14, 15))),
+            Token::Space,
+            Token::Keyword(Keyword::Where),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(22, 23))),
+            Token::DoubleArrow,
+            Token::Value(LexerValue::SingleQuoted(Slice::new(27, 28))),
+            Token::Space,
+            Token::Comparison(Comparison::Equal),
+            Token::Space,
+            Token::Value(LexerValue::SingleQuoted(Slice::new(33, 34))),
+            Token::EOF,
+        ];
+
+        let lexer = Parser::new_positionless(tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Select(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![SelectItem::simple_identifier("a")]),
+                from_clause: Some(FromClause {
+                    source: TableSource::Table(TableName::from(Identifier {
+                        value: String::from("b"),
+                    })),
+                    alias: None,
+                    joins: vec![],
+                }),
+                where_clause: Some(WhereClause {
+                    expr: Expr::BinaryOperator {
+                        left: Box::new(Expr::BinaryOperator {
+                            left: Box::new(Expr::Identifier(Identifier {
+                                value: String::from("c"),
+                            })),
+                            op: BinaryOperator::JsonExtractText,
+                            right: Box::new(Expr::Value(Value::String(
+                                String::from("d"),
+                                QuoteType::Single,
+                            ))),
+                        }),
+                        op: BinaryOperator::Equal,
+                        right: Box::new(Expr::Value(Value::String(
+                            String::from("e"),
+                            QuoteType::Single,
+                        ))),
+                    },
+                }),
+                order_by_clause: None,
+                group_by_clause: None,
+            }),
+        )]));
+
+        assert_eq!(lexer, expected);
+    }
+
     #[test]
     fn test_select_statement_with_multiple_select_items() {
         let query = String::from("select a,b");
@@ -1547,10 +2831,11 @@ mod parser_tests {
             UserStatement::Select(SelectExpressionBody {
                 select_item_list: SelectItemList::from(vec![SelectItem::simple_identifier("a")]),
                 from_clause: Some(FromClause {
-                    identifier: Identifier {
+                    source: TableSource::Table(TableName::from(Identifier {
                         value: String::from("b"),
-                    },
+                    })),
                     alias: None,
+                    joins: vec![],
                 }),
                 where_clause: Some(WhereClause {
                     expr: Expr::BinaryOperator {
@@ -1599,10 +2884,11 @@ mod parser_tests {
             UserStatement::Select(SelectExpressionBody {
                 select_item_list: SelectItemList::from(vec![SelectItem::simple_identifier("a")]),
                 from_clause: Some(FromClause {
-                    identifier: Identifier {
+                    source: TableSource::Table(TableName::from(Identifier {
                         value: String::from("b"),
-                    },
+                    })),
                     alias: None,
+                    joins: vec![],
                 }),
                 where_clause: Some(WhereClause {
                     expr: Expr::IsNull(Box::new(Expr::Identifier(Identifier {
@@ -1645,10 +2931,11 @@ mod parser_tests {
             UserStatement::Select(SelectExpressionBody {
                 select_item_list: SelectItemList::from(vec![SelectItem::simple_identifier("a")]),
                 from_clause: Some(FromClause {
-                    identifier: Identifier {
+                    source: TableSource::Table(TableName::from(Identifier {
                         value: String::from("b"),
-                    },
+                    })),
                     alias: None,
+                    joins: vec![],
                 }),
                 where_clause: Some(WhereClause {
                     expr: Expr::IsTrue(Box::new(Expr::Identifier(Identifier {
@@ -1693,10 +2980,11 @@ mod parser_tests {
             UserStatement::Select(SelectExpressionBody {
                 select_item_list: SelectItemList::from(vec![SelectItem::simple_identifier("a")]),
                 from_clause: Some(FromClause {
-                    identifier: Identifier {
+                    source: TableSource::Table(TableName::from(Identifier {
                         value: String::from("b"),
-                    },
+                    })),
                     alias: None,
+                    joins: vec![],
                 }),
                 where_clause: Some(WhereClause {
                     expr: Expr::IsNotNull(Box::new(Expr::Identifier(Identifier {
@@ -1847,10 +3135,11 @@ mod parser_tests {
                         SelectItem::simple_identifier("Age"),
                     ]),
                     from_clause: Some(FromClause {
-                        identifier: Identifier {
+                        source: TableSource::Table(TableName::from(Identifier {
                             value: String::from("Users"),
-                        },
+                        })),
                         alias: None,
+                        joins: vec![],
                     }),
                     where_clause: Some(WhereClause {
                         expr: Expr::BinaryOperator {
@@ -1862,241 +3151,2064 @@ mod parser_tests {
                         },
                     }),
                     group_by_clause: Some(GroupByClause {
-                        identifier: Identifier {
+                        expr: Box::new(Expr::Identifier(Identifier {
                             value: String::from("Name"),
-                        },
+                        })),
                     }),
                     order_by_clause: Some(OrderByClause {
                         dir: OrderDirection::Desc,
-                        identifier: Identifier {
+                        expr: Box::new(Expr::Identifier(Identifier {
                             value: String::from("Name"),
-                        },
+                        })),
                     }),
                 }),
             )]));
 
-        assert_eq!(lexer, expected);
+        assert_eq!(lexer, expected);
+    }
+
+    #[test]
+    fn test_select_statement_with_group_by() {
+        let query = String::from("select a from b group by c;");
+        let tokens = vec![
+            Token::Keyword(Keyword::Select),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(7, 8))),
+            Token::Space,
+            Token::Keyword(Keyword::From),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(14, 15))),
+            Token::Space,
+            Token::Keyword(Keyword::Group),
+            Token::Space,
+            Token::Keyword(Keyword::By),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(25, 26))),
+            Token::EOF,
+        ];
+
+        let lexer = Parser::new_positionless(tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Select(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![SelectItem::simple_identifier("a")]),
+                from_clause: Some(FromClause {
+                    source: TableSource::Table(TableName::from(Identifier {
+                        value: String::from("b"),
+                    })),
+                    alias: None,
+                    joins: vec![],
+                }),
+                where_clause: None,
+                order_by_clause: None,
+                group_by_clause: Some(GroupByClause {
+                    expr: Box::new(Expr::Identifier(Identifier {
+                        value: String::from("c"),
+                    })),
+                }),
+            }),
+        )]));
+
+        assert_eq!(lexer, expected);
+    }
+
+    #[test]
+    fn test_empty_tokens() {
+        let tokens = vec![];
+        let actual = Parser::new_positionless(tokens, EMPTY_QUERY).parse();
+        let expected = Ok(Program::Statements(vec![]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_select_statement_missing_select_items_list() {
+        let tokens = vec![Token::Keyword(Keyword::Select), Token::EOF];
+        let actual = Parser::new_positionless(tokens, EMPTY_QUERY).parse();
+
+        let errors = match actual {
+            Ok(_) => vec![],
+            Err(e) => e,
+        };
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0],
+            ParseError {
+                position: 0,
+                end: 0,
+                kind: ParseErrorKind::ExpectedIdentifier,
+            }
+        );
+    }
+
+    #[test]
+    fn test_token_stream_without_eof_does_not_panic() {
+        let tokens = vec![Token::Keyword(Keyword::Select), Token::Space];
+        let actual = Parser::new_positionless(tokens, EMPTY_QUERY).parse();
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn test_select_statement_missing_select_item_after_comma() {
+        let tokens = vec![
+            Token::Keyword(Keyword::Select),
+            Token::Identifier(LexerIdent::new(Slice::new(0, 1))),
+            Token::Comma,
+            Token::EOF,
+        ];
+
+        let actual = Parser::new_positionless(tokens, &String::from("select a,")).parse();
+
+        let errors = match actual {
+            Ok(_) => vec![],
+            Err(e) => e,
+        };
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0],
+            ParseError {
+                position: 0,
+                end: 0,
+                kind: ParseErrorKind::ExpectedIdentifier,
+            }
+        );
+    }
+
+    #[test]
+    fn test_missing_statement() {
+        let tokens = vec![Token::Semicolon];
+        let lexer = Parser::new_positionless(tokens, EMPTY_QUERY).parse();
+
+        let errors = match lexer {
+            Ok(_) => vec![],
+            Err(e) => e,
+        };
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0],
+            ParseError {
+                position: 0,
+                end: 0,
+                kind: ParseErrorKind::ExpectedStatemnt,
+            }
+        );
+    }
+
+    #[test]
+    fn test_simple_insert_statement() {
+        let tokens = vec![Token::Keyword(Keyword::Insert), Token::EOF];
+        let lexer = Parser::new_positionless(tokens, EMPTY_QUERY).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Insert,
+        )]));
+
+        assert_eq!(lexer, expected);
+    }
+
+    #[test]
+    fn test_simple_update_statement() {
+        let tokens = vec![Token::Keyword(Keyword::Update), Token::EOF];
+        let lexer = Parser::new_positionless(tokens, EMPTY_QUERY).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Update,
+        )]));
+
+        assert_eq!(lexer, expected);
+    }
+
+    #[test]
+    fn test_simple_delete_statement() {
+        let tokens = vec![Token::Keyword(Keyword::Delete), Token::EOF];
+        let lexer = Parser::new_positionless(tokens, EMPTY_QUERY).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Delete,
+        )]));
+
+        assert_eq!(lexer, expected);
+    }
+
+    #[test]
+    fn test_simple_create_table_statement() {
+        let query = String::from("CREATE TABLE Users (Id INT, Age INT)");
+        let tokens = vec![
+            Token::Keyword(Keyword::Create),
+            Token::Space,
+            Token::Keyword(Keyword::Table),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(13, 18))),
+            Token::Space,
+            Token::ParenOpen,
+            Token::Identifier(LexerIdent::new(Slice::new(20, 22))),
+            Token::Space,
+            Token::Keyword(Keyword::Int),
+            Token::Comma,
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(28, 31))),
+            Token::Space,
+            Token::Keyword(Keyword::Int),
+            Token::ParenClose,
+            Token::EOF,
+        ];
+        let lexer = Parser::new_positionless(tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::CreateTable(CreateTableBody {
+                table_name: Identifier::from("Users".to_string()),
+                if_not_exists: false,
+                column_list: vec![
+                    ColumnDefinition {
+                        column_name: Identifier::from("Id".to_string()),
+                        datatype: DataType::Int,
+                        nullable: true,
+                        primary_key: false,
+                        unique: false,
+                        default: None,
+                        generated: None,
+                    },
+                    ColumnDefinition {
+                        column_name: Identifier::from("Age".to_string()),
+                        datatype: DataType::Int,
+                        nullable: true,
+                        primary_key: false,
+                        unique: false,
+                        default: None,
+                        generated: None,
+                    },
+                ],
+                partition_by: None,
+            }),
+        )]));
+
+        assert_eq!(lexer, expected);
+    }
+
+    #[test]
+    fn test_create_table_statement_with_boolean_column() {
+        let query = String::from("CREATE TABLE Users (Active BOOLEAN)");
+        let tokens = vec![
+            Token::Keyword(Keyword::Create),
+            Token::Space,
+            Token::Keyword(Keyword::Table),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(13, 18))),
+            Token::Space,
+            Token::ParenOpen,
+            Token::Identifier(LexerIdent::new(Slice::new(20, 26))),
+            Token::Space,
+            Token::Keyword(Keyword::Boolean),
+            Token::ParenClose,
+            Token::EOF,
+        ];
+        let lexer = Parser::new_positionless(tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::CreateTable(CreateTableBody {
+                table_name: Identifier::from("Users".to_string()),
+                if_not_exists: false,
+                column_list: vec![ColumnDefinition {
+                    column_name: Identifier::from("Active".to_string()),
+                    datatype: DataType::Boolean,
+                    nullable: true,
+                    primary_key: false,
+                    unique: false,
+                    default: None,
+                    generated: None,
+                }],
+                partition_by: None,
+            }),
+        )]));
+
+        assert_eq!(lexer, expected);
+    }
+
+    #[test]
+    fn test_create_table_statement_partitioned_by_range() {
+        let query = String::from("CREATE TABLE Logs (Day INT) PARTITION BY RANGE (Day)");
+        let tokens = vec![
+            Token::Keyword(Keyword::Create),
+            Token::Space,
+            Token::Keyword(Keyword::Table),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(13, 17))),
+            Token::Space,
+            Token::ParenOpen,
+            Token::Identifier(LexerIdent::new(Slice::new(19, 22))),
+            Token::Space,
+            Token::Keyword(Keyword::Int),
+            Token::ParenClose,
+            Token::Space,
+            Token::Keyword(Keyword::Partition),
+            Token::Space,
+            Token::Keyword(Keyword::By),
+            Token::Space,
+            Token::Keyword(Keyword::Range),
+            Token::Space,
+            Token::ParenOpen,
+            Token::Identifier(LexerIdent::new(Slice::new(48, 51))),
+            Token::ParenClose,
+            Token::EOF,
+        ];
+        let lexer = Parser::new_positionless(tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::CreateTable(CreateTableBody {
+                table_name: Identifier::from("Logs".to_string()),
+                if_not_exists: false,
+                column_list: vec![ColumnDefinition {
+                    column_name: Identifier::from("Day".to_string()),
+                    datatype: DataType::Int,
+                    nullable: true,
+                    primary_key: false,
+                    unique: false,
+                    default: None,
+                    generated: None,
+                }],
+                partition_by: Some(PartitionBy::Range(Identifier::from("Day".to_string()))),
+            }),
+        )]));
+
+        assert_eq!(lexer, expected);
+    }
+
+    #[test]
+    fn test_create_index_statement() {
+        let query = String::from("CREATE INDEX ix ON Users (Name)");
+        let tokens = vec![
+            Token::Keyword(Keyword::Create),
+            Token::Space,
+            Token::Keyword(Keyword::Index),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(13, 15))),
+            Token::Space,
+            Token::Keyword(Keyword::On),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(19, 24))),
+            Token::Space,
+            Token::ParenOpen,
+            Token::Identifier(LexerIdent::new(Slice::new(26, 30))),
+            Token::ParenClose,
+            Token::EOF,
+        ];
+        let lexer = Parser::new_positionless(tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::CreateIndex(CreateIndexBody {
+                index_name: Some(Identifier::from("ix".to_string())),
+                table_name: Identifier::from("Users".to_string()),
+                key_list: vec![IndexKey {
+                    expr: Expr::Identifier(Identifier::from("Name".to_string())),
+                    dir: OrderDirection::Asc,
+                }],
+                include_list: vec![],
+                where_clause: None,
+            }),
+        )]));
+
+        assert_eq!(lexer, expected);
+    }
+
+    #[test]
+    fn test_create_index_statement_on_expression() {
+        let query = String::from("CREATE INDEX ON Users ((Age + 1))");
+        let tokens = vec![
+            Token::Keyword(Keyword::Create),
+            Token::Space,
+            Token::Keyword(Keyword::Index),
+            Token::Space,
+            Token::Keyword(Keyword::On),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(16, 21))),
+            Token::Space,
+            Token::ParenOpen,
+            Token::ParenOpen,
+            Token::Identifier(LexerIdent::new(Slice::new(24, 27))),
+            Token::Space,
+            Token::Arithmetic(Arithmetic::Plus),
+            Token::Space,
+            Token::Numeric(Slice::new(30, 31)),
+            Token::ParenClose,
+            Token::ParenClose,
+            Token::EOF,
+        ];
+        let lexer = Parser::new_positionless(tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::CreateIndex(CreateIndexBody {
+                index_name: None,
+                table_name: Identifier::from("Users".to_string()),
+                key_list: vec![IndexKey {
+                    expr: Expr::BinaryOperator {
+                        left: Box::new(Expr::Identifier(Identifier::from("Age".to_string()))),
+                        op: BinaryOperator::Plus,
+                        right: Box::new(Expr::Value(Value::Number(String::from("1")))),
+                    },
+                    dir: OrderDirection::Asc,
+                }],
+                include_list: vec![],
+                where_clause: None,
+            }),
+        )]));
+
+        assert_eq!(lexer, expected);
+    }
+
+    #[test]
+    fn test_create_composite_index_statement() {
+        let query = String::from("CREATE INDEX ON t (a, b DESC, (c + 1) ASC)");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let key = |expr, dir| IndexKey { expr, dir };
+        let name = |value: &str| Expr::Identifier(Identifier::from(value.to_string()));
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::CreateIndex(CreateIndexBody {
+                index_name: None,
+                table_name: Identifier::from("t".to_string()),
+                key_list: vec![
+                    key(name("a"), OrderDirection::Asc),
+                    key(name("b"), OrderDirection::Desc),
+                    key(
+                        Expr::BinaryOperator {
+                            left: Box::new(name("c")),
+                            op: BinaryOperator::Plus,
+                            right: Box::new(Expr::Value(Value::Number(String::from("1")))),
+                        },
+                        OrderDirection::Asc,
+                    ),
+                ],
+                include_list: vec![],
+                where_clause: None,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_create_partial_index_statement() {
+        let query = String::from("CREATE INDEX ON Users (Name) WHERE Active = true");
+        let tokens = vec![
+            Token::Keyword(Keyword::Create),
+            Token::Space,
+            Token::Keyword(Keyword::Index),
+            Token::Space,
+            Token::Keyword(Keyword::On),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(16, 21))),
+            Token::Space,
+            Token::ParenOpen,
+            Token::Identifier(LexerIdent::new(Slice::new(23, 27))),
+            Token::ParenClose,
+            Token::Space,
+            Token::Keyword(Keyword::Where),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(35, 41))),
+            Token::Space,
+            Token::Comparison(Comparison::Equal),
+            Token::Space,
+            Token::Keyword(Keyword::True),
+            Token::EOF,
+        ];
+        let lexer = Parser::new_positionless(tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::CreateIndex(CreateIndexBody {
+                index_name: None,
+                table_name: Identifier::from("Users".to_string()),
+                key_list: vec![IndexKey {
+                    expr: Expr::Identifier(Identifier::from("Name".to_string())),
+                    dir: OrderDirection::Asc,
+                }],
+                include_list: vec![],
+                where_clause: Some(WhereClause {
+                    expr: Expr::BinaryOperator {
+                        left: Box::new(Expr::Identifier(Identifier::from("Active".to_string()))),
+                        op: BinaryOperator::Equal,
+                        right: Box::new(Expr::Value(Value::Boolean(true))),
+                    },
+                }),
+            }),
+        )]));
+
+        assert_eq!(lexer, expected);
+    }
+
+    #[test]
+    fn test_create_partial_index_statement_without_predicate() {
+        for query in [
+            "CREATE INDEX ON t (a) WHERE",
+            "CREATE INDEX ON t (a) WHERE;",
+        ] {
+            let query = String::from(query);
+            let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+            assert_eq!(
+                actual.map_err(|errors| errors[0].kind.clone()),
+                Err(ParseErrorKind::ExpectedExpression),
+                "{query}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_create_index_statement_with_include_list() {
+        let query = String::from("CREATE INDEX ON Users (Name) INCLUDE (Age, Email)");
+        let tokens = vec![
+            Token::Keyword(Keyword::Create),
+            Token::Space,
+            Token::Keyword(Keyword::Index),
+            Token::Space,
+            Token::Keyword(Keyword::On),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(16, 21))),
+            Token::Space,
+            Token::ParenOpen,
+            Token::Identifier(LexerIdent::new(Slice::new(23, 27))),
+            Token::ParenClose,
+            Token::Space,
+            Token::Keyword(Keyword::Include),
+            Token::Space,
+            Token::ParenOpen,
+            Token::Identifier(LexerIdent::new(Slice::new(38, 41))),
+            Token::Comma,
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(43, 48))),
+            Token::ParenClose,
+            Token::EOF,
+        ];
+        let lexer = Parser::new_positionless(tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::CreateIndex(CreateIndexBody {
+                index_name: None,
+                table_name: Identifier::from("Users".to_string()),
+                key_list: vec![IndexKey {
+                    expr: Expr::Identifier(Identifier::from("Name".to_string())),
+                    dir: OrderDirection::Asc,
+                }],
+                include_list: vec![
+                    Identifier::from("Age".to_string()),
+                    Identifier::from("Email".to_string()),
+                ],
+                where_clause: None,
+            }),
+        )]));
+
+        assert_eq!(lexer, expected);
+    }
+
+    #[test]
+    fn test_simple_create_database_statement() {
+        let query = String::from("CREATE Database Db");
+        let tokens = vec![
+            Token::Keyword(Keyword::Create),
+            Token::Space,
+            Token::Keyword(Keyword::Database),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(16, 18))),
+            Token::EOF,
+        ];
+        let lexer = Parser::new_positionless(tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::Server(
+            ServerStatement::CreateDatabase(CreateDatabaseBody {
+                database_name: Identifier::from("Db".to_string()),
+                if_not_exists: false,
+            }),
+        )]));
+
+        assert_eq!(lexer, expected);
+    }
+
+    #[test]
+    fn test_parse_from_lexer_iter() {
+        let query = String::from("CREATE Database Db");
+        let mut lexer = lexer::Lexer::new(&query);
+        let actual = Parser::new(lexer.iter(), &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::Server(
+            ServerStatement::CreateDatabase(CreateDatabaseBody {
+                database_name: Identifier::from("Db".to_string()),
+                if_not_exists: false,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_error_span_covers_offending_token() {
+        let query = String::from("CREATE TABLE 123 (Id INT)");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let expected = Err(vec![ParseError {
+            kind: ParseErrorKind::ExpectedIdentifier,
+            position: 13,
+            end: 16,
+        }]);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_non_reserved_keywords_as_identifiers() {
+        let query = String::from("CREATE TABLE Index (Json JSON, Range INT)");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::CreateTable(CreateTableBody {
+                table_name: Identifier::from("Index".to_string()),
+                if_not_exists: false,
+                column_list: vec![
+                    ColumnDefinition {
+                        column_name: Identifier::from("Json".to_string()),
+                        datatype: DataType::Json,
+                        nullable: true,
+                        primary_key: false,
+                        unique: false,
+                        default: None,
+                        generated: None,
+                    },
+                    ColumnDefinition {
+                        column_name: Identifier::from("Range".to_string()),
+                        datatype: DataType::Int,
+                        nullable: true,
+                        primary_key: false,
+                        unique: false,
+                        default: None,
+                        generated: None,
+                    },
+                ],
+                partition_by: None,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_reserved_keywords_are_not_identifiers() {
+        let query = String::from("CREATE TABLE Select (Id INT)");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn test_delimited_identifiers() {
+        let query = String::from("CREATE TABLE [Order] ([Select] INT, \"Group By\" INT)");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::CreateTable(CreateTableBody {
+                table_name: Identifier::from("Order".to_string()),
+                if_not_exists: false,
+                column_list: vec![
+                    ColumnDefinition {
+                        column_name: Identifier::from("Select".to_string()),
+                        datatype: DataType::Int,
+                        nullable: true,
+                        primary_key: false,
+                        unique: false,
+                        default: None,
+                        generated: None,
+                    },
+                    ColumnDefinition {
+                        column_name: Identifier::from("Group By".to_string()),
+                        datatype: DataType::Int,
+                        nullable: true,
+                        primary_key: false,
+                        unique: false,
+                        default: None,
+                        generated: None,
+                    },
+                ],
+                partition_by: None,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_select_with_joins() {
+        let query = String::from(
+            "SELECT u.Name FROM Users u INNER JOIN Orders o ON u.Id = o.UserId LEFT JOIN Items ON o.Id = Items.OrderId",
+        );
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let qualified = |table: &str, column: &str| {
+            Expr::QualifiedIdentifier(vec![
+                Identifier::from(table.to_string()),
+                Identifier::from(column.to_string()),
+            ])
+        };
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Select(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![SelectItem::qualified_identifier(
+                    vec!["u", "Name"],
+                )]),
+                from_clause: Some(FromClause {
+                    source: TableSource::Table(TableName::from(Identifier::from(
+                        "Users".to_string(),
+                    ))),
+                    alias: Some(Identifier::from("u".to_string())),
+                    joins: vec![
+                        Join {
+                            join_type: JoinType::Inner,
+                            identifier: TableName::from(Identifier::from("Orders".to_string())),
+                            alias: Some(Identifier::from("o".to_string())),
+                            on: Expr::BinaryOperator {
+                                left: Box::new(qualified("u", "Id")),
+                                op: BinaryOperator::Equal,
+                                right: Box::new(qualified("o", "UserId")),
+                            },
+                        },
+                        Join {
+                            join_type: JoinType::Left,
+                            identifier: TableName::from(Identifier::from("Items".to_string())),
+                            alias: None,
+                            on: Expr::BinaryOperator {
+                                left: Box::new(qualified("o", "Id")),
+                                op: BinaryOperator::Equal,
+                                right: Box::new(qualified("Items", "OrderId")),
+                            },
+                        },
+                    ],
+                }),
+                where_clause: None,
+                order_by_clause: None,
+                group_by_clause: None,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_database_qualified_names() {
+        let query = String::from(
+            "SELECT Sales.Users.Name FROM Sales.Users JOIN Archive.Orders o ON Users.Id = o.UserId",
+        );
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let name = |value: &str| Identifier::from(value.to_string());
+        let qualified =
+            |table: &str, column: &str| Expr::QualifiedIdentifier(vec![name(table), name(column)]);
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Select(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![SelectItem::qualified_identifier(
+                    vec!["Sales", "Users", "Name"],
+                )]),
+                from_clause: Some(FromClause {
+                    source: TableSource::Table(TableName::qualified(name("Sales"), name("Users"))),
+                    alias: None,
+                    joins: vec![Join {
+                        join_type: JoinType::Inner,
+                        identifier: TableName::qualified(name("Archive"), name("Orders")),
+                        alias: Some(name("o")),
+                        on: Expr::BinaryOperator {
+                            left: Box::new(qualified("Users", "Id")),
+                            op: BinaryOperator::Equal,
+                            right: Box::new(qualified("o", "UserId")),
+                        },
+                    }],
+                }),
+                where_clause: None,
+                order_by_clause: None,
+                group_by_clause: None,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_order_by_and_group_by_expressions() {
+        let query =
+            String::from("SELECT Name FROM Users GROUP BY Age / 10 ORDER BY LENGTH(Name) DESC");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let name = |value: &str| Identifier::from(value.to_string());
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Select(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![SelectItem::simple_identifier("Name")]),
+                from_clause: Some(FromClause {
+                    source: TableSource::Table(TableName::from(name("Users"))),
+                    alias: None,
+                    joins: vec![],
+                }),
+                where_clause: None,
+                group_by_clause: Some(GroupByClause {
+                    expr: Box::new(Expr::BinaryOperator {
+                        left: Box::new(Expr::Identifier(name("Age"))),
+                        op: BinaryOperator::Divide,
+                        right: Box::new(Expr::Value(Value::Number(String::from("10")))),
+                    }),
+                }),
+                order_by_clause: Some(OrderByClause {
+                    expr: Box::new(Expr::Function {
+                        name: name("LENGTH"),
+                        args: vec![Expr::Identifier(name("Name"))],
+                        distinct: false,
+                    }),
+                    dir: OrderDirection::Desc,
+                }),
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_order_by_without_expression_is_an_error() {
+        let query = String::from("SELECT a FROM b ORDER BY DESC");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn test_failed_statement_without_error_is_reported() {
+        for query in ["SELECT 1; SELECT a (1); SELECT 2", "SELECT a (1)"] {
+            let query = String::from(query);
+            let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+            assert!(actual.is_err(), "{query}: {actual:?}");
+        }
+    }
+
+    #[test]
+    fn test_join_without_on_is_an_error() {
+        let query = String::from("SELECT a FROM b RIGHT JOIN c");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let expected = Err(vec![ParseError {
+            kind: ParseErrorKind::ExpectedKeyword(String::from("ON")),
+            position: 28,
+            end: 28,
+        }]);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_statement_kind() {
+        let cases = [
+            ("SELECT 1", StatementKind::ReadOnly),
+            ("CREATE TABLE t (Id INT)", StatementKind::Ddl),
+            ("CREATE INDEX IX_Id ON t (Id)", StatementKind::Ddl),
+            ("CREATE DATABASE d", StatementKind::Server),
+        ];
+
+        for (query, expected) in cases {
+            let query = String::from(query);
+            let program = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query)
+                .parse()
+                .unwrap();
+
+            let Program::Statements(statements) = program else {
+                panic!("No statements in {query}");
+            };
+
+            assert_eq!(statements[0].kind(), expected, "{query}");
+        }
+
+        assert_eq!(
+            Statement::User(UserStatement::Delete).kind(),
+            StatementKind::Write
+        );
+    }
+
+    #[test]
+    fn test_aggregate_function_calls() {
+        let query = String::from("SELECT COUNT(*), SUM(DISTINCT a), max(a)");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let a = || Expr::Identifier(Identifier::from("a".to_string()));
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Select(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![
+                    SelectItem::new(Expr::Function {
+                        name: Identifier::from("COUNT".to_string()),
+                        args: vec![Expr::Wildcard],
+                        distinct: false,
+                    }),
+                    SelectItem::new(Expr::Function {
+                        name: Identifier::from("SUM".to_string()),
+                        args: vec![a()],
+                        distinct: true,
+                    }),
+                    SelectItem::new(Expr::Function {
+                        name: Identifier::from("max".to_string()),
+                        args: vec![a()],
+                        distinct: false,
+                    }),
+                ]),
+                from_clause: None,
+                where_clause: None,
+                order_by_clause: None,
+                group_by_clause: None,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+
+        let Ok(Program::Statements(statements)) = actual else {
+            unreachable!();
+        };
+        let Statement::User(UserStatement::Select(body)) = &statements[0] else {
+            unreachable!();
+        };
+
+        assert!(body
+            .select_item_list
+            .item_list
+            .iter()
+            .all(|item| item.expr.is_aggregate()));
+        assert_eq!(
+            body.select_item_list.item_list[1].expr.to_string(),
+            "SUM(DISTINCT a)"
+        );
+    }
+
+    #[test]
+    fn test_distinct_requires_an_argument() {
+        for query in ["SELECT COUNT(DISTINCT)", "SELECT COUNT(DISTINCT *)"] {
+            let query = String::from(query);
+            let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+            assert!(actual.is_err(), "{query}");
+        }
+    }
+
+    #[test]
+    fn test_uuid_columns_and_functions() {
+        let query = String::from("CREATE TABLE t (Id UUID, Other UNIQUEIDENTIFIER); SELECT UUID()");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![
+            Statement::User(UserStatement::CreateTable(CreateTableBody {
+                table_name: Identifier::from("t".to_string()),
+                if_not_exists: false,
+                column_list: vec![
+                    ColumnDefinition {
+                        column_name: Identifier::from("Id".to_string()),
+                        datatype: DataType::Uuid,
+                        nullable: true,
+                        primary_key: false,
+                        unique: false,
+                        default: None,
+                        generated: None,
+                    },
+                    ColumnDefinition {
+                        column_name: Identifier::from("Other".to_string()),
+                        datatype: DataType::Uuid,
+                        nullable: true,
+                        primary_key: false,
+                        unique: false,
+                        default: None,
+                        generated: None,
+                    },
+                ],
+                partition_by: None,
+            })),
+            Statement::User(UserStatement::Select(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![SelectItem::new(Expr::Function {
+                    name: Identifier::from("UUID".to_string()),
+                    args: vec![],
+                    distinct: false,
+                })]),
+                from_clause: None,
+                where_clause: None,
+                order_by_clause: None,
+                group_by_clause: None,
+            })),
+        ]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_generated_columns() {
+        let query = String::from(
+            "CREATE TABLE t (a INT, b INT GENERATED ALWAYS AS (a + 1) STORED, c INT GENERATED ALWAYS AS (a) VIRTUAL, d INT GENERATED ALWAYS AS (a))",
+        );
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let a = || Expr::Identifier(Identifier::from("a".to_string()));
+        let column = |name: &str, generated| ColumnDefinition {
+            column_name: Identifier::from(name.to_string()),
+            datatype: DataType::Int,
+            nullable: true,
+            primary_key: false,
+            unique: false,
+            default: None,
+            generated,
+        };
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::CreateTable(CreateTableBody {
+                table_name: Identifier::from("t".to_string()),
+                if_not_exists: false,
+                column_list: vec![
+                    column("a", None),
+                    column(
+                        "b",
+                        Some(GeneratedColumn {
+                            expr: Expr::BinaryOperator {
+                                left: Box::new(a()),
+                                op: BinaryOperator::Plus,
+                                right: Box::new(Expr::Value(Value::Number("1".to_string()))),
+                            },
+                            storage: GeneratedStorage::Stored,
+                        }),
+                    ),
+                    column(
+                        "c",
+                        Some(GeneratedColumn {
+                            expr: a(),
+                            storage: GeneratedStorage::Virtual,
+                        }),
+                    ),
+                    column(
+                        "d",
+                        Some(GeneratedColumn {
+                            expr: a(),
+                            storage: GeneratedStorage::Virtual,
+                        }),
+                    ),
+                ],
+                partition_by: None,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_generated_column_requires_always_as() {
+        let query = String::from("CREATE TABLE t (a INT, b INT GENERATED AS (a))");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        assert_eq!(
+            actual.map_err(|errors| errors[0].kind.clone()),
+            Err(ParseErrorKind::ExpectedKeyword(String::from("ALWAYS")))
+        );
+    }
+
+    #[test]
+    fn test_select_statement_with_function_call() {
+        let query = String::from("select count(*) as Total, round(a, 2)");
+        let tokens = vec![
+            Token::Keyword(Keyword::Select),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(7, 12))),
+            Token::ParenOpen,
+            Token::Arithmetic(Arithmetic::Multiply),
+            Token::ParenClose,
+            Token::Space,
+            Token::Keyword(Keyword::As),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(19, 24))),
+            Token::Comma,
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(26, 31))),
+            Token::ParenOpen,
+            Token::Identifier(LexerIdent::new(Slice::new(32, 33))),
+            Token::Comma,
+            Token::Space,
+            Token::Numeric(Slice::new(35, 36)),
+            Token::ParenClose,
+            Token::EOF,
+        ];
+
+        let lexer = Parser::new_positionless(tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Select(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![
+                    SelectItem::aliased(
+                        Expr::Function {
+                            name: Identifier::from("count".to_string()),
+                            args: vec![Expr::Wildcard],
+                            distinct: false,
+                        },
+                        Identifier::from("Total".to_string()),
+                    ),
+                    SelectItem::new(Expr::Function {
+                        name: Identifier::from("round".to_string()),
+                        args: vec![
+                            Expr::Identifier(Identifier::from("a".to_string())),
+                            Expr::Value(Value::Number("2".to_string())),
+                        ],
+                        distinct: false,
+                    }),
+                ]),
+                from_clause: None,
+                where_clause: None,
+                order_by_clause: None,
+                group_by_clause: None,
+            }),
+        )]));
+
+        assert_eq!(lexer, expected);
+    }
+
+    #[test]
+    fn test_select_statement_with_unclosed_function_call() {
+        let query = String::from("select f(1");
+        let tokens = vec![
+            Token::Keyword(Keyword::Select),
+            Token::Space,
+            Token::Identifier(LexerIdent::new(Slice::new(7, 8))),
+            Token::ParenOpen,
+            Token::Numeric(Slice::new(9, 10)),
+            Token::EOF,
+        ];
+
+        let lexer = Parser::new_positionless(tokens, &query).parse();
+
+        assert!(lexer.is_err());
+    }
+
+    #[test]
+    fn test_nested_scalar_function_calls() {
+        let query = String::from(
+            "SELECT COALESCE(UPPER(u.Name), 'none') FROM Users u WHERE LEN(u.Name) > 3",
+        );
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let function = |name: &str, args| Expr::Function {
+            name: Identifier::from(name.to_string()),
+            args,
+            distinct: false,
+        };
+        let name = || {
+            Expr::QualifiedIdentifier(vec![
+                Identifier::from("u".to_string()),
+                Identifier::from("Name".to_string()),
+            ])
+        };
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Select(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![SelectItem::new(function(
+                    "COALESCE",
+                    vec![
+                        function("UPPER", vec![name()]),
+                        Expr::Value(Value::String("none".to_string(), QuoteType::Single)),
+                    ],
+                ))]),
+                from_clause: Some(FromClause {
+                    source: TableSource::Table(TableName::from(Identifier::from(
+                        "Users".to_string(),
+                    ))),
+                    alias: Some(Identifier::from("u".to_string())),
+                    joins: vec![],
+                }),
+                where_clause: Some(WhereClause {
+                    expr: Expr::BinaryOperator {
+                        left: Box::new(function("LEN", vec![name()])),
+                        op: BinaryOperator::GreaterThan,
+                        right: Box::new(Expr::Value(Value::Number("3".to_string()))),
+                    },
+                }),
+                order_by_clause: None,
+                group_by_clause: None,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_in_and_not_in() {
+        let query = String::from("SELECT 1 IN (a, b + 2) FROM t WHERE c + 1 NOT IN ((3))");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let identifier = |name: &str| Expr::Identifier(Identifier::from(name.to_string()));
+        let number = |n: &str| Expr::Value(Value::Number(n.to_string()));
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Select(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![SelectItem::new(Expr::IsIn {
+                    expr: Box::new(number("1")),
+                    list: vec![
+                        identifier("a"),
+                        Expr::BinaryOperator {
+                            left: Box::new(identifier("b")),
+                            op: BinaryOperator::Plus,
+                            right: Box::new(number("2")),
+                        },
+                    ],
+                })]),
+                from_clause: Some(FromClause {
+                    source: TableSource::Table(TableName::from(Identifier::from("t".to_string()))),
+                    alias: None,
+                    joins: vec![],
+                }),
+                where_clause: Some(WhereClause {
+                    expr: Expr::IsNotIn {
+                        expr: Box::new(Expr::BinaryOperator {
+                            left: Box::new(identifier("c")),
+                            op: BinaryOperator::Plus,
+                            right: Box::new(number("1")),
+                        }),
+                        list: vec![number("3")],
+                    },
+                }),
+                order_by_clause: None,
+                group_by_clause: None,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_malformed_in_lists() {
+        let cases = [
+            (
+                "SELECT 1 IN 1",
+                ParseErrorKind::ExpectedParentheses("(".to_string()),
+            ),
+            ("SELECT 1 IN ()", ParseErrorKind::ExpectedValue),
+            (
+                "SELECT 1 IN (1, 2",
+                ParseErrorKind::ExpectedParentheses(")".to_string()),
+            ),
+            (
+                "SELECT 1 NOT 1",
+                ParseErrorKind::ExpectedKeyword("IN or LIKE".to_string()),
+            ),
+        ];
+
+        for (query, expected) in cases {
+            let query = String::from(query);
+            let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+            assert_eq!(
+                actual.map_err(|errors| errors[0].kind.clone()),
+                Err(expected),
+                "{query}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_like_and_not_like() {
+        let query = String::from("SELECT 'abc' LIKE 'a%' FROM t WHERE Name NOT LIKE 'x' + '_'");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let string = |s: &str| Expr::Value(Value::String(s.to_string(), QuoteType::Single));
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Select(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![SelectItem::new(Expr::Like {
+                    expr: Box::new(string("abc")),
+                    pattern: Box::new(string("a%")),
+                })]),
+                from_clause: Some(FromClause {
+                    source: TableSource::Table(TableName::from(Identifier::from("t".to_string()))),
+                    alias: None,
+                    joins: vec![],
+                }),
+                where_clause: Some(WhereClause {
+                    expr: Expr::NotLike {
+                        expr: Box::new(Expr::Identifier(Identifier::from("Name".to_string()))),
+                        pattern: Box::new(Expr::BinaryOperator {
+                            left: Box::new(string("x")),
+                            op: BinaryOperator::Plus,
+                            right: Box::new(string("_")),
+                        }),
+                    },
+                }),
+                order_by_clause: None,
+                group_by_clause: None,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_unary_operators() {
+        let query = String::from("SELECT NOT 1 = 2, -(1 + 2) * 3, +4, NOT NOT TRUE");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let number = |n: &str| Box::new(Expr::Value(Value::Number(n.to_string())));
+        let unary = |op, expr| Expr::UnaryOperator {
+            op,
+            expr: Box::new(expr),
+        };
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Select(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![
+                    SelectItem::new(unary(
+                        UnaryOperator::Not,
+                        Expr::BinaryOperator {
+                            left: number("1"),
+                            op: BinaryOperator::Equal,
+                            right: number("2"),
+                        },
+                    )),
+                    SelectItem::new(Expr::BinaryOperator {
+                        left: Box::new(unary(
+                            UnaryOperator::Minus,
+                            Expr::BinaryOperator {
+                                left: number("1"),
+                                op: BinaryOperator::Plus,
+                                right: number("2"),
+                            },
+                        )),
+                        op: BinaryOperator::Multiply,
+                        right: number("3"),
+                    }),
+                    SelectItem::new(unary(UnaryOperator::Plus, *number("4"))),
+                    SelectItem::new(unary(
+                        UnaryOperator::Not,
+                        unary(UnaryOperator::Not, Expr::Value(Value::Boolean(true))),
+                    )),
+                ]),
+                from_clause: None,
+                where_clause: None,
+                order_by_clause: None,
+                group_by_clause: None,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_unary_operator_without_operand() {
+        let query = String::from("SELECT NOT");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        assert_eq!(
+            actual.map_err(|errors| errors[0].kind.clone()),
+            Err(ParseErrorKind::ExpectedValue)
+        );
+    }
+
+    #[test]
+    fn test_cast() {
+        let query = String::from("SELECT CAST(1 + 2 AS BOOLEAN), CAST('{}' AS json)");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Select(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![
+                    SelectItem::new(Expr::Cast {
+                        expr: Box::new(Expr::BinaryOperator {
+                            left: Box::new(Expr::Value(Value::Number("1".to_string()))),
+                            op: BinaryOperator::Plus,
+                            right: Box::new(Expr::Value(Value::Number("2".to_string()))),
+                        }),
+                        data_type: DataType::Boolean,
+                    }),
+                    SelectItem::new(Expr::Cast {
+                        expr: Box::new(Expr::Value(Value::String(
+                            "{}".to_string(),
+                            QuoteType::Single,
+                        ))),
+                        data_type: DataType::Json,
+                    }),
+                ]),
+                from_clause: None,
+                where_clause: None,
+                order_by_clause: None,
+                group_by_clause: None,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_malformed_casts() {
+        let cases = [
+            (
+                "SELECT CAST 1",
+                ParseErrorKind::ExpectedParentheses("(".to_string()),
+            ),
+            (
+                "SELECT CAST(1 INT)",
+                ParseErrorKind::ExpectedKeyword("AS".to_string()),
+            ),
+            ("SELECT CAST(1 AS Money)", ParseErrorKind::ExpectedDataType),
+            (
+                "SELECT CAST(1 AS INT",
+                ParseErrorKind::ExpectedParentheses(")".to_string()),
+            ),
+        ];
+
+        for (query, expected) in cases {
+            let query = String::from(query);
+            let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+            assert_eq!(
+                actual.map_err(|errors| errors[0].kind.clone()),
+                Err(expected),
+                "{query}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_derived_table() {
+        let query =
+            String::from("SELECT * FROM (SELECT Id AS x FROM Users) t JOIN Orders o ON t.x = o.Id");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let derived = SelectExpressionBody {
+            select_item_list: SelectItemList::from(vec![SelectItem::aliased_identifier(
+                "Id",
+                Identifier::from("x".to_string()),
+            )]),
+            from_clause: Some(FromClause {
+                source: TableSource::Table(TableName::from(Identifier::from("Users".to_string()))),
+                alias: None,
+                joins: vec![],
+            }),
+            where_clause: None,
+            order_by_clause: None,
+            group_by_clause: None,
+        };
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Select(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![SelectItem::new(Expr::Wildcard)]),
+                from_clause: Some(FromClause {
+                    source: TableSource::Derived(Box::new(derived)),
+                    alias: Some(Identifier::from("t".to_string())),
+                    joins: vec![Join {
+                        join_type: JoinType::Inner,
+                        identifier: TableName::from(Identifier::from("Orders".to_string())),
+                        alias: Some(Identifier::from("o".to_string())),
+                        on: Expr::BinaryOperator {
+                            left: Box::new(Expr::QualifiedIdentifier(vec![
+                                Identifier::from("t".to_string()),
+                                Identifier::from("x".to_string()),
+                            ])),
+                            op: BinaryOperator::Equal,
+                            right: Box::new(Expr::QualifiedIdentifier(vec![
+                                Identifier::from("o".to_string()),
+                                Identifier::from("Id".to_string()),
+                            ])),
+                        },
+                    }],
+                }),
+                where_clause: None,
+                order_by_clause: None,
+                group_by_clause: None,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_malformed_derived_tables() {
+        let cases = [
+            ("SELECT * FROM (SELECT 1)", ParseErrorKind::ExpectedAlias),
+            (
+                "SELECT * FROM (1) t",
+                ParseErrorKind::ExpectedKeyword("SELECT".to_string()),
+            ),
+            (
+                "SELECT * FROM (SELECT 1 t",
+                ParseErrorKind::ExpectedParentheses(")".to_string()),
+            ),
+        ];
+
+        for (query, expected) in cases {
+            let query = String::from(query);
+            let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+            assert_eq!(
+                actual.map_err(|errors| errors[0].kind.clone()),
+                Err(expected),
+                "{query}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_subqueries() {
+        let query = String::from(
+            "SELECT (SELECT Id FROM t) FROM Users WHERE Id IN (SELECT UserId FROM Orders)",
+        );
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let select = |item: SelectItem, table: &str| {
+            Box::new(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![item]),
+                from_clause: Some(FromClause {
+                    source: TableSource::Table(TableName::from(Identifier::from(
+                        table.to_string(),
+                    ))),
+                    alias: None,
+                    joins: vec![],
+                }),
+                where_clause: None,
+                order_by_clause: None,
+                group_by_clause: None,
+            })
+        };
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::Select(SelectExpressionBody {
+                select_item_list: SelectItemList::from(vec![SelectItem::new(Expr::Subquery(
+                    select(SelectItem::simple_identifier("Id"), "t"),
+                ))]),
+                from_clause: Some(FromClause {
+                    source: TableSource::Table(TableName::from(Identifier::from(
+                        "Users".to_string(),
+                    ))),
+                    alias: None,
+                    joins: vec![],
+                }),
+                where_clause: Some(WhereClause {
+                    expr: Expr::IsInSubquery {
+                        expr: Box::new(Expr::Identifier(Identifier::from("Id".to_string()))),
+                        subquery: select(SelectItem::simple_identifier("UserId"), "Orders"),
+                    },
+                }),
+                order_by_clause: None,
+                group_by_clause: None,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_not_in_subquery() {
+        let query = String::from("SELECT 1 NOT IN (SELECT Id FROM t)");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let Ok(Program::Statements(statements)) = actual else {
+            panic!("Failed to parse: {actual:?}");
+        };
+        let Statement::User(UserStatement::Select(body)) = &statements[0] else {
+            panic!("Expected a SELECT");
+        };
+
+        assert!(matches!(
+            body.select_item_list.item_list[0].expr,
+            Expr::IsNotInSubquery { .. }
+        ));
+    }
+
+    #[test]
+    fn test_not_exists() {
+        let query = String::from("SELECT 1 FROM t WHERE NOT EXISTS (SELECT * FROM Bans)");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let Ok(Program::Statements(statements)) = actual else {
+            panic!("Failed to parse: {actual:?}");
+        };
+        let Statement::User(UserStatement::Select(body)) = &statements[0] else {
+            panic!("Expected a SELECT");
+        };
+        let Some(WhereClause {
+            expr: Expr::UnaryOperator { op, expr },
+        }) = &body.where_clause
+        else {
+            panic!("Expected a NOT in the WHERE clause");
+        };
+
+        assert_eq!(*op, UnaryOperator::Not);
+        assert_eq!(expr.to_string(), "EXISTS (SELECT [*] FROM Bans )");
+    }
+
+    #[test]
+    fn test_exists_requires_a_select() {
+        let query = String::from("SELECT EXISTS (1)");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        assert_eq!(
+            actual.map_err(|errors| errors[0].kind.clone()),
+            Err(ParseErrorKind::ExpectedKeyword(String::from("SELECT")))
+        );
+    }
+
+    #[test]
+    fn test_create_table_with_richer_data_types() {
+        let query = String::from(
+            "CREATE TABLE Users (Name VARCHAR( 50 ), Bio TEXT, Score FLOAT, Born DATE, Views BIGINT)",
+        );
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let column = |name: &str, datatype: DataType| ColumnDefinition {
+            column_name: Identifier::from(name.to_string()),
+            datatype,
+            nullable: true,
+            primary_key: false,
+            unique: false,
+            default: None,
+            generated: None,
+        };
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::CreateTable(CreateTableBody {
+                table_name: Identifier::from("Users".to_string()),
+                if_not_exists: false,
+                column_list: vec![
+                    column("Name", DataType::Varchar(50)),
+                    column("Bio", DataType::Text),
+                    column("Score", DataType::Float),
+                    column("Born", DataType::Date),
+                    column("Views", DataType::BigInt),
+                ],
+                partition_by: None,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_varchar_requires_a_length() {
+        let cases = [
+            (
+                "CREATE TABLE t (a VARCHAR)",
+                ParseErrorKind::ExpectedParentheses("(".to_string()),
+            ),
+            (
+                "CREATE TABLE t (a VARCHAR())",
+                ParseErrorKind::ExpectedValue,
+            ),
+            (
+                "CREATE TABLE t (a VARCHAR(1.5))",
+                ParseErrorKind::InvalidNumericLiteral,
+            ),
+            (
+                "CREATE TABLE t (a VARCHAR(10, b INT)",
+                ParseErrorKind::ExpectedParentheses(")".to_string()),
+            ),
+        ];
+
+        for (query, expected) in cases {
+            let query = String::from(query);
+            let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+            assert_eq!(
+                actual.map_err(|errors| errors[0].kind.clone()),
+                Err(expected),
+                "{query}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_column_constraints() {
+        let query = String::from(
+            "CREATE TABLE Users (Id INT PRIMARY KEY, Name TEXT NOT NULL UNIQUE, Age INT DEFAULT 0 NOT NULL, Bio TEXT NULL)",
+        );
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let column = |name: &str, datatype: DataType| ColumnDefinition {
+            column_name: Identifier::from(name.to_string()),
+            datatype,
+            nullable: true,
+            primary_key: false,
+            unique: false,
+            default: None,
+            generated: None,
+        };
+
+        let expected = Ok(Program::Statements(vec![Statement::User(
+            UserStatement::CreateTable(CreateTableBody {
+                table_name: Identifier::from("Users".to_string()),
+                if_not_exists: false,
+                column_list: vec![
+                    ColumnDefinition {
+                        nullable: false,
+                        primary_key: true,
+                        ..column("Id", DataType::Int)
+                    },
+                    ColumnDefinition {
+                        nullable: false,
+                        unique: true,
+                        ..column("Name", DataType::Text)
+                    },
+                    ColumnDefinition {
+                        nullable: false,
+                        default: Some(Expr::Value(Value::Number("0".to_string()))),
+                        ..column("Age", DataType::Int)
+                    },
+                    column("Bio", DataType::Text),
+                ],
+                partition_by: None,
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
     }
 
     #[test]
-    fn test_select_statement_with_group_by() {
-        let query = String::from("select a from b group by c;");
-        let tokens = vec![
-            Token::Keyword(Keyword::Select),
-            Token::Space,
-            Token::Identifier(LexerIdent::new(Slice::new(7, 8))),
-            Token::Space,
-            Token::Keyword(Keyword::From),
-            Token::Space,
-            Token::Identifier(LexerIdent::new(Slice::new(14, 15))),
-            Token::Space,
-            Token::Keyword(Keyword::Group),
-            Token::Space,
-            Token::Keyword(Keyword::By),
-            Token::Space,
-            Token::Identifier(LexerIdent::new(Slice::new(25, 26))),
-            Token::EOF,
+    fn test_incomplete_column_constraints() {
+        let cases = [
+            (
+                "CREATE TABLE t (a INT NOT 1)",
+                ParseErrorKind::ExpectedKeyword("NULL".to_string()),
+            ),
+            (
+                "CREATE TABLE t (a INT PRIMARY)",
+                ParseErrorKind::ExpectedKeyword("KEY".to_string()),
+            ),
         ];
 
-        let lexer = Parser::new_positionless(tokens, &query).parse();
+        for (query, expected) in cases {
+            let query = String::from(query);
+            let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+            assert_eq!(
+                actual.map_err(|errors| errors[0].kind.clone()),
+                Err(expected),
+                "{query}"
+            );
+        }
+    }
+
+    fn parse_with(query: &str, options: ParserOptions) -> Result<Program, Vec<ParseError>> {
+        let query = String::from(query);
+        let tokens = lexer::Lexer::new(&query).lex().tokens;
+
+        let result = Parser::new(tokens, &query).with_options(options).parse();
+        result
+    }
+
+    #[test]
+    fn test_depth_limit_counts_nesting_not_expressions() {
+        let wide = format!(
+            "SELECT {}",
+            (1..=100)
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        assert!(parse_with(&wide, ParserOptions::default()).is_ok());
+
+        let nested = |depth: usize| format!("SELECT {}1{}", "(".repeat(depth), ")".repeat(depth));
+        let options = ParserOptions {
+            max_depth: 5,
+            ..ParserOptions::default()
+        };
+
+        assert!(parse_with(&nested(4), options).is_ok());
+        assert_eq!(
+            parse_with(&nested(5), options).map_err(|errors| errors[0].kind.clone()),
+            Err(ParseErrorKind::MaximumRecursionDepthReached(5))
+        );
+    }
+
+    #[test]
+    fn test_statement_and_select_item_limits() {
+        let options = ParserOptions {
+            max_statements: 2,
+            max_select_items: 2,
+            ..ParserOptions::default()
+        };
+
+        assert!(parse_with("SELECT 1, 2; SELECT 3", options).is_ok());
+        assert_eq!(
+            parse_with("SELECT 1; SELECT 2; SELECT 3", options)
+                .map_err(|errors| errors[0].kind.clone()),
+            Err(ParseErrorKind::TooManyStatements(2))
+        );
+        assert_eq!(
+            parse_with("SELECT 1, 2, 3", options).map_err(|errors| errors[0].kind.clone()),
+            Err(ParseErrorKind::TooManySelectItems(2))
+        );
+    }
+
+    #[test]
+    fn test_newlines_and_comments_are_skipped() {
+        let query = String::from("\n-- Setup\nSELECT /* one */ 1,\n  2;\n/* done */\n");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
 
         let expected = Ok(Program::Statements(vec![Statement::User(
             UserStatement::Select(SelectExpressionBody {
-                select_item_list: SelectItemList::from(vec![SelectItem::simple_identifier("a")]),
-                from_clause: Some(FromClause {
-                    identifier: Identifier {
-                        value: String::from("b"),
-                    },
-                    alias: None,
-                }),
+                select_item_list: SelectItemList::from(vec![
+                    SelectItem::new(Expr::Value(Value::Number("1".to_string()))),
+                    SelectItem::new(Expr::Value(Value::Number("2".to_string()))),
+                ]),
+                from_clause: None,
                 where_clause: None,
                 order_by_clause: None,
-                group_by_clause: Some(GroupByClause {
-                    identifier: Identifier {
-                        value: String::from("c"),
-                    },
-                }),
+                group_by_clause: None,
             }),
         )]));
 
-        assert_eq!(lexer, expected);
+        assert_eq!(actual, expected);
     }
 
     #[test]
-    fn test_empty_tokens() {
-        let tokens = vec![];
-        let actual = Parser::new_positionless(tokens, EMPTY_QUERY).parse();
-        let expected = Ok(Program::Statements(vec![]));
+    fn test_set_statement() {
+        let query = String::from("SET cache_size = 1000");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::Server(
+            ServerStatement::Set(SetBody {
+                name: Identifier::from("cache_size".to_string()),
+                value: Expr::Value(Value::Number("1000".to_string())),
+            }),
+        )]));
+
+        assert_eq!(actual, expected);
+
+        let query = String::from("SET cache_size 1000");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        assert_eq!(
+            actual.map_err(|errors| errors[0].kind.clone()),
+            Err(ParseErrorKind::ExpectedKeyword("=".to_string()))
+        );
+
+        let query = String::from("PRAGMA null_display = '(null)'");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let expected = Ok(Program::Statements(vec![Statement::Server(
+            ServerStatement::Set(SetBody {
+                name: Identifier::from("null_display".to_string()),
+                value: Expr::Value(Value::String("(null)".to_string(), QuoteType::Single)),
+            }),
+        )]));
 
         assert_eq!(actual, expected);
     }
 
     #[test]
-    fn test_select_statement_missing_select_items_list() {
-        let tokens = vec![Token::Keyword(Keyword::Select), Token::EOF];
-        let actual = Parser::new_positionless(tokens, EMPTY_QUERY).parse();
-
-        let errors = match actual {
-            Ok(_) => vec![],
-            Err(e) => e,
+    fn test_describe_statement() {
+        let query = String::from("DESCRIBE Users; desc [Sales Data]");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        let describe = |name: &str| {
+            Statement::User(UserStatement::Describe(DescribeBody {
+                table_name: Identifier::from(name.to_string()),
+            }))
         };
 
-        assert_eq!(errors.len(), 1);
         assert_eq!(
-            errors[0],
-            ParseError {
-                position: 0,
-                kind: ParseErrorKind::ExpectedIdentifier,
-            }
+            actual,
+            Ok(Program::Statements(vec![
+                describe("Users"),
+                describe("Sales Data")
+            ]))
         );
     }
 
     #[test]
-    fn test_select_statement_missing_select_item_after_comma() {
-        let tokens = vec![
-            Token::Keyword(Keyword::Select),
-            Token::Identifier(LexerIdent::new(Slice::new(0, 1))),
-            Token::Comma,
-            Token::EOF,
-        ];
+    fn test_if_exists_options() {
+        let query = String::from(
+            "CREATE TABLE IF NOT EXISTS t (Id INT); CREATE DATABASE IF NOT EXISTS Sales; DROP TABLE t; DROP DATABASE IF EXISTS Sales",
+        );
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
 
-        let actual = Parser::new_positionless(tokens, &String::from("select a,")).parse();
+        let Ok(Program::Statements(statements)) = actual else {
+            panic!("Failed to parse: {actual:?}");
+        };
 
-        let errors = match actual {
-            Ok(_) => vec![],
-            Err(e) => e,
+        let Statement::User(UserStatement::CreateTable(create_table)) = &statements[0] else {
+            panic!("Expected CREATE TABLE: {:?}", statements[0]);
         };
+        assert!(create_table.if_not_exists);
+        assert_eq!(create_table.table_name, Identifier::from("t".to_string()));
 
-        assert_eq!(errors.len(), 1);
         assert_eq!(
-            errors[0],
-            ParseError {
-                position: 0,
-                kind: ParseErrorKind::ExpectedIdentifier,
-            }
+            statements[1..],
+            [
+                Statement::Server(ServerStatement::CreateDatabase(CreateDatabaseBody {
+                    database_name: Identifier::from("Sales".to_string()),
+                    if_not_exists: true,
+                })),
+                Statement::User(UserStatement::DropTable(DropTableBody {
+                    table_name: Identifier::from("t".to_string()),
+                    if_exists: false,
+                })),
+                Statement::Server(ServerStatement::DropDatabase(DropDatabaseBody {
+                    database_name: Identifier::from("Sales".to_string()),
+                    if_exists: true,
+                })),
+            ]
         );
+        assert_eq!(statements[2].kind(), StatementKind::Ddl);
+        assert_eq!(statements[3].kind(), StatementKind::Server);
+
+        let cases = [
+            ("CREATE TABLE IF EXISTS t (Id INT)", "NOT"),
+            ("CREATE DATABASE IF NOT Sales", "EXISTS"),
+            ("DROP TABLE IF t", "EXISTS"),
+        ];
+
+        for (query, keyword) in cases {
+            let query = String::from(query);
+            let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+            assert_eq!(
+                actual.map_err(|errors| errors[0].kind.clone()),
+                Err(ParseErrorKind::ExpectedKeyword(keyword.to_string())),
+                "{query}"
+            );
+        }
     }
 
     #[test]
-    fn test_missing_statement() {
-        let tokens = vec![Token::Semicolon];
-        let lexer = Parser::new_positionless(tokens, EMPTY_QUERY).parse();
+    fn test_use_statement() {
+        let query = String::from("USE [Sales Data]; SELECT 1");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
 
-        let errors = match lexer {
-            Ok(_) => vec![],
-            Err(e) => e,
+        let Ok(Program::Statements(statements)) = actual else {
+            panic!("Failed to parse: {actual:?}");
         };
 
-        assert_eq!(errors.len(), 1);
         assert_eq!(
-            errors[0],
-            ParseError {
-                position: 0,
-                kind: ParseErrorKind::ExpectedStatemnt,
-            }
+            statements[0],
+            Statement::Server(ServerStatement::Use(UseBody {
+                database_name: Identifier::from("Sales Data".to_string()),
+            }))
+        );
+        assert_eq!(statements[0].kind(), StatementKind::ReadOnly);
+
+        let query = String::from("USE");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        assert_eq!(
+            actual.map_err(|errors| errors[0].kind.clone()),
+            Err(ParseErrorKind::ExpectedIdentifier)
         );
     }
 
     #[test]
-    fn test_simple_insert_statement() {
-        let tokens = vec![Token::Keyword(Keyword::Insert), Token::EOF];
-        let lexer = Parser::new_positionless(tokens, EMPTY_QUERY).parse();
+    fn test_errors_from_every_statement_are_reported() {
+        let query = String::from("SELECT FROM t; SELECT 1;\nUSE; oops 1 2; SELECT 2");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
 
-        let expected = Ok(Program::Statements(vec![Statement::User(
-            UserStatement::Insert,
-        )]));
+        let errors = actual.unwrap_err();
+        let kinds: Vec<ParseErrorKind> = errors.iter().map(|e| e.kind.clone()).collect();
+        let offending: Vec<&str> = errors.iter().map(|e| &query[e.position..e.end]).collect();
 
-        assert_eq!(lexer, expected);
+        assert_eq!(
+            kinds,
+            vec![
+                ParseErrorKind::ExpectedIdentifier,
+                ParseErrorKind::ExpectedIdentifier,
+                ParseErrorKind::ExpectedStatemnt,
+            ]
+        );
+        assert_eq!(offending, vec!["FROM", ";", "oops"]);
     }
 
     #[test]
-    fn test_simple_update_statement() {
-        let tokens = vec![Token::Keyword(Keyword::Update), Token::EOF];
-        let lexer = Parser::new_positionless(tokens, EMPTY_QUERY).parse();
+    fn test_qualified_names_with_many_parts() {
+        let query =
+            String::from("SELECT sales.dbo.users.Name AS n FROM users WHERE sales.users.Id = 1");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
 
-        let expected = Ok(Program::Statements(vec![Statement::User(
-            UserStatement::Update,
-        )]));
+        let Ok(Program::Statements(statements)) = actual else {
+            panic!("Failed to parse: {actual:?}");
+        };
+        let Statement::User(UserStatement::Select(body)) = &statements[0] else {
+            panic!("Expected a SELECT: {:?}", statements[0]);
+        };
 
-        assert_eq!(lexer, expected);
-    }
+        let qualified = |parts: &[&str]| {
+            Expr::QualifiedIdentifier(
+                parts
+                    .iter()
+                    .map(|p| Identifier::from(p.to_string()))
+                    .collect(),
+            )
+        };
 
-    #[test]
-    fn test_simple_delete_statement() {
-        let tokens = vec![Token::Keyword(Keyword::Delete), Token::EOF];
-        let lexer = Parser::new_positionless(tokens, EMPTY_QUERY).parse();
+        assert_eq!(
+            body.select_item_list.item_list[0],
+            SelectItem::aliased(
+                qualified(&["sales", "dbo", "users", "Name"]),
+                Identifier::from("n".to_string())
+            )
+        );
+        assert_eq!(
+            body.where_clause.as_ref().unwrap().expr,
+            Expr::BinaryOperator {
+                left: Box::new(qualified(&["sales", "users", "Id"])),
+                op: BinaryOperator::Equal,
+                right: Box::new(Expr::Value(Value::Number(String::from("1")))),
+            }
+        );
 
-        let expected = Ok(Program::Statements(vec![Statement::User(
-            UserStatement::Delete,
-        )]));
+        let query = String::from("SELECT a.b. FROM t");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
 
-        assert_eq!(lexer, expected);
+        assert_eq!(
+            actual.map_err(|errors| errors[0].kind.clone()),
+            Err(ParseErrorKind::ExpectedIdentifier)
+        );
     }
 
     #[test]
-    fn test_simple_create_table_statement() {
-        let query = String::from("CREATE TABLE Users (Id INT, Age INT)");
-        let tokens = vec![
-            Token::Keyword(Keyword::Create),
-            Token::Space,
-            Token::Keyword(Keyword::Table),
-            Token::Space,
-            Token::Identifier(LexerIdent::new(Slice::new(13, 18))),
-            Token::Space,
-            Token::ParenOpen,
-            Token::Identifier(LexerIdent::new(Slice::new(20, 22))),
-            Token::Space,
-            Token::Keyword(Keyword::Int),
-            Token::Comma,
-            Token::Space,
-            Token::Identifier(LexerIdent::new(Slice::new(28, 31))),
-            Token::Space,
-            Token::Keyword(Keyword::Int),
-            Token::ParenClose,
-            Token::EOF,
-        ];
-        let lexer = Parser::new_positionless(tokens, &query).parse();
+    fn test_select_item_aliases() {
+        let query = String::from(
+            "SELECT 1 + 2 AS three, Name n, u.Id AS 'User Id', Price * 2 AS [Double], COUNT(*) \"Total\" FROM u",
+        );
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
 
-        let expected = Ok(Program::Statements(vec![Statement::User(
-            UserStatement::CreateTable(CreateTableBody {
-                table_name: Identifier::from("Users".to_string()),
-                column_list: vec![
-                    ColumnDefinition {
-                        column_name: Identifier::from("Id".to_string()),
-                        datatype: DataType::Int,
-                        nullable: false,
-                    },
-                    ColumnDefinition {
-                        column_name: Identifier::from("Age".to_string()),
-                        datatype: DataType::Int,
-                        nullable: false,
-                    },
-                ],
-            }),
-        )]));
+        let Ok(Program::Statements(statements)) = actual else {
+            panic!("Failed to parse: {actual:?}");
+        };
+        let Statement::User(UserStatement::Select(body)) = &statements[0] else {
+            panic!("Expected a SELECT: {:?}", statements[0]);
+        };
 
-        assert_eq!(lexer, expected);
-    }
+        let aliases: Vec<Option<&str>> = body
+            .select_item_list
+            .item_list
+            .iter()
+            .map(|item| item.alias.as_ref().map(|a| a.value.as_str()))
+            .collect();
 
-    #[test]
-    fn test_simple_create_database_statement() {
-        let query = String::from("CREATE Database Db");
-        let tokens = vec![
-            Token::Keyword(Keyword::Create),
-            Token::Space,
-            Token::Keyword(Keyword::Database),
-            Token::Space,
-            Token::Identifier(LexerIdent::new(Slice::new(16, 18))),
-            Token::EOF,
-        ];
-        let lexer = Parser::new_positionless(tokens, &query).parse();
+        assert_eq!(
+            aliases,
+            vec![
+                Some("three"),
+                Some("n"),
+                Some("User Id"),
+                Some("Double"),
+                Some("Total")
+            ]
+        );
+        assert_eq!(
+            body.select_item_list.item_list[3].expr,
+            Expr::BinaryOperator {
+                left: Box::new(Expr::Identifier(Identifier::from("Price".to_string()))),
+                op: BinaryOperator::Multiply,
+                right: Box::new(Expr::Value(Value::Number(String::from("2")))),
+            }
+        );
+        assert_eq!(statements.len(), 1);
 
-        let expected = Ok(Program::Statements(vec![Statement::Server(
-            ServerStatement::CreateDatabase(CreateDatabaseBody {
-                database_name: Identifier::from("Db".to_string()),
-            }),
-        )]));
+        let query = String::from("SELECT 1 AS FROM t");
+        let actual = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
 
-        assert_eq!(lexer, expected);
+        assert_eq!(
+            actual.map_err(|errors| errors[0].kind.clone()),
+            Err(ParseErrorKind::ExpectedIdentifier)
+        );
     }
 }
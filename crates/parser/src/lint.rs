@@ -0,0 +1,111 @@
+use std::fmt;
+
+use crate::ast::{Expr, Program, SelectExpressionBody, Statement, TableSource, UserStatement};
+
+/// Something legal but likely to be a mistake.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum LintWarning {
+    /// SELECT * ties the result to the table's current columns.
+    SelectStar,
+    /// DELETE with no WHERE clause removes every row.
+    DeleteWithoutWhere,
+    /// UPDATE with no WHERE clause changes every row.
+    UpdateWithoutWhere,
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintWarning::SelectStar => {
+                f.write_str("SELECT * returns every column. List the columns needed instead.")
+            }
+            LintWarning::DeleteWithoutWhere => {
+                f.write_str("DELETE has no WHERE clause, so every row will be deleted.")
+            }
+            LintWarning::UpdateWithoutWhere => {
+                f.write_str("UPDATE has no WHERE clause, so every row will be changed.")
+            }
+        }
+    }
+}
+
+/// Check a parsed program for risky statements. Warnings are in statement order.
+pub fn lint(program: &Program) -> Vec<LintWarning> {
+    let mut warnings = vec![];
+
+    let Program::Statements(statements) = program else {
+        return warnings;
+    };
+
+    for statement in statements {
+        match statement {
+            Statement::User(UserStatement::Select(body)) => lint_select(body, &mut warnings),
+            // Neither statement can have a WHERE clause yet
+            Statement::User(UserStatement::Delete) => {
+                warnings.push(LintWarning::DeleteWithoutWhere)
+            }
+            Statement::User(UserStatement::Update) => {
+                warnings.push(LintWarning::UpdateWithoutWhere)
+            }
+            Statement::User(_) | Statement::Server(_) => {}
+        }
+    }
+
+    warnings
+}
+
+fn lint_select(body: &SelectExpressionBody, warnings: &mut Vec<LintWarning>) {
+    // Only a bare *, so COUNT(*) is fine
+    if body
+        .select_item_list
+        .item_list
+        .iter()
+        .any(|item| item.expr == Expr::Wildcard)
+    {
+        warnings.push(LintWarning::SelectStar);
+    }
+
+    if let Some(from_clause) = &body.from_clause {
+        if let TableSource::Derived(derived) = &from_clause.source {
+            lint_select(derived, warnings);
+        }
+    }
+}
+
+#[cfg(test)]
+mod lint_tests {
+    use crate::lint::{lint, LintWarning};
+    use crate::Parser;
+
+    fn lint_query(query: &str) -> Vec<LintWarning> {
+        let query = String::from(query);
+        let program = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        lint(&program.unwrap())
+    }
+
+    #[test]
+    fn test_select_star() {
+        assert_eq!(lint_query("SELECT * FROM t"), vec![LintWarning::SelectStar]);
+        assert_eq!(
+            lint_query("SELECT 1 FROM (SELECT * FROM t) x"),
+            vec![LintWarning::SelectStar]
+        );
+        assert_eq!(lint_query("SELECT COUNT(*) FROM t"), vec![]);
+        assert_eq!(
+            lint_query("SELECT 1 WHERE EXISTS (SELECT * FROM t)"),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_changes_without_where() {
+        assert_eq!(
+            lint_query("SELECT 1; DELETE; UPDATE"),
+            vec![
+                LintWarning::DeleteWithoutWhere,
+                LintWarning::UpdateWithoutWhere
+            ]
+        );
+    }
+}
@@ -1,29 +1,31 @@
 use cli_common::ParseErrorKind;
 
+/// Tracks how deeply the parser has recursed.
+/// Every successful `dec` must be paired with an `inc` on the way back out.
 pub struct RecursionGuard {
+    max_depth: usize,
     remaining: usize,
 }
 
 impl RecursionGuard {
     pub fn new(max_depth: usize) -> Self {
         RecursionGuard {
+            max_depth,
             remaining: max_depth,
         }
     }
 
     pub fn dec(&mut self) -> Result<(), ParseErrorKind> {
         if self.remaining == 0 {
-            return Err(ParseErrorKind::MaximumRecursionDepthReached);
+            return Err(ParseErrorKind::MaximumRecursionDepthReached(self.max_depth));
         }
 
         self.remaining -= 1;
 
         Ok(())
     }
-}
 
-impl Drop for RecursionGuard {
-    fn drop(&mut self) {
-        self.remaining += 1;
+    pub fn inc(&mut self) {
+        self.remaining = (self.remaining + 1).min(self.max_depth);
     }
 }
@@ -0,0 +1,506 @@
+use std::fmt::{self, Display, Formatter};
+
+use lexer::token::KEYWORDS;
+
+use crate::ast::{
+    ColumnDefinition, CreateIndexBody, CreateTableBody, Expr, FromClause, GeneratedStorage,
+    IndexKey, PartitionBy, Program, QuoteType, SelectExpressionBody, SelectItem, ServerStatement,
+    Statement, TableName, TableSource, UnaryOperator, UserStatement, Value,
+};
+
+/// Write a program out as SQL which parses back to the same program.
+/// Unlike the Display impls, which are meant for messages, names are quoted where needed and
+/// nested expressions are bracketed, so the parser's precedence can't change their meaning.
+/// The lexer has no escapes, so a string containing ' or a name containing both ] and "
+/// can't be written faithfully.
+pub fn to_sql(program: &Program) -> String {
+    Sql(program).to_string()
+}
+
+/// Write a single statement out as SQL, without the trailing semicolon.
+pub fn statement_to_sql(statement: &Statement) -> String {
+    Sql(statement).to_string()
+}
+
+/// An AST node, formatted as SQL.
+struct Sql<'a, T>(&'a T);
+
+/// An expression used as an operand, bracketed unless it can't be split up.
+struct Operand<'a>(&'a Expr);
+
+/// A name, quoted if it isn't a plain word or could be read as a keyword.
+struct Name<'a>(&'a str);
+
+/// IF NOT EXISTS, with a trailing space, when set.
+struct IfNotExists(bool);
+
+/// IF EXISTS, with a trailing space, when set.
+struct IfExists(bool);
+
+/// Write each item, separated by commas.
+fn comma_separated<T>(f: &mut Formatter<'_>, items: impl IntoIterator<Item = T>) -> fmt::Result
+where
+    T: Display,
+{
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+
+        write!(f, "{item}")?;
+    }
+
+    Ok(())
+}
+
+impl Display for Sql<'_, Program> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Program::Statements(statements) = self.0 else {
+            return Ok(());
+        };
+
+        for (i, statement) in statements.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+
+            write!(f, "{};", Sql(statement))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Sql<'_, Statement> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Statement::User(UserStatement::Select(body)) => write!(f, "{}", Sql(body)),
+            Statement::User(UserStatement::Update) => f.write_str("UPDATE"),
+            Statement::User(UserStatement::Insert) => f.write_str("INSERT"),
+            Statement::User(UserStatement::Delete) => f.write_str("DELETE"),
+            Statement::User(UserStatement::CreateTable(body)) => write!(f, "{}", Sql(body)),
+            Statement::User(UserStatement::CreateIndex(body)) => write!(f, "{}", Sql(body)),
+            Statement::User(UserStatement::DropTable(body)) => write!(
+                f,
+                "DROP TABLE {}{}",
+                IfExists(body.if_exists),
+                Name(&body.table_name.value)
+            ),
+            Statement::User(UserStatement::Describe(body)) => {
+                write!(f, "DESCRIBE {}", Name(&body.table_name.value))
+            }
+            Statement::Server(ServerStatement::CreateDatabase(body)) => write!(
+                f,
+                "CREATE DATABASE {}{}",
+                IfNotExists(body.if_not_exists),
+                Name(&body.database_name.value)
+            ),
+            Statement::Server(ServerStatement::DropDatabase(body)) => write!(
+                f,
+                "DROP DATABASE {}{}",
+                IfExists(body.if_exists),
+                Name(&body.database_name.value)
+            ),
+            Statement::Server(ServerStatement::Set(body)) => {
+                write!(f, "SET {} = {}", Name(&body.name.value), Sql(&body.value))
+            }
+            Statement::Server(ServerStatement::Use(body)) => {
+                write!(f, "USE {}", Name(&body.database_name.value))
+            }
+        }
+    }
+}
+
+impl Display for Sql<'_, SelectExpressionBody> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let body = self.0;
+
+        f.write_str("SELECT ")?;
+        comma_separated(f, body.select_item_list.item_list.iter().map(Sql))?;
+
+        if let Some(from_clause) = &body.from_clause {
+            write!(f, " FROM {}", Sql(from_clause))?;
+        }
+
+        if let Some(where_clause) = &body.where_clause {
+            write!(f, " WHERE {}", Sql(&where_clause.expr))?;
+        }
+
+        if let Some(group_by) = &body.group_by_clause {
+            write!(f, " GROUP BY {}", Sql(group_by.expr.as_ref()))?;
+        }
+
+        if let Some(order_by) = &body.order_by_clause {
+            write!(
+                f,
+                " ORDER BY {} {}",
+                Sql(order_by.expr.as_ref()),
+                order_by.dir
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Sql<'_, SelectItem> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Sql(&self.0.expr))?;
+
+        match &self.0.alias {
+            Some(alias) => write!(f, " AS {}", Name(&alias.value)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for Sql<'_, FromClause> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let from_clause = self.0;
+
+        match &from_clause.source {
+            TableSource::Table(name) => write!(f, "{}", Sql(name))?,
+            TableSource::Derived(body) => write!(f, "({})", Sql(body.as_ref()))?,
+        }
+
+        if let Some(alias) = &from_clause.alias {
+            write!(f, " {}", Name(&alias.value))?;
+        }
+
+        for join in &from_clause.joins {
+            write!(f, " {} JOIN {}", join.join_type, Sql(&join.identifier))?;
+
+            if let Some(alias) = &join.alias {
+                write!(f, " {}", Name(&alias.value))?;
+            }
+
+            write!(f, " ON {}", Sql(&join.on))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Sql<'_, TableName> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(database) = &self.0.database {
+            write!(f, "{}.", Name(&database.value))?;
+        }
+
+        write!(f, "{}", Name(&self.0.table.value))
+    }
+}
+
+impl Display for Sql<'_, CreateTableBody> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let body = self.0;
+
+        write!(
+            f,
+            "CREATE TABLE {}{} (",
+            IfNotExists(body.if_not_exists),
+            Name(&body.table_name.value)
+        )?;
+        comma_separated(f, body.column_list.iter().map(Sql))?;
+        f.write_str(")")?;
+
+        match &body.partition_by {
+            Some(PartitionBy::Range(column)) => {
+                write!(f, " PARTITION BY RANGE ({})", Name(&column.value))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for Sql<'_, ColumnDefinition> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let column = self.0;
+
+        write!(f, "{} {}", Name(&column.column_name.value), column.datatype)?;
+
+        // A primary key is never NULL, so saying so would be redundant
+        if !column.nullable && !column.primary_key {
+            f.write_str(" NOT NULL")?;
+        }
+
+        if column.primary_key {
+            f.write_str(" PRIMARY KEY")?;
+        }
+
+        if column.unique {
+            f.write_str(" UNIQUE")?;
+        }
+
+        if let Some(default) = &column.default {
+            write!(f, " DEFAULT {}", Operand(default))?;
+        }
+
+        if let Some(generated) = &column.generated {
+            let storage = match generated.storage {
+                GeneratedStorage::Stored => "STORED",
+                GeneratedStorage::Virtual => "VIRTUAL",
+            };
+
+            write!(
+                f,
+                " GENERATED ALWAYS AS ({}) {storage}",
+                Sql(&generated.expr)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Sql<'_, CreateIndexBody> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let body = self.0;
+
+        f.write_str("CREATE INDEX ")?;
+
+        if let Some(index_name) = &body.index_name {
+            write!(f, "{} ", Name(&index_name.value))?;
+        }
+
+        write!(f, "ON {} (", Name(&body.table_name.value))?;
+        comma_separated(f, body.key_list.iter().map(Sql))?;
+        f.write_str(")")?;
+
+        if !body.include_list.is_empty() {
+            f.write_str(" INCLUDE (")?;
+            comma_separated(f, body.include_list.iter().map(|c| Name(&c.value)))?;
+            f.write_str(")")?;
+        }
+
+        match &body.where_clause {
+            Some(where_clause) => write!(f, " WHERE {}", Sql(&where_clause.expr)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for Sql<'_, IndexKey> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", Sql(&self.0.expr), self.0.dir)
+    }
+}
+
+impl Display for Sql<'_, Expr> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Expr::IsTrue(e) => write!(f, "{} IS TRUE", Operand(e)),
+            Expr::IsNotTrue(e) => write!(f, "{} IS NOT TRUE", Operand(e)),
+            Expr::IsFalse(e) => write!(f, "{} IS FALSE", Operand(e)),
+            Expr::IsNotFalse(e) => write!(f, "{} IS NOT FALSE", Operand(e)),
+            Expr::IsNull(e) => write!(f, "{} IS NULL", Operand(e)),
+            Expr::IsNotNull(e) => write!(f, "{} IS NOT NULL", Operand(e)),
+            Expr::IsIn { expr, list } => {
+                write!(f, "{} IN (", Operand(expr))?;
+                comma_separated(f, list.iter().map(Sql))?;
+                f.write_str(")")
+            }
+            Expr::IsNotIn { expr, list } => {
+                write!(f, "{} NOT IN (", Operand(expr))?;
+                comma_separated(f, list.iter().map(Sql))?;
+                f.write_str(")")
+            }
+            Expr::IsInSubquery { expr, subquery } => {
+                write!(f, "{} IN ({})", Operand(expr), Sql(subquery.as_ref()))
+            }
+            Expr::IsNotInSubquery { expr, subquery } => {
+                write!(f, "{} NOT IN ({})", Operand(expr), Sql(subquery.as_ref()))
+            }
+            Expr::Exists(subquery) => write!(f, "EXISTS ({})", Sql(subquery.as_ref())),
+            Expr::Subquery(subquery) => write!(f, "({})", Sql(subquery.as_ref())),
+            Expr::Between {
+                expr,
+                lower,
+                higher,
+            } => write!(
+                f,
+                "{} BETWEEN {} AND {}",
+                Operand(expr),
+                Operand(lower),
+                Operand(higher)
+            ),
+            Expr::NotBetween {
+                expr,
+                lower,
+                higher,
+            } => write!(
+                f,
+                "{} NOT BETWEEN {} AND {}",
+                Operand(expr),
+                Operand(lower),
+                Operand(higher)
+            ),
+            Expr::Like { expr, pattern } => {
+                write!(f, "{} LIKE {}", Operand(expr), Operand(pattern))
+            }
+            Expr::NotLike { expr, pattern } => {
+                write!(f, "{} NOT LIKE {}", Operand(expr), Operand(pattern))
+            }
+            Expr::BinaryOperator { left, op, right } => {
+                write!(f, "{} {op} {}", Operand(left), Operand(right))
+            }
+            Expr::UnaryOperator { op, expr } => match op {
+                UnaryOperator::Not => write!(f, "NOT {}", Operand(expr)),
+                // The space stops -1 being read as a negative number, and - -1 as a comment
+                UnaryOperator::Minus | UnaryOperator::Plus => write!(f, "{op} {}", Operand(expr)),
+            },
+            Expr::Cast { expr, data_type } => {
+                write!(f, "CAST({} AS {data_type})", Sql(expr.as_ref()))
+            }
+            Expr::Value(value) => write!(f, "{}", Sql(value)),
+            Expr::Identifier(identifier) => write!(f, "{}", Name(&identifier.value)),
+            Expr::QualifiedIdentifier(identifiers) => {
+                for (i, identifier) in identifiers.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(".")?;
+                    }
+
+                    write!(f, "{}", Name(&identifier.value))?;
+                }
+
+                Ok(())
+            }
+            Expr::Function {
+                name,
+                args,
+                distinct,
+            } => {
+                write!(f, "{}(", Name(&name.value))?;
+
+                if *distinct {
+                    f.write_str("DISTINCT ")?;
+                }
+
+                comma_separated(f, args.iter().map(Sql))?;
+                f.write_str(")")
+            }
+            Expr::Parameter(parameter) => write!(f, "{parameter}"),
+            Expr::Wildcard => f.write_str("*"),
+        }
+    }
+}
+
+impl Display for Sql<'_, Value> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            // A bare name in a select list
+            Value::String(name, QuoteType::None) => write!(f, "{}", Name(name)),
+            value => write!(f, "{value}"),
+        }
+    }
+}
+
+impl Display for Operand<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Expr::Value(_)
+            | Expr::Identifier(_)
+            | Expr::QualifiedIdentifier(_)
+            | Expr::Function { .. }
+            | Expr::Parameter(_)
+            | Expr::Wildcard
+            | Expr::Cast { .. }
+            | Expr::Subquery(_)
+            | Expr::Exists(_) => write!(f, "{}", Sql(self.0)),
+            expr => write!(f, "({})", Sql(expr)),
+        }
+    }
+}
+
+impl Display for IfNotExists {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            true => f.write_str("IF NOT EXISTS "),
+            false => Ok(()),
+        }
+    }
+}
+
+impl Display for IfExists {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            true => f.write_str("IF EXISTS "),
+            false => Ok(()),
+        }
+    }
+}
+
+impl Display for Name<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = self.0;
+
+        let mut chars = name.chars();
+        let is_plain = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+        // Non-reserved keywords are quoted too, as not every position accepts them as names
+        let is_keyword = KEYWORDS.contains_key(name.to_lowercase().as_str());
+
+        match (is_plain && !is_keyword, name.contains(']')) {
+            (true, _) => f.write_str(name),
+            (false, false) => write!(f, "[{name}]"),
+            (false, true) => write!(f, "\"{name}\""),
+        }
+    }
+}
+
+#[cfg(test)]
+mod to_sql_tests {
+    use crate::ast::{to_sql, Program};
+    use crate::Parser;
+
+    fn parse(query: &str) -> Program {
+        let query = String::from(query);
+        let program = Parser::new(lexer::Lexer::new(&query).lex().tokens, &query).parse();
+
+        program.unwrap()
+    }
+
+    #[test]
+    fn test_to_sql_round_trips() {
+        let queries = [
+            "SELECT 1 + 2 * 3, (1 + 2) * 3, - 1, -1, NOT (1 = 2) AS [Not Equal]",
+            "SELECT Name, u.Email e, COUNT(DISTINCT Id) FROM Users u INNER JOIN Orders o ON u.Id = o.UserId LEFT JOIN Items ON o.Id = Items.OrderId WHERE Name LIKE 'a%' GROUP BY Name ORDER BY Name DESC",
+            "SELECT x FROM (SELECT [Select], [My Column], \"a]b\" FROM [Table]) d WHERE EXISTS (SELECT 1) ORDER BY x",
+            "SELECT 1 WHERE Id IN (1, 2) IS NOT TRUE; SELECT 1 WHERE Id NOT IN (SELECT Id FROM t)",
+            "SELECT CAST('1' AS VARCHAR(10)), X'BEEF', NULL, TRUE, ?, $2, :name, Data -> 'a' ->> 'b'",
+            "CREATE TABLE t (Id INT PRIMARY KEY, [Key] TEXT NOT NULL UNIQUE DEFAULT 'x', Total INT GENERATED ALWAYS AS (Id + 1) STORED, n INT DEFAULT (1 + 2)) PARTITION BY RANGE (Id)",
+            "CREATE INDEX ix ON t (Id, Total * 2 DESC, Name ASC) INCLUDE ([Key]) WHERE Id > 0; CREATE INDEX ON t (Id)",
+            "SELECT Name FROM Users GROUP BY Age / 10 ORDER BY LENGTH(Name) DESC; SELECT 1 ORDER BY a + b",
+            "SELECT Sales.Users.Name FROM Sales.Users u JOIN [Sales Data].Orders ON u.Id = Orders.UserId",
+            "CREATE TABLE IF NOT EXISTS t (Id INT); DROP TABLE IF EXISTS t; DROP TABLE [If]",
+            "DESCRIBE Users; DESC [Sales Data]",
+            "CREATE DATABASE IF NOT EXISTS Sales; DROP DATABASE IF EXISTS Sales; DROP DATABASE Sales",
+            "CREATE DATABASE [Sales Data]; USE [Sales Data]; SET cache_size = 100; INSERT; UPDATE; DELETE",
+        ];
+
+        for query in queries {
+            let program = parse(query);
+            let sql = to_sql(&program);
+
+            assert_eq!(parse(&sql), program, "{query}\n{sql}");
+        }
+    }
+
+    #[test]
+    fn test_to_sql_output() {
+        assert_eq!(
+            to_sql(&parse(
+                "select a as [b c] from [select] s where (1 + 2) * 3 = - 4"
+            )),
+            "SELECT a AS [b c] FROM [select] s WHERE ((1 + 2) * 3) = (- 4);"
+        );
+        assert_eq!(
+            to_sql(&parse("CREATE TABLE t (a INT NULL DEFAULT - 1); USE db")),
+            "CREATE TABLE t (a INT DEFAULT (- 1));\nUSE db;"
+        );
+        assert_eq!(to_sql(&Program::Empty), "");
+    }
+}
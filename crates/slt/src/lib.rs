@@ -0,0 +1,259 @@
+use engine::engine::{Engine, StatementResult};
+use lexer::Lexer;
+use parser::Parser;
+use thiserror::Error;
+
+/// A single test from a script.
+#[derive(Debug, PartialEq)]
+pub enum Record {
+    Statement {
+        line: usize,
+        sql: String,
+        expect_error: bool,
+    },
+    Query {
+        line: usize,
+        sql: String,
+        column_count: usize,
+        expected: Vec<String>,
+    },
+}
+
+#[derive(Debug, PartialEq, Error)]
+pub enum ScriptError {
+    #[error("Line {0}: unrecognised record '{1}'")]
+    UnrecognisedRecord(usize, String),
+    #[error("Line {0}: record has no SQL")]
+    MissingSql(usize),
+}
+
+#[derive(Debug, PartialEq, Error)]
+pub enum Failure {
+    #[error("Line {0}: expected the statement to succeed, but it failed: {1}")]
+    UnexpectedError(usize, String),
+    #[error("Line {0}: expected the statement to fail, but it succeeded")]
+    UnexpectedSuccess(usize),
+    #[error("Line {0}: expected {1} columns, got {2}")]
+    ColumnCountMismatch(usize, usize, usize),
+    #[error("Line {0}: results don't match.\nExpected:\n{1:#?}\nActual:\n{2:#?}")]
+    ResultMismatch(usize, Vec<String>, Vec<String>),
+}
+
+/// Parse a sqllogictest script into its records.
+pub fn parse_script(script: &str) -> Result<Vec<Record>, ScriptError> {
+    let mut records = vec![];
+    let mut lines = script
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l))
+        .peekable();
+
+    while let Some((line, text)) = lines.next() {
+        let text = text.trim();
+
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+
+        let mut sql_lines = vec![];
+        while let Some((_, l)) = lines.next_if(|(_, l)| !l.trim().is_empty() && l.trim() != "----")
+        {
+            sql_lines.push(l);
+        }
+
+        if sql_lines.is_empty() {
+            return Err(ScriptError::MissingSql(line));
+        }
+
+        let sql = sql_lines.join("\n");
+        let words: Vec<&str> = text.split_whitespace().collect();
+
+        let record = match words.as_slice() {
+            ["statement", "ok"] => Record::Statement {
+                line,
+                sql,
+                expect_error: false,
+            },
+            ["statement", "error"] => Record::Statement {
+                line,
+                sql,
+                expect_error: true,
+            },
+            ["query", types] => {
+                let mut expected = vec![];
+
+                if lines.next_if(|(_, l)| l.trim() == "----").is_some() {
+                    while let Some((_, l)) = lines.next_if(|(_, l)| !l.trim().is_empty()) {
+                        expected.push(l.trim().to_string());
+                    }
+                }
+
+                Record::Query {
+                    line,
+                    sql,
+                    column_count: types.len(),
+                    expected,
+                }
+            }
+            _ => return Err(ScriptError::UnrecognisedRecord(line, text.to_string())),
+        };
+
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+pub struct Runner {
+    engine: Engine,
+}
+
+impl Default for Runner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Runner {
+    /// Create a runner with a fresh engine.
+    /// The engine isn't initialised, so nothing is read from or written to the data directory.
+    pub fn new() -> Self {
+        Runner {
+            engine: Engine::new(),
+        }
+    }
+
+    /// Run every record in a script, collecting all failures.
+    pub fn run_script(&self, script: &str) -> Result<Vec<Failure>, ScriptError> {
+        let records = parse_script(script)?;
+
+        Ok(records
+            .iter()
+            .filter_map(|record| self.run(record).err())
+            .collect())
+    }
+
+    pub fn run(&self, record: &Record) -> Result<(), Failure> {
+        match record {
+            Record::Statement {
+                line,
+                sql,
+                expect_error,
+            } => match (self.execute(sql), expect_error) {
+                (Ok(_), false) | (Err(_), true) => Ok(()),
+                (Ok(_), true) => Err(Failure::UnexpectedSuccess(*line)),
+                (Err(err), false) => Err(Failure::UnexpectedError(*line, err)),
+            },
+            Record::Query {
+                line,
+                sql,
+                column_count,
+                expected,
+            } => {
+                let results = self
+                    .execute(sql)
+                    .map_err(|err| Failure::UnexpectedError(*line, err))?;
+
+                let mut actual = vec![];
+
+                for result in results {
+                    let columns = result.result_set.columns;
+
+                    if columns.len() != *column_count {
+                        return Err(Failure::ColumnCountMismatch(
+                            *line,
+                            *column_count,
+                            columns.len(),
+                        ));
+                    }
+
+                    let row: Vec<String> = columns.iter().map(|c| c.value.to_string()).collect();
+                    actual.push(row.join(" "));
+                }
+
+                match actual == *expected {
+                    true => Ok(()),
+                    false => Err(Failure::ResultMismatch(*line, expected.clone(), actual)),
+                }
+            }
+        }
+    }
+
+    /// Lex, parse and execute the SQL, returning any error as a message.
+    fn execute(&self, sql: &str) -> Result<Vec<StatementResult>, String> {
+        let sql = sql.to_string();
+        let lex_result = Lexer::new(&sql).lex();
+
+        if let Some(err) = lex_result.errors.first() {
+            return Err(format!("{err:?}"));
+        }
+
+        let program = Parser::new(lex_result.tokens, &sql)
+            .parse()
+            .map_err(|errors| format!("{errors:?}"))?;
+
+        let result = self
+            .engine
+            .execute(&program)
+            .map_err(|err| format!("{err:?}"))?;
+
+        result
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .map_err(|err| format!("{:?}", err.error))
+    }
+}
+
+#[cfg(test)]
+mod slt_tests {
+    use crate::{parse_script, Failure, Record, Runner, ScriptError};
+
+    #[test]
+    fn test_parse_script() {
+        let script = "# A comment\nstatement ok\nCREATE TABLE t (Id INT)\n\nquery IT\nSELECT 1,\n'a'\n----\n1 a\n";
+
+        let expected = vec![
+            Record::Statement {
+                line: 2,
+                sql: String::from("CREATE TABLE t (Id INT)"),
+                expect_error: false,
+            },
+            Record::Query {
+                line: 5,
+                sql: String::from("SELECT 1,\n'a'"),
+                column_count: 2,
+                expected: vec![String::from("1 a")],
+            },
+        ];
+
+        assert_eq!(parse_script(script), Ok(expected));
+    }
+
+    #[test]
+    fn test_parse_script_unrecognised_record() {
+        let script = "statement maybe\nSELECT 1\n";
+
+        assert_eq!(
+            parse_script(script),
+            Err(ScriptError::UnrecognisedRecord(
+                1,
+                String::from("statement maybe")
+            ))
+        );
+    }
+
+    #[test]
+    fn test_run_script_reports_failures() {
+        let script = "statement error\nSELECT 1\n\nquery I\nSELECT 2\n----\n3\n";
+
+        let failures = Runner::new().run_script(script).unwrap();
+
+        assert_eq!(
+            failures,
+            vec![
+                Failure::UnexpectedSuccess(1),
+                Failure::ResultMismatch(4, vec![String::from("3")], vec![String::from("2")]),
+            ]
+        );
+    }
+}
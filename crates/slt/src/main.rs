@@ -0,0 +1,44 @@
+use std::{env::args, process::exit};
+
+use slt::Runner;
+
+fn main() {
+    let files: Vec<String> = args().skip(1).collect();
+
+    if files.is_empty() {
+        eprintln!("Usage: slt <script.slt>...");
+        exit(2);
+    }
+
+    let mut failed = false;
+
+    for file in files {
+        let script = match std::fs::read_to_string(&file) {
+            Ok(script) => script,
+            Err(err) => {
+                eprintln!("{file}: {err}");
+                failed = true;
+                continue;
+            }
+        };
+
+        match Runner::new().run_script(&script) {
+            Ok(failures) if failures.is_empty() => println!("{file}: ok"),
+            Ok(failures) => {
+                failed = true;
+
+                for failure in failures {
+                    println!("{file}: {failure}");
+                }
+            }
+            Err(err) => {
+                failed = true;
+                println!("{file}: {err}");
+            }
+        }
+    }
+
+    if failed {
+        exit(1);
+    }
+}
@@ -0,0 +1,36 @@
+use std::path::Path;
+
+use slt::Runner;
+
+/// Run every script in tests/slt, each against a fresh engine.
+#[test]
+fn test_conformance_suite() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/slt");
+
+    let mut scripts: Vec<_> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "slt"))
+        .collect();
+    scripts.sort();
+
+    assert!(!scripts.is_empty(), "No scripts found");
+
+    let failures: Vec<String> = scripts
+        .iter()
+        .flat_map(|path| {
+            let script = std::fs::read_to_string(path).unwrap();
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+
+            match Runner::new().run_script(&script) {
+                Ok(failures) => failures
+                    .iter()
+                    .map(|failure| format!("{name}: {failure}"))
+                    .collect(),
+                Err(err) => vec![format!("{name}: {err}")],
+            }
+        })
+        .collect();
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}